@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::io;
+use std::path::PathBuf;
 
 use chrono::NaiveDate;
 
@@ -93,6 +94,12 @@ impl Action for DeleteEntries {
             format!("Restored {}", pluralize(count)),
         )
     }
+
+    fn touches_current_day(&self, current_date: NaiveDate) -> bool {
+        self.targets
+            .iter()
+            .any(|target| target.is_on_current_day(current_date))
+    }
 }
 
 pub struct RestoreEntries {
@@ -120,7 +127,7 @@ impl Action for RestoreEntries {
 
                     for (i, (_date, line_idx, entry)) in current_day_entries.iter().enumerate() {
                         let insert_idx = (line_idx + i).min(app.lines.len());
-                        if matches!(entry.entry_type, EntryType::Task { completed: true }) {
+                        if entry.entry_type.is_closed_task() {
                             any_completed = true;
                         }
 
@@ -167,61 +174,63 @@ impl Action for RestoreEntries {
                     }
 
                     for (date, date_entries) in entries_by_date {
-                        if let Ok(mut lines) = storage::load_day_lines(date, &path) {
-                            for (i, (line_idx, entry)) in date_entries.into_iter().enumerate() {
-                                let insert_idx = (line_idx + i).min(lines.len());
-                                lines.insert(insert_idx, Line::Entry(entry.to_raw()));
-
-                                delete_targets.push(DeleteTarget::Projected(entry));
-                            }
-                            let _ = storage::save_day_lines(date, &path, &lines);
-                        }
+                        let _ =
+                            storage::modify_day_lines(app.store.as_ref(), date, &path, |lines| {
+                                for (i, (line_idx, entry)) in date_entries.into_iter().enumerate() {
+                                    let insert_idx = (line_idx + i).min(lines.len());
+                                    lines.insert(insert_idx, Line::Entry(entry.to_raw()));
+
+                                    delete_targets.push(DeleteTarget::Projected(entry));
+                                }
+                            });
                     }
 
                     app.refresh_projected_entries();
                 }
             }
             ViewMode::Filter(_) => {
-                let path = app.active_path().to_path_buf();
-
-                let mut entries_by_date: HashMap<NaiveDate, Vec<(usize, Entry)>> = HashMap::new();
+                // Cross-journal results restore into the journal they came from
+                let mut entries_by_day: HashMap<(PathBuf, NaiveDate), Vec<(usize, Entry)>> =
+                    HashMap::new();
                 for (date, line_idx, entry) in &self.entries {
-                    entries_by_date
-                        .entry(*date)
+                    entries_by_day
+                        .entry((app.entry_path(entry).to_path_buf(), *date))
                         .or_default()
                         .push((*line_idx, entry.clone()));
                 }
 
-                for (date, date_entries) in entries_by_date {
-                    if let Ok(mut lines) = storage::load_day_lines(date, &path) {
-                        for (i, (line_idx, entry)) in date_entries.into_iter().enumerate() {
-                            let insert_idx = (line_idx + i).min(lines.len());
-
-                            let restored_entry = Entry {
-                                entry_type: entry.entry_type.clone(),
-                                content: entry.content.clone(),
-                                source_date: date,
-                                line_index: insert_idx,
-                                source_type: entry.source_type.clone(),
-                            };
-                            lines.insert(insert_idx, Line::Entry(entry.to_raw()));
-
-                            if let ViewMode::Filter(state) = &mut app.view {
-                                let filter_index = state.entries.len();
-                                state.entries.push(restored_entry.clone());
-                                state.selected = filter_index;
-
-                                delete_targets.push(DeleteTarget::Filter {
-                                    index: filter_index,
-                                    entry: restored_entry,
-                                });
+                for ((path, date), date_entries) in entries_by_day {
+                    let restored =
+                        storage::modify_day_lines(app.store.as_ref(), date, &path, |lines| {
+                            for (i, (line_idx, entry)) in date_entries.into_iter().enumerate() {
+                                let insert_idx = (line_idx + i).min(lines.len());
+
+                                let restored_entry = Entry {
+                                    entry_type: entry.entry_type.clone(),
+                                    content: entry.content.clone(),
+                                    source_date: date,
+                                    line_index: insert_idx,
+                                    source_type: entry.source_type.clone(),
+                                    depth: entry.depth,
+                                    journal: entry.journal.clone(),
+                                };
+                                lines.insert(insert_idx, Line::Entry(entry.to_raw()));
+
+                                if let ViewMode::Filter(state) = &mut app.view {
+                                    let filter_index = state.entries.len();
+                                    state.entries.push(restored_entry.clone());
+                                    state.selected = filter_index;
+
+                                    delete_targets.push(DeleteTarget::Filter {
+                                        index: filter_index,
+                                        entry: restored_entry,
+                                    });
+                                }
                             }
-                        }
-                        let _ = storage::save_day_lines(date, &path, &lines);
+                        });
 
-                        if date == app.current_date {
-                            let _ = app.reload_current_day();
-                        }
+                    if restored.is_ok() && date == app.current_date && path == app.active_path() {
+                        let _ = app.reload_current_day();
                     }
                 }
             }
@@ -239,6 +248,12 @@ impl Action for RestoreEntries {
             format!("Deleted {}", pluralize(count)),
         )
     }
+
+    fn touches_current_day(&self, current_date: NaiveDate) -> bool {
+        self.entries
+            .iter()
+            .any(|(date, _, _)| *date == current_date)
+    }
 }
 
 /// Execute a single delete without modifying undo state
@@ -250,7 +265,12 @@ fn execute_delete_raw(
 
     match target {
         DeleteTarget::Projected(entry) => {
-            storage::delete_entry(entry.source_date, &path, entry.line_index)?;
+            storage::delete_entry(
+                app.store.as_ref(),
+                entry.source_date,
+                &path,
+                entry.line_index,
+            )?;
 
             app.refresh_projected_entries();
             clamp_daily_selection(app);
@@ -268,7 +288,12 @@ fn execute_delete_raw(
             Ok(result)
         }
         DeleteTarget::Filter { entry, .. } => {
-            storage::delete_entry(entry.source_date, &path, entry.line_index)?;
+            storage::delete_entry(
+                app.store.as_ref(),
+                entry.source_date,
+                app.entry_path(entry),
+                entry.line_index,
+            )?;
 
             // Adjust line_index for remaining entries from the same date.
             // state.entries removal happens in DeleteEntries::execute to handle
@@ -276,6 +301,7 @@ fn execute_delete_raw(
             if let ViewMode::Filter(state) = &mut app.view {
                 for filter_entry in &mut state.entries {
                     if filter_entry.source_date == entry.source_date
+                        && filter_entry.journal == entry.journal
                         && filter_entry.line_index > entry.line_index
                     {
                         filter_entry.line_index -= 1;
@@ -283,7 +309,7 @@ fn execute_delete_raw(
                 }
             }
 
-            if entry.source_date == app.current_date {
+            if app.is_on_current_day(entry) {
                 app.reload_current_day()?;
             }
 
@@ -335,6 +361,10 @@ impl Action for CreateEntry {
             visibility: StatusVisibility::OnUndo,
         }
     }
+
+    fn touches_current_day(&self, current_date: NaiveDate) -> bool {
+        self.target.date == current_date
+    }
 }
 
 struct UncreateEntry {
@@ -352,7 +382,12 @@ impl Action for UncreateEntry {
         let path = app.active_path().to_path_buf();
 
         // Delete the entry
-        storage::delete_entry(self.target.date, &path, self.target.line_index)?;
+        storage::delete_entry(
+            app.store.as_ref(),
+            self.target.date,
+            &path,
+            self.target.line_index,
+        )?;
 
         // Update app state
         if self.target.date == app.current_date {
@@ -390,6 +425,10 @@ impl Action for UncreateEntry {
             visibility: StatusVisibility::OnUndo,
         }
     }
+
+    fn touches_current_day(&self, current_date: NaiveDate) -> bool {
+        self.target.date == current_date
+    }
 }
 
 struct RecreateEntry {
@@ -407,13 +446,12 @@ impl Action for RecreateEntry {
         let path = app.active_path().to_path_buf();
 
         // Recreate the entry at the original position
-        let mut lines = storage::load_day_lines(self.target.date, &path)?;
-
         // Insert at original position (or end if position is beyond current length)
-        let insert_pos = self.target.line_index.min(lines.len());
-        lines.insert(insert_pos, Line::Entry(self.target.entry.to_raw()));
-
-        storage::save_day_lines(self.target.date, &path, &lines)?;
+        let raw = self.target.entry.to_raw();
+        storage::modify_day_lines(app.store.as_ref(), self.target.date, &path, |lines| {
+            let insert_pos = self.target.line_index.min(lines.len());
+            lines.insert(insert_pos, Line::Entry(raw));
+        })?;
 
         // Update app state
         if self.target.date == app.current_date {
@@ -436,6 +474,10 @@ impl Action for RecreateEntry {
             visibility: StatusVisibility::OnUndo,
         }
     }
+
+    fn touches_current_day(&self, current_date: NaiveDate) -> bool {
+        self.target.date == current_date
+    }
 }
 
 #[derive(Clone)]
@@ -471,6 +513,10 @@ impl Action for EditEntry {
             visibility: StatusVisibility::OnUndo,
         }
     }
+
+    fn touches_current_day(&self, current_date: NaiveDate) -> bool {
+        self.target.location.is_on_current_day(current_date)
+    }
 }
 
 struct RestoreEdit {
@@ -499,6 +545,10 @@ impl Action for RestoreEdit {
             visibility: StatusVisibility::OnUndo,
         }
     }
+
+    fn touches_current_day(&self, current_date: NaiveDate) -> bool {
+        self.target.location.is_on_current_day(current_date)
+    }
 }
 
 struct RedoEdit {
@@ -532,6 +582,10 @@ impl Action for RedoEdit {
             visibility: StatusVisibility::OnUndo,
         }
     }
+
+    fn touches_current_day(&self, current_date: NaiveDate) -> bool {
+        self.target.location.is_on_current_day(current_date)
+    }
 }
 
 fn set_entry_content_raw(app: &mut App, target: &EditTarget) -> io::Result<()> {
@@ -539,9 +593,15 @@ fn set_entry_content_raw(app: &mut App, target: &EditTarget) -> io::Result<()> {
 
     match &target.location {
         EntryLocation::Projected(entry) => {
-            storage::mutate_entry(entry.source_date, &path, entry.line_index, |raw_entry| {
-                raw_entry.content = target.original_content.clone();
-            })?;
+            storage::mutate_entry(
+                app.store.as_ref(),
+                entry.source_date,
+                &path,
+                entry.line_index,
+                |raw_entry| {
+                    raw_entry.content = target.original_content.clone();
+                },
+            )?;
 
             app.refresh_projected_entries();
         }
@@ -552,9 +612,15 @@ fn set_entry_content_raw(app: &mut App, target: &EditTarget) -> io::Result<()> {
             }
         }
         EntryLocation::Filter { index, entry } => {
-            storage::mutate_entry(entry.source_date, &path, entry.line_index, |raw_entry| {
-                raw_entry.content = target.original_content.clone();
-            })?;
+            storage::mutate_entry(
+                app.store.as_ref(),
+                entry.source_date,
+                app.entry_path(entry),
+                entry.line_index,
+                |raw_entry| {
+                    raw_entry.content = target.original_content.clone();
+                },
+            )?;
 
             if let ViewMode::Filter(state) = &mut app.view
                 && let Some(filter_entry) = state.entries.get_mut(*index)
@@ -562,7 +628,7 @@ fn set_entry_content_raw(app: &mut App, target: &EditTarget) -> io::Result<()> {
                 filter_entry.content = target.original_content.clone();
             }
 
-            if entry.source_date == app.current_date {
+            if app.is_on_current_day(entry) {
                 app.reload_current_day()?;
             }
         }
@@ -576,14 +642,35 @@ pub struct CycleTarget {
     pub original_type: EntryType,
 }
 
+/// What a cycle action steps through.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum CycleKind {
+    /// Task -> Note -> Event
+    EntryType,
+    /// Open -> In progress -> Done -> Cancelled -> Migrated (tasks only)
+    TaskState,
+}
+
 pub struct CycleEntryType {
     targets: Vec<CycleTarget>,
+    kind: CycleKind,
 }
 
 impl CycleEntryType {
     #[must_use]
     pub fn new(targets: Vec<CycleTarget>) -> Self {
-        Self { targets }
+        Self {
+            targets,
+            kind: CycleKind::EntryType,
+        }
+    }
+
+    #[must_use]
+    pub fn task_states(targets: Vec<CycleTarget>) -> Self {
+        Self {
+            targets,
+            kind: CycleKind::TaskState,
+        }
     }
 
     #[must_use]
@@ -598,15 +685,35 @@ impl CycleEntryType {
 impl Action for CycleEntryType {
     fn execute(&mut self, app: &mut App) -> io::Result<Box<dyn Action>> {
         for target in &self.targets {
-            execute_cycle_raw(app, &target.location)?;
+            match (self.kind, &target.original_type) {
+                (CycleKind::EntryType, _) => {
+                    execute_cycle_raw(app, &target.location)?;
+                }
+                (CycleKind::TaskState, EntryType::Task { state }) => {
+                    let next = EntryType::Task {
+                        state: state.next(),
+                    };
+                    set_entry_type_raw(app, &target.location, &next)?;
+                }
+                (CycleKind::TaskState, _) => {}
+            }
         }
 
-        Ok(Box::new(RestoreEntryType::new(self.targets.clone())))
+        Ok(Box::new(RestoreEntryType::new(
+            self.targets.clone(),
+            self.kind,
+        )))
     }
 
     fn description(&self) -> ActionDescription {
         let count = self.targets.len();
-        if count == 1 {
+        if self.kind == CycleKind::TaskState {
+            ActionDescription {
+                past: format!("Cycled task state on {} {}", count, pluralize(count)),
+                past_reversed: format!("Restored task state on {} {}", count, pluralize(count)),
+                visibility: StatusVisibility::Silent,
+            }
+        } else if count == 1 {
             ActionDescription {
                 past: "Cycled entry type".to_string(),
                 past_reversed: "Restored entry type".to_string(),
@@ -620,15 +727,25 @@ impl Action for CycleEntryType {
             }
         }
     }
+
+    fn touches_current_day(&self, current_date: NaiveDate) -> bool {
+        self.targets
+            .iter()
+            .any(|target| target.location.is_on_current_day(current_date))
+    }
 }
 
 struct RestoreEntryType {
     original_targets: Vec<CycleTarget>,
+    kind: CycleKind,
 }
 
 impl RestoreEntryType {
-    fn new(original_targets: Vec<CycleTarget>) -> Self {
-        Self { original_targets }
+    fn new(original_targets: Vec<CycleTarget>, kind: CycleKind) -> Self {
+        Self {
+            original_targets,
+            kind,
+        }
     }
 }
 
@@ -640,7 +757,10 @@ impl Action for RestoreEntryType {
         }
 
         // Return an action that will re-cycle (redo)
-        Ok(Box::new(CycleEntryType::new(self.original_targets.clone())))
+        Ok(Box::new(CycleEntryType {
+            targets: self.original_targets.clone(),
+            kind: self.kind,
+        }))
     }
 
     fn description(&self) -> ActionDescription {
@@ -659,6 +779,12 @@ impl Action for RestoreEntryType {
             }
         }
     }
+
+    fn touches_current_day(&self, current_date: NaiveDate) -> bool {
+        self.original_targets
+            .iter()
+            .any(|target| target.location.is_on_current_day(current_date))
+    }
 }
 
 fn execute_cycle_raw(app: &mut App, location: &EntryLocation) -> io::Result<Option<EntryType>> {
@@ -666,7 +792,12 @@ fn execute_cycle_raw(app: &mut App, location: &EntryLocation) -> io::Result<Opti
 
     match location {
         EntryLocation::Projected(entry) => {
-            let new_type = storage::cycle_entry_type(entry.source_date, &path, entry.line_index)?;
+            let new_type = storage::cycle_entry_type(
+                app.store.as_ref(),
+                entry.source_date,
+                &path,
+                entry.line_index,
+            )?;
             if let Some(ref new_type) = new_type
                 && let ViewMode::Daily(state) = &mut app.view
                 && let Some(projected_entry) = state.projected_entries.iter_mut().find(|e| {
@@ -688,7 +819,12 @@ fn execute_cycle_raw(app: &mut App, location: &EntryLocation) -> io::Result<Opti
             }
         }
         EntryLocation::Filter { index, entry } => {
-            let new_type = storage::cycle_entry_type(entry.source_date, &path, entry.line_index)?;
+            let new_type = storage::cycle_entry_type(
+                app.store.as_ref(),
+                entry.source_date,
+                app.entry_path(entry),
+                entry.line_index,
+            )?;
             if let Some(ref new_type) = new_type {
                 if let ViewMode::Filter(state) = &mut app.view
                     && let Some(filter_entry) = state.entries.get_mut(*index)
@@ -696,7 +832,7 @@ fn execute_cycle_raw(app: &mut App, location: &EntryLocation) -> io::Result<Opti
                     filter_entry.entry_type = new_type.clone();
                 }
 
-                if entry.source_date == app.current_date {
+                if app.is_on_current_day(entry) {
                     app.reload_current_day()?;
                 }
             }
@@ -714,9 +850,15 @@ fn set_entry_type_raw(
 
     match location {
         EntryLocation::Projected(entry) => {
-            storage::mutate_entry(entry.source_date, &path, entry.line_index, |raw_entry| {
-                raw_entry.entry_type = entry_type.clone();
-            })?;
+            storage::mutate_entry(
+                app.store.as_ref(),
+                entry.source_date,
+                &path,
+                entry.line_index,
+                |raw_entry| {
+                    raw_entry.entry_type = entry_type.clone();
+                },
+            )?;
             if let ViewMode::Daily(state) = &mut app.view
                 && let Some(projected_entry) = state.projected_entries.iter_mut().find(|e| {
                     e.source_date == entry.source_date && e.line_index == entry.line_index
@@ -732,16 +874,22 @@ fn set_entry_type_raw(
             }
         }
         EntryLocation::Filter { index, entry } => {
-            storage::mutate_entry(entry.source_date, &path, entry.line_index, |raw_entry| {
-                raw_entry.entry_type = entry_type.clone();
-            })?;
+            storage::mutate_entry(
+                app.store.as_ref(),
+                entry.source_date,
+                app.entry_path(entry),
+                entry.line_index,
+                |raw_entry| {
+                    raw_entry.entry_type = entry_type.clone();
+                },
+            )?;
             if let ViewMode::Filter(state) = &mut app.view
                 && let Some(filter_entry) = state.entries.get_mut(*index)
             {
                 filter_entry.entry_type = entry_type.clone();
             }
 
-            if entry.source_date == app.current_date {
+            if app.is_on_current_day(entry) {
                 app.reload_current_day()?;
             }
         }
@@ -782,6 +930,10 @@ impl Action for PasteEntries {
             visibility: StatusVisibility::Always,
         }
     }
+
+    fn touches_current_day(&self, current_date: NaiveDate) -> bool {
+        self.target.date == current_date
+    }
 }
 
 struct UnpasteEntries {
@@ -801,7 +953,7 @@ impl Action for UnpasteEntries {
         // Delete entries in reverse order to maintain indices
         for i in (0..self.target.entries.len()).rev() {
             let line_index = self.target.start_line_index + i;
-            storage::delete_entry(self.target.date, &path, line_index)?;
+            storage::delete_entry(app.store.as_ref(), self.target.date, &path, line_index)?;
         }
 
         if self.target.date == app.current_date {
@@ -824,6 +976,10 @@ impl Action for UnpasteEntries {
             visibility: StatusVisibility::Always,
         }
     }
+
+    fn touches_current_day(&self, current_date: NaiveDate) -> bool {
+        self.target.date == current_date
+    }
 }
 
 struct RepasteEntries {
@@ -840,14 +996,12 @@ impl Action for RepasteEntries {
     fn execute(&mut self, app: &mut App) -> io::Result<Box<dyn Action>> {
         let path = app.active_path().to_path_buf();
 
-        let mut lines = storage::load_day_lines(self.target.date, &path)?;
-
-        for (i, entry) in self.target.entries.iter().enumerate() {
-            let insert_pos = (self.target.start_line_index + i).min(lines.len());
-            lines.insert(insert_pos, Line::Entry(entry.to_raw()));
-        }
-
-        storage::save_day_lines(self.target.date, &path, &lines)?;
+        storage::modify_day_lines(app.store.as_ref(), self.target.date, &path, |lines| {
+            for (i, entry) in self.target.entries.iter().enumerate() {
+                let insert_pos = (self.target.start_line_index + i).min(lines.len());
+                lines.insert(insert_pos, Line::Entry(entry.to_raw()));
+            }
+        })?;
 
         if self.target.date == app.current_date {
             app.reload_current_day()?;
@@ -868,4 +1022,8 @@ impl Action for RepasteEntries {
             visibility: StatusVisibility::Always,
         }
     }
+
+    fn touches_current_day(&self, current_date: NaiveDate) -> bool {
+        self.target.date == current_date
+    }
 }
@@ -1,4 +1,6 @@
 mod entry;
+mod occurrence;
+mod outline;
 mod tag;
 mod types;
 
@@ -6,5 +8,7 @@ pub use entry::{
     CreateEntry, CreateTarget, CycleEntryType, CycleTarget, DeleteEntries, EditEntry, EditTarget,
     PasteEntries, PasteTarget, RestoreEntries,
 };
+pub use occurrence::SetOccurrence;
+pub use outline::{DepthTarget, SetDepths};
 pub use tag::{AppendTag, RemoveAllTags, RemoveLastTag, TagTarget};
 pub use types::{Action, ActionDescription, ActionExecutor, ContentTarget, StatusVisibility};
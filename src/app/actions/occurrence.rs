@@ -0,0 +1,68 @@
+use std::io;
+
+use chrono::NaiveDate;
+
+use crate::app::{App, EntryLocation};
+use crate::storage::{move_occurrence, skip_occurrence};
+
+use super::types::{Action, ActionDescription, get_entry_content, set_entry_content};
+
+/// Rewrites the skip/moved metadata of a recurring entry. The whole source
+/// content is stored rather than the change, so undo restores it exactly.
+pub struct SetOccurrence {
+    location: EntryLocation,
+    content: String,
+    past: String,
+    past_reversed: String,
+}
+
+impl SetOccurrence {
+    /// Skips the occurrence shown on `date`.
+    #[must_use]
+    pub fn skip(location: EntryLocation, current_content: &str, date: NaiveDate) -> Self {
+        Self {
+            location,
+            content: skip_occurrence(current_content, date),
+            past: format!("Skipped {}", date.format("%m/%d")),
+            past_reversed: "Restored occurrence".to_string(),
+        }
+    }
+
+    /// Moves the occurrence shown on `date` to `target`.
+    #[must_use]
+    pub fn reschedule(
+        location: EntryLocation,
+        current_content: &str,
+        date: NaiveDate,
+        target: NaiveDate,
+    ) -> Self {
+        Self {
+            location,
+            content: move_occurrence(current_content, date, target),
+            past: format!("Moved occurrence to {}", target.format("%m/%d")),
+            past_reversed: format!("Moved occurrence back to {}", date.format("%m/%d")),
+        }
+    }
+}
+
+impl Action for SetOccurrence {
+    fn execute(&mut self, app: &mut App) -> io::Result<Box<dyn Action>> {
+        let previous = get_entry_content(app, &self.location)?;
+        set_entry_content(app, &self.location, &self.content)?;
+
+        Ok(Box::new(Self {
+            location: self.location.clone(),
+            content: previous,
+            past: self.past_reversed.clone(),
+            past_reversed: self.past.clone(),
+        }))
+    }
+
+    fn description(&self) -> ActionDescription {
+        ActionDescription::always(self.past.clone(), self.past_reversed.clone())
+    }
+
+    fn touches_current_day(&self, current_date: NaiveDate) -> bool {
+        self.location.is_on_current_day(current_date)
+    }
+}
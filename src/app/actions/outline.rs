@@ -0,0 +1,79 @@
+use std::io;
+
+use chrono::NaiveDate;
+
+use crate::app::App;
+use crate::storage::Line;
+
+use super::types::{Action, ActionDescription, StatusVisibility};
+
+/// New nesting depth for one line of the current day.
+#[derive(Clone)]
+pub struct DepthTarget {
+    pub line_idx: usize,
+    pub depth: usize,
+}
+
+/// Sets the depth of an entry and its sub-entries. Depths are stored
+/// explicitly rather than as an offset, since outdenting can adopt following
+/// siblings that a plain re-indent would not give back.
+pub struct SetDepths {
+    targets: Vec<DepthTarget>,
+    past: &'static str,
+    past_reversed: &'static str,
+}
+
+impl SetDepths {
+    #[must_use]
+    pub fn indent(targets: Vec<DepthTarget>) -> Self {
+        Self {
+            targets,
+            past: "Indented entry",
+            past_reversed: "Restored indentation",
+        }
+    }
+
+    #[must_use]
+    pub fn outdent(targets: Vec<DepthTarget>) -> Self {
+        Self {
+            targets,
+            past: "Outdented entry",
+            past_reversed: "Restored indentation",
+        }
+    }
+}
+
+impl Action for SetDepths {
+    fn execute(&mut self, app: &mut App) -> io::Result<Box<dyn Action>> {
+        let mut previous = Vec::with_capacity(self.targets.len());
+        for target in &self.targets {
+            if let Some(Line::Entry(raw_entry)) = app.lines.get_mut(target.line_idx) {
+                previous.push(DepthTarget {
+                    line_idx: target.line_idx,
+                    depth: raw_entry.depth,
+                });
+                raw_entry.depth = target.depth;
+            }
+        }
+        app.save();
+
+        Ok(Box::new(Self {
+            targets: previous,
+            past: self.past_reversed,
+            past_reversed: self.past,
+        }))
+    }
+
+    fn description(&self) -> ActionDescription {
+        ActionDescription {
+            past: self.past.to_string(),
+            past_reversed: self.past_reversed.to_string(),
+            visibility: StatusVisibility::Silent,
+        }
+    }
+
+    fn touches_current_day(&self, _current_date: NaiveDate) -> bool {
+        // Depths are set on the day on screen
+        true
+    }
+}
@@ -1,5 +1,7 @@
 use std::io;
 
+use chrono::NaiveDate;
+
 use crate::app::{App, EntryLocation};
 use crate::ui::{remove_all_trailing_tags, remove_last_trailing_tag};
 
@@ -79,6 +81,12 @@ impl Action for TagRemovalAction {
             visibility: StatusVisibility::Always,
         }
     }
+
+    fn touches_current_day(&self, current_date: NaiveDate) -> bool {
+        self.targets
+            .iter()
+            .any(|target| target.location.is_on_current_day(current_date))
+    }
 }
 
 pub struct RemoveLastTag(TagRemovalAction);
@@ -107,6 +115,10 @@ impl Action for RemoveLastTag {
     fn description(&self) -> ActionDescription {
         self.0.description()
     }
+
+    fn touches_current_day(&self, current_date: NaiveDate) -> bool {
+        self.0.touches_current_day(current_date)
+    }
 }
 
 pub struct RemoveAllTags(TagRemovalAction);
@@ -135,6 +147,10 @@ impl Action for RemoveAllTags {
     fn description(&self) -> ActionDescription {
         self.0.description()
     }
+
+    fn touches_current_day(&self, current_date: NaiveDate) -> bool {
+        self.0.touches_current_day(current_date)
+    }
 }
 
 pub struct AppendTag {
@@ -192,6 +208,12 @@ impl Action for AppendTag {
             visibility: StatusVisibility::Always,
         }
     }
+
+    fn touches_current_day(&self, current_date: NaiveDate) -> bool {
+        self.targets
+            .iter()
+            .any(|target| target.location.is_on_current_day(current_date))
+    }
 }
 
 #[derive(Clone)]
@@ -261,4 +283,10 @@ impl Action for RestoreContent {
             visibility: StatusVisibility::Always,
         }
     }
+
+    fn touches_current_day(&self, current_date: NaiveDate) -> bool {
+        self.targets
+            .iter()
+            .any(|target| target.location.is_on_current_day(current_date))
+    }
 }
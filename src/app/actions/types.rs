@@ -1,5 +1,7 @@
 use std::io;
 
+use chrono::NaiveDate;
+
 use super::super::App;
 use crate::app::EntryLocation;
 
@@ -49,6 +51,9 @@ impl ActionDescription {
 pub trait Action: Send {
     fn execute(&mut self, app: &mut App) -> io::Result<Box<dyn Action>>;
     fn description(&self) -> ActionDescription;
+    /// Whether the action addresses lines of the day on screen, whose indices
+    /// a merge with on-disk changes can shift.
+    fn touches_current_day(&self, current_date: NaiveDate) -> bool;
 }
 
 const MAX_UNDO_DEPTH: usize = 50;
@@ -137,6 +142,15 @@ impl ActionExecutor {
         self.redo_stack.clear();
     }
 
+    /// Drops the actions on the day on screen after a merge, keeping those on
+    /// other days.
+    pub fn forget_current_day(&mut self, current_date: NaiveDate) {
+        self.undo_stack
+            .retain(|(action, _)| !action.touches_current_day(current_date));
+        self.redo_stack
+            .retain(|(action, _)| !action.touches_current_day(current_date));
+    }
+
     pub fn clear(&mut self) {
         self.undo_stack.clear();
         self.redo_stack.clear();
@@ -51,6 +51,10 @@ impl App {
         self.toggle_sidebar(SidebarType::Agenda);
     }
 
+    pub fn toggle_habits(&mut self) {
+        self.toggle_sidebar(SidebarType::Habits);
+    }
+
     pub fn sync_calendar_state(&mut self, date: NaiveDate) {
         let display_month = first_of_month(date.year(), date.month());
         let month_changed = self.calendar_state.display_month != display_month;
@@ -116,8 +120,10 @@ impl App {
 
     fn load_month_cache(&self, month: NaiveDate) -> HashMap<NaiveDate, DayInfo> {
         let (start, end) = month_date_range(month.year(), month.month());
-        let mut cache =
-            storage::scan_days_in_range(start, end, self.active_path()).unwrap_or_default();
+        let mut cache = self
+            .store
+            .scan_days_in_range(start, end, self.active_path())
+            .unwrap_or_default();
 
         // Mark dates with calendar events
         for date in start.iter_days().take_while(|d| *d <= end) {
@@ -128,7 +134,7 @@ impl App {
 
         // Mark dates with recurring entries
         if let Ok(recurring_dates) =
-            storage::scan_recurring_in_range(start, end, self.active_path())
+            storage::scan_recurring_in_range(self.store.as_ref(), start, end, self.active_path())
         {
             for date in recurring_dates {
                 cache.entry(date).or_default().has_recurring = true;
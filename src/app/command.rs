@@ -8,11 +8,11 @@ use crossterm::terminal::{
     Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
 };
 
-use crate::config::{Config, get_config_path, get_hub_config_path};
+use crate::config::{Config, JournalLayout, get_config_path, get_hub_config_path};
 use crate::registry::Command as RegistryCommand;
-use crate::storage::{JournalSlot, find_git_root};
+use crate::storage::{self, JournalSlot, find_git_root};
 
-use super::App;
+use super::{App, ConfirmContext, InputMode};
 
 impl App {
     pub fn execute_command(&mut self, command: &RegistryCommand) -> io::Result<()> {
@@ -63,6 +63,19 @@ impl App {
             "add-gitignore" => {
                 self.add_caliber_to_gitignore();
             }
+            "doctor" => {
+                self.diagnose_journal()?;
+            }
+            "habits" => {
+                self.toggle_habits();
+            }
+            "refresh-calendars" => {
+                if self.refresh_calendars_now() {
+                    self.set_status("Refreshing calendars");
+                } else {
+                    self.set_error("No calendars to refresh");
+                }
+            }
             _ => {}
         }
         Ok(())
@@ -103,6 +116,56 @@ impl App {
         Ok(())
     }
 
+    fn diagnose_journal(&mut self) -> io::Result<()> {
+        if self.config.journal_layout == JournalLayout::DailyFiles {
+            self.set_error("Doctor only checks single-file journals");
+            return Ok(());
+        }
+        self.save();
+        let journal = storage::load_journal(self.active_path())?;
+        let issues = storage::diagnose_journal(&journal);
+
+        let fixable = issues.iter().filter(|i| i.is_fixable()).count();
+        if issues.is_empty() {
+            self.set_status("Journal is healthy");
+        } else if fixable == 0 {
+            self.set_error(format!(
+                "{} issue(s) need manual attention ({})",
+                issues.len(),
+                issues[0]
+            ));
+        } else {
+            self.input_mode = InputMode::Confirm(ConfirmContext::RepairJournal(fixable));
+        }
+        Ok(())
+    }
+
+    /// Rewrites the journal into canonical form. Line indices may shift, so undo
+    /// history is discarded.
+    pub fn confirm_repair_journal(&mut self) -> io::Result<()> {
+        let issues = storage::modify_journal(self.active_path(), |journal| {
+            let issues = storage::diagnose_journal(journal);
+            *journal = storage::repair_journal(journal);
+            issues
+        })?;
+        let fixable = issues.iter().filter(|i| i.is_fixable()).count();
+
+        self.update_file_mtime();
+        self.executor.clear();
+        self.refresh_view_after_tag_change()?;
+
+        let remaining = issues.len() - fixable;
+        if remaining > 0 {
+            self.set_error(format!(
+                "Repaired {fixable} issue(s); {remaining} need manual attention"
+            ));
+        } else {
+            self.set_status(format!("Repaired {fixable} issue(s)"));
+        }
+        self.input_mode = InputMode::Normal;
+        Ok(())
+    }
+
     fn add_caliber_to_gitignore(&mut self) {
         let Some(git_root) = find_git_root() else {
             self.set_error("Not in a git repository");
@@ -0,0 +1,71 @@
+use std::io;
+
+use crate::storage::{self, ConflictResolution, Line, SaveOutcome};
+
+use super::{App, InputMode, ViewMode};
+
+impl App {
+    /// Adopts the result of a merge with on-disk changes. Line indices on this
+    /// day may have shifted, so undo history for it is discarded.
+    pub(super) fn apply_merged_lines(&mut self, lines: Vec<Line>) {
+        self.set_loaded_lines(lines);
+        self.executor.forget_current_day(self.current_date);
+        if let ViewMode::Daily(_) = self.view {
+            self.refresh_projected_entries();
+        }
+    }
+
+    /// Resolves the pending conflict and writes the result. The on-disk version the
+    /// conflict was computed against becomes the new base, so anything written since
+    /// is merged again rather than overwritten.
+    pub fn resolve_conflict(&mut self, resolution: ConflictResolution) -> io::Result<()> {
+        let InputMode::Conflict(state) = std::mem::replace(&mut self.input_mode, InputMode::Normal)
+        else {
+            return Ok(());
+        };
+        let conflict = state.conflict;
+        let resolved = conflict.resolve(resolution);
+
+        let outcome = storage::save_day_lines_merged(
+            self.store.as_ref(),
+            conflict.date,
+            &state.path,
+            &conflict.theirs,
+            &resolved,
+        )?;
+        let is_current_day =
+            conflict.date == self.current_date && state.path.as_path() == self.active_path();
+
+        match outcome {
+            SaveOutcome::Saved(stored) | SaveOutcome::Merged(stored) => {
+                if is_current_day {
+                    self.apply_merged_lines(stored);
+                }
+                let label = match resolution {
+                    ConflictResolution::Mine => "Kept your version",
+                    ConflictResolution::Theirs => "Kept the version on disk",
+                    ConflictResolution::Both => "Kept both versions",
+                };
+                self.set_status(label);
+            }
+            SaveOutcome::Conflict(conflict) => {
+                self.input_mode = InputMode::Conflict(super::ConflictState {
+                    path: state.path,
+                    conflict,
+                });
+            }
+        }
+
+        self.update_file_mtime();
+        self.invalidate_sidebar_caches();
+        self.refresh_calendar_cache();
+        Ok(())
+    }
+
+    /// Closes the conflict overlay without writing. In-memory changes are kept and
+    /// merged again on the next save.
+    pub fn defer_conflict(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.set_error("Conflict unresolved, changes not saved");
+    }
+}
@@ -47,15 +47,28 @@ impl App {
                 }
             }
             EntryLocation::Projected(entry) => {
-                storage::mutate_entry(entry.source_date, &path, entry.line_index, |raw_entry| {
-                    raw_entry.content = content.to_string();
-                })?;
+                storage::mutate_entry(
+                    self.store.as_ref(),
+                    entry.source_date,
+                    &path,
+                    entry.line_index,
+                    |raw_entry| {
+                        raw_entry.content = content.to_string();
+                    },
+                )?;
                 self.refresh_projected_entries();
+                self.invalidate_sidebar_caches();
             }
             EntryLocation::Filter { index, entry } => {
-                storage::mutate_entry(entry.source_date, &path, entry.line_index, |raw_entry| {
-                    raw_entry.content = content.to_string();
-                })?;
+                storage::mutate_entry(
+                    self.store.as_ref(),
+                    entry.source_date,
+                    self.entry_path(entry),
+                    entry.line_index,
+                    |raw_entry| {
+                        raw_entry.content = content.to_string();
+                    },
+                )?;
 
                 if let ViewMode::Filter(state) = &mut self.view
                     && let Some(filter_entry) = state.entries.get_mut(*index)
@@ -63,7 +76,7 @@ impl App {
                     filter_entry.content = content.to_string();
                 }
 
-                if entry.source_date == self.current_date {
+                if self.is_on_current_day(entry) {
                     self.reload_current_day()?;
                 }
             }
@@ -81,7 +94,9 @@ impl App {
                 }
             }
             EntryLocation::Projected(entry) | EntryLocation::Filter { entry, .. } => {
-                let lines = storage::load_day_lines(entry.source_date, self.active_path())?;
+                let lines = self
+                    .store
+                    .load_day_lines(entry.source_date, self.entry_path(entry))?;
                 if let Some(Line::Entry(raw_entry)) = lines.get(entry.line_index) {
                     Ok(raw_entry.content.clone())
                 } else {
@@ -5,15 +5,26 @@ use chrono::Local;
 use crate::cursor::CursorBuffer;
 use crate::storage::{ParseContext, parse_date};
 
-use super::{App, DatePickerState, InputMode};
+use super::{App, DatePickerPurpose, DatePickerState, InputMode};
 
 impl App {
     pub fn open_date_picker(&mut self) {
         self.input_mode = InputMode::DatePicker(DatePickerState {
             buffer: CursorBuffer::empty(),
+            purpose: DatePickerPurpose::GoTo,
         });
     }
 
+    /// Opens the date picker to move the selected recurring occurrence.
+    pub fn open_move_occurrence_picker(&mut self) {
+        if let Some(entry) = self.current_recurring_occurrence() {
+            self.input_mode = InputMode::DatePicker(DatePickerState {
+                buffer: CursorBuffer::empty(),
+                purpose: DatePickerPurpose::MoveOccurrence(entry),
+            });
+        }
+    }
+
     pub fn submit_date_picker(&mut self) -> io::Result<()> {
         let InputMode::DatePicker(state) = &self.input_mode else {
             return Ok(());
@@ -25,11 +36,28 @@ impl App {
             return Ok(());
         }
 
-        let today = Local::now().date_naive();
-        match parse_date(&input, ParseContext::Interface, today) {
+        let parsed = match &state.purpose {
+            DatePickerPurpose::GoTo => {
+                parse_date(&input, ParseContext::Interface, Local::now().date_naive())
+            }
+            // Occurrences move forward from the day they're shown on
+            DatePickerPurpose::MoveOccurrence(_) => {
+                parse_date(&input, ParseContext::Entry, self.current_date)
+            }
+        };
+        match parsed {
             Some(date) => {
-                self.input_mode = InputMode::Normal;
-                self.goto_day(date)?;
+                let InputMode::DatePicker(state) =
+                    std::mem::replace(&mut self.input_mode, InputMode::Normal)
+                else {
+                    return Ok(());
+                };
+                match state.purpose {
+                    DatePickerPurpose::GoTo => self.goto_day(date)?,
+                    DatePickerPurpose::MoveOccurrence(entry) => {
+                        self.move_occurrence(entry, date)?
+                    }
+                }
             }
             None => {
                 self.set_error(format!("Invalid date: {}", input));
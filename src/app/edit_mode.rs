@@ -1,5 +1,11 @@
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+
 use crate::cursor::CursorBuffer;
-use crate::storage::{self, Entry, EntryType, Line, RawEntry, SourceType, restore_done_meta};
+use crate::storage::{
+    self, Entry, EntryType, Line, RawEntry, SourceJournal, SourceType, restore_entry_meta,
+};
 
 use super::actions::{CreateEntry, CreateTarget, EditEntry, EditTarget};
 use super::{App, EditContext, EntryLocation, InputMode, InsertPosition, ViewMode};
@@ -24,14 +30,17 @@ impl App {
                 let date = *date;
                 let line_index = *line_index;
                 let filter_index = *filter_index;
-                let path = self.active_path();
+                let journal = self.filter_entry_journal(filter_index);
+                let path = self.journal_path(journal.as_ref());
+                let on_current_day = date == self.current_date && path == self.active_path();
 
-                if let Ok(Some(new_type)) = storage::cycle_entry_type(date, path, line_index)
+                if let Ok(Some(new_type)) =
+                    storage::cycle_entry_type(self.store.as_ref(), date, &path, line_index)
                     && let ViewMode::Filter(state) = &mut self.view
                     && let Some(filter_entry) = state.entries.get_mut(filter_index)
                 {
                     filter_entry.entry_type = new_type;
-                    if date == self.current_date {
+                    if on_current_day {
                         let _ = self.reload_current_day();
                     }
                 }
@@ -121,7 +130,10 @@ impl App {
             return;
         };
 
-        let new_content = restore_done_meta(&new_content, &original_content);
+        let mut new_content = restore_entry_meta(&new_content, &original_content);
+        if is_new_entry {
+            new_content = self.with_created_date(&new_content);
+        }
 
         if let Some(entry) = self.get_daily_entry_mut(entry_index) {
             entry.content = new_content.clone();
@@ -129,7 +141,8 @@ impl App {
         }
 
         if is_new_entry {
-            if let Line::Entry(raw_entry) = &self.lines[line_idx] {
+            // A merge with on-disk changes during save may have shortened the day
+            if let Some(Line::Entry(raw_entry)) = self.lines.get(line_idx) {
                 let entry =
                     Entry::from_raw(raw_entry, self.current_date, line_idx, SourceType::Local);
                 let target = CreateTarget {
@@ -161,14 +174,15 @@ impl App {
         new_content: String,
         original_content: String,
     ) {
-        let path = self.active_path().to_path_buf();
+        let journal = self.filter_entry_journal(filter_index);
+        let path = self.journal_path(journal.as_ref());
 
-        let new_content = restore_done_meta(&new_content, &original_content);
+        let new_content = restore_entry_meta(&new_content, &original_content);
 
         if new_content.trim().is_empty() {
-            let _ = storage::delete_entry(date, &path, line_index);
+            let _ = storage::delete_entry(self.store.as_ref(), date, &path, line_index);
         } else if let Some((entry_type, new_content)) =
-            self.update_remote_entry(date, line_index, new_content, &original_content)
+            self.update_remote_entry(&path, date, line_index, new_content, &original_content)
         {
             let entry = Entry {
                 entry_type: entry_type.clone(),
@@ -176,6 +190,8 @@ impl App {
                 source_date: date,
                 line_index,
                 source_type: SourceType::Local,
+                depth: 0,
+                journal,
             };
             let target = EditTarget {
                 location: EntryLocation::Filter {
@@ -201,23 +217,23 @@ impl App {
     ) {
         let path = self.active_path().to_path_buf();
 
+        let content = self.with_created_date(&content);
+        let raw_entry = RawEntry::new(entry_type.clone(), content.clone());
         if !content.trim().is_empty()
-            && let Ok(mut lines) = storage::load_day_lines(date, &path)
+            && let Ok(line_index) =
+                storage::modify_day_lines(self.store.as_ref(), date, &path, |lines| {
+                    lines.push(Line::Entry(raw_entry));
+                    lines.len() - 1
+                })
         {
-            let line_index = lines.len();
-            let raw_entry = RawEntry {
-                entry_type: entry_type.clone(),
-                content: content.clone(),
-            };
-            lines.push(Line::Entry(raw_entry));
-            let _ = storage::save_day_lines(date, &path, &lines);
-
             let entry = Entry {
                 entry_type,
                 content,
                 source_date: date,
                 line_index,
                 source_type: SourceType::Local,
+                depth: 0,
+                journal: None,
             };
             let target = CreateTarget {
                 date,
@@ -235,17 +251,48 @@ impl App {
         }
     }
 
+    /// Stamps a new entry with today's date when `record_dates` is enabled
+    fn with_created_date(&self, content: &str) -> String {
+        if self.config.record_dates {
+            storage::add_created_date(content, Local::now().date_naive())
+        } else {
+            content.to_string()
+        }
+    }
+
+    /// Source journal of the filter result at `filter_index`, if it came from a
+    /// cross-journal query
+    fn filter_entry_journal(&self, filter_index: usize) -> Option<SourceJournal> {
+        let ViewMode::Filter(state) = &self.view else {
+            return None;
+        };
+        state.entries.get(filter_index)?.journal.clone()
+    }
+
+    fn journal_path(&self, journal: Option<&SourceJournal>) -> PathBuf {
+        journal.map_or_else(
+            || self.active_path().to_path_buf(),
+            |journal| journal.path.clone(),
+        )
+    }
+
     fn update_remote_entry(
         &mut self,
+        path: &Path,
         date: chrono::NaiveDate,
         line_index: usize,
         new_content: String,
         original_content: &str,
     ) -> Option<(EntryType, String)> {
-        let path = self.active_path().to_path_buf();
-        let entry_type = storage::get_entry_type(date, &path, line_index);
-
-        match storage::update_entry_content(date, &path, line_index, new_content.clone()) {
+        let entry_type = storage::get_entry_type(self.store.as_ref(), date, path, line_index);
+
+        match storage::update_entry_content(
+            self.store.as_ref(),
+            date,
+            path,
+            line_index,
+            new_content.clone(),
+        ) {
             Ok(false) => {
                 self.set_error(format!(
                     "Failed to update: no entry at index {line_index} for {date}"
@@ -287,15 +334,15 @@ impl App {
                 let entry_type = self
                     .get_daily_entry(entry_index)
                     .map(|e| e.entry_type.clone())
-                    .unwrap_or(EntryType::Task { completed: false });
+                    .unwrap_or(EntryType::task());
 
-                let new_raw_entry = RawEntry {
-                    entry_type: match entry_type {
-                        EntryType::Task { .. } => EntryType::Task { completed: false },
+                let new_raw_entry = RawEntry::new(
+                    match entry_type {
+                        EntryType::Task { .. } => EntryType::task(),
                         other => other,
                     },
-                    content: String::new(),
-                };
+                    String::new(),
+                );
                 self.add_entry_internal(new_raw_entry, InsertPosition::Below);
             }
             EditContext::FilterQuickAdd { date, entry_type } => {
@@ -304,7 +351,7 @@ impl App {
                 self.input_mode = InputMode::Edit(EditContext::FilterQuickAdd {
                     date,
                     entry_type: match entry_type {
-                        EntryType::Task { .. } => EntryType::Task { completed: false },
+                        EntryType::Task { .. } => EntryType::task(),
                         other => other,
                     },
                 });
@@ -313,7 +360,7 @@ impl App {
         }
     }
 
-    pub(super) fn add_entry_internal(&mut self, entry: RawEntry, position: InsertPosition) {
+    pub(super) fn add_entry_internal(&mut self, mut entry: RawEntry, position: InsertPosition) {
         use super::SelectedItem;
 
         let insert_pos =
@@ -321,11 +368,34 @@ impl App {
                 self.lines.len()
             } else {
                 match self.get_selected_item() {
-                    SelectedItem::Daily { index, .. } => match position {
-                        InsertPosition::Below => self.entry_indices[index] + 1,
-                        InsertPosition::Above => self.entry_indices[index],
-                        InsertPosition::Bottom => unreachable!(),
-                    },
+                    SelectedItem::Daily {
+                        line_idx,
+                        entry: selected,
+                        ..
+                    } => {
+                        // New entries become siblings of the selection, or its first
+                        // sub-entry when its sub-entries are expanded below it
+                        let subtree_end = storage::subtree_end(&self.lines, line_idx);
+                        match position {
+                            InsertPosition::Below if selected.collapsed => {
+                                entry.depth = selected.depth;
+                                subtree_end
+                            }
+                            InsertPosition::Below if subtree_end > line_idx + 1 => {
+                                entry.depth = selected.depth + 1;
+                                line_idx + 1
+                            }
+                            InsertPosition::Below => {
+                                entry.depth = selected.depth;
+                                line_idx + 1
+                            }
+                            InsertPosition::Above => {
+                                entry.depth = selected.depth;
+                                line_idx
+                            }
+                            InsertPosition::Bottom => unreachable!(),
+                        }
+                    }
                     _ => self.lines.len(),
                 }
             };
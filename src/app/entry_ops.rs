@@ -1,11 +1,11 @@
 use std::io;
 
-use chrono::{Datelike, Days, NaiveDate, Weekday};
+use chrono::{Datelike, Days, Local, NaiveDate, Weekday};
 
 use crate::cursor::CursorBuffer;
 use crate::storage::{
     self, Entry, EntryType, RawEntry, SourceType, add_done_date, is_done_on_date,
-    parse_to_raw_entry, remove_done_date, strip_done_meta,
+    parse_to_raw_entry, record_completion, remove_done_date, strip_entry_meta,
 };
 
 use super::{App, EditContext, InputMode, Line, SelectedItem, ViewMode};
@@ -32,6 +32,32 @@ pub enum EntryLocation {
     Filter { index: usize, entry: Entry },
 }
 
+impl DeleteTarget {
+    /// Whether the entry is on the day on screen, as daily entries always are.
+    #[must_use]
+    pub fn is_on_current_day(&self, current_date: NaiveDate) -> bool {
+        match self {
+            Self::Daily { .. } => true,
+            Self::Projected(entry) | Self::Filter { entry, .. } => {
+                entry.source_date == current_date
+            }
+        }
+    }
+}
+
+impl EntryLocation {
+    /// Whether the entry is on the day on screen, as daily entries always are.
+    #[must_use]
+    pub fn is_on_current_day(&self, current_date: NaiveDate) -> bool {
+        match self {
+            Self::Daily { .. } => true,
+            Self::Projected(entry) | Self::Filter { entry, .. } => {
+                entry.source_date == current_date
+            }
+        }
+    }
+}
+
 /// Target for toggle operations (type alias for semantic clarity)
 pub type ToggleTarget = EntryLocation;
 
@@ -60,11 +86,14 @@ impl App {
         target_date: NaiveDate,
     ) -> io::Result<()> {
         let path = self.active_path().to_path_buf();
-        let mut target_lines = storage::load_day_lines(target_date, &path)?;
-        for entry in entries {
-            target_lines.push(Line::Entry(entry));
-        }
-        storage::save_day_lines(target_date, &path, &target_lines)?;
+        // Keep nesting among the moved entries but attach them at the top level
+        let min_depth = entries.iter().map(|e| e.depth).min().unwrap_or(0);
+        storage::modify_day_lines(self.store.as_ref(), target_date, &path, |lines| {
+            lines.extend(entries.into_iter().map(|mut entry| {
+                entry.depth -= min_depth;
+                Line::Entry(entry)
+            }));
+        })?;
         self.refresh_affected_views(target_date)
     }
 
@@ -138,11 +167,25 @@ impl App {
 
     pub fn execute_toggle(&mut self, target: ToggleTarget) -> io::Result<()> {
         let path = self.active_path().to_path_buf();
+        let completed_on = self.config.record_dates.then(|| Local::now().date_naive());
         match target {
+            ToggleTarget::Projected(entry) if entry.source_type == SourceType::Due => {
+                storage::toggle_entry_complete(
+                    self.store.as_ref(),
+                    entry.source_date,
+                    &path,
+                    entry.line_index,
+                    completed_on,
+                )?;
+                self.refresh_projected_entries();
+            }
             ToggleTarget::Projected(entry) => {
-                let Some(content) =
-                    storage::get_entry_content(entry.source_date, &path, entry.line_index)
-                else {
+                let Some(content) = storage::get_entry_content(
+                    self.store.as_ref(),
+                    entry.source_date,
+                    &path,
+                    entry.line_index,
+                ) else {
                     return Ok(());
                 };
 
@@ -153,16 +196,20 @@ impl App {
                 };
 
                 storage::update_entry_content(
+                    self.store.as_ref(),
                     entry.source_date,
                     &path,
                     entry.line_index,
                     new_content,
                 )?;
                 self.refresh_projected_entries();
+                self.invalidate_sidebar_caches();
             }
             ToggleTarget::Daily { line_idx } => {
                 if let Line::Entry(raw_entry) = &mut self.lines[line_idx] {
                     raw_entry.toggle_complete();
+                    raw_entry.content =
+                        record_completion(&raw_entry.content, &raw_entry.entry_type, completed_on);
                     self.save();
                     if self.hide_completed {
                         self.clamp_selection_to_visible();
@@ -170,16 +217,25 @@ impl App {
                 }
             }
             ToggleTarget::Filter { index, entry } => {
-                storage::toggle_entry_complete(entry.source_date, &path, entry.line_index)?;
+                storage::toggle_entry_complete(
+                    self.store.as_ref(),
+                    entry.source_date,
+                    self.entry_path(&entry),
+                    entry.line_index,
+                    completed_on,
+                )?;
 
                 if let ViewMode::Filter(state) = &mut self.view {
                     let filter_entry = &mut state.entries[index];
-                    if let EntryType::Task { completed } = &mut filter_entry.entry_type {
-                        *completed = !*completed;
-                    }
+                    filter_entry.toggle_complete();
+                    filter_entry.content = record_completion(
+                        &filter_entry.content,
+                        &filter_entry.entry_type,
+                        completed_on,
+                    );
                 }
 
-                if entry.source_date == self.current_date {
+                if self.is_on_current_day(&entry) {
                     self.reload_current_day()?;
                 }
             }
@@ -218,7 +274,7 @@ impl App {
         // Keep original with metadata for restoration on save
         self.original_edit_content = Some(content.clone());
         // Strip metadata for display in edit buffer
-        let display_content = strip_done_meta(&content);
+        let display_content = strip_entry_meta(&content);
         self.edit_buffer = Some(CursorBuffer::new(display_content));
         self.input_mode = InputMode::Edit(ctx);
         self.update_hints();
@@ -332,6 +388,23 @@ impl App {
         self.execute_action(Box::new(action))
     }
 
+    pub fn cycle_current_task_state(&mut self) -> io::Result<()> {
+        let Some(target) = self.extract_cycle_target_from_current() else {
+            return Ok(());
+        };
+        if !matches!(target.original_type, EntryType::Task { .. }) {
+            return Ok(());
+        }
+        if let EntryLocation::Projected(entry) = &target.location
+            && entry.source_type == SourceType::Recurring
+        {
+            self.set_error("Cannot change the state of recurring entries");
+            return Ok(());
+        }
+        let action = super::actions::CycleEntryType::task_states(vec![target]);
+        self.execute_action(Box::new(action))
+    }
+
     fn is_test_environment() -> bool {
         // cfg(test) only works for unit tests in this crate.
         // For integration tests, we check CALIBER_SKIP_CLIPBOARD which TestContext sets.
@@ -366,7 +439,20 @@ impl App {
         self.paste_entries_from_text(&text)
     }
 
+    /// Source journal's name if the selected filter result lives outside the active journal
+    fn selected_foreign_journal(&self) -> Option<String> {
+        match self.get_selected_item() {
+            SelectedItem::Filter { entry, .. } => self.foreign_journal(entry).map(str::to_string),
+            _ => None,
+        }
+    }
+
     pub fn paste_entries_from_text(&mut self, text: &str) -> io::Result<()> {
+        if let Some(name) = self.selected_foreign_journal() {
+            self.set_status(format!("Switch to {name} to paste there"));
+            return Ok(());
+        }
+
         let mut raw_entries = Self::parse_paste_raw(text);
         if raw_entries.is_empty() {
             self.set_error("Nothing to paste");
@@ -389,25 +475,25 @@ impl App {
         };
 
         let path = self.active_path().to_path_buf();
-        let mut lines = storage::load_day_lines(date, &path)?;
-        let insert_pos = if lines.is_empty() {
-            0
-        } else {
-            insert_after + 1
-        };
-
         // Insert raw entries into lines and build full entries for action
-        let entries: Vec<Entry> = raw_entries
-            .iter()
-            .enumerate()
-            .map(|(i, raw)| {
-                let line_idx = insert_pos + i;
-                lines.insert(line_idx, Line::Entry(raw.clone()));
-                Entry::from_raw(raw, date, line_idx, SourceType::Local)
-            })
-            .collect();
-
-        storage::save_day_lines(date, &path, &lines)?;
+        let (insert_pos, entries) =
+            storage::modify_day_lines(self.store.as_ref(), date, &path, |lines| {
+                let insert_pos = if lines.is_empty() {
+                    0
+                } else {
+                    insert_after + 1
+                };
+                let entries: Vec<Entry> = raw_entries
+                    .iter()
+                    .enumerate()
+                    .map(|(i, raw)| {
+                        let line_idx = insert_pos + i;
+                        lines.insert(line_idx, Line::Entry(raw.clone()));
+                        Entry::from_raw(raw, date, line_idx, SourceType::Local)
+                    })
+                    .collect();
+                (insert_pos, entries)
+            })?;
         let pasted_count = entries.len();
         self.refresh_affected_views(date)?;
 
@@ -453,15 +539,23 @@ impl App {
     }
 
     fn move_current_entry_to_date(&mut self, target_date: NaiveDate) -> io::Result<()> {
-        if let SelectedItem::Projected { .. } = self.get_selected_item() {
+        if let SelectedItem::Projected { entry, .. } = self.get_selected_item() {
+            if entry.source_type == SourceType::Recurring {
+                return self.move_current_occurrence(target_date);
+            }
             self.set_status("Press o to go to source");
             return Ok(());
         }
+        if let Some(name) = self.selected_foreign_journal() {
+            self.set_status(format!("Switch to {name} to move its entries"));
+            return Ok(());
+        }
 
         let (source_date, raw_entry) = match self.get_selected_item() {
             SelectedItem::Daily { entry, .. } => (self.current_date, entry.clone()),
             SelectedItem::Filter { entry, .. } => {
                 let content = storage::get_entry_content(
+                    self.store.as_ref(),
                     entry.source_date,
                     self.active_path(),
                     entry.line_index,
@@ -469,10 +563,7 @@ impl App {
                 .unwrap_or_default();
                 (
                     entry.source_date,
-                    RawEntry {
-                        entry_type: entry.entry_type.clone(),
-                        content,
-                    },
+                    RawEntry::new(entry.entry_type.clone(), content).with_depth(entry.depth),
                 )
             }
             SelectedItem::Projected { .. } | SelectedItem::None => return Ok(()),
@@ -538,4 +629,64 @@ impl App {
 
         target
     }
+
+    /// Returns the selected projection if it's a recurring occurrence.
+    pub(super) fn current_recurring_occurrence(&mut self) -> Option<Entry> {
+        match self.get_selected_item() {
+            SelectedItem::Projected { entry, .. } if entry.source_type == SourceType::Recurring => {
+                Some(entry.clone())
+            }
+            _ => {
+                self.set_status("Not a recurring occurrence");
+                None
+            }
+        }
+    }
+
+    pub fn skip_current_occurrence(&mut self) -> io::Result<()> {
+        let Some(entry) = self.current_recurring_occurrence() else {
+            return Ok(());
+        };
+        let location = EntryLocation::Projected(entry);
+        let content = self.get_entry_content(&location)?;
+        let action = super::actions::SetOccurrence::skip(location, &content, self.current_date);
+        self.execute_action(Box::new(action))
+    }
+
+    /// Moves the selected recurring occurrence to another day, leaving the rest
+    /// of the series where it is.
+    pub fn move_current_occurrence(&mut self, target_date: NaiveDate) -> io::Result<()> {
+        match self.current_recurring_occurrence() {
+            Some(entry) => self.move_occurrence(entry, target_date),
+            None => Ok(()),
+        }
+    }
+
+    pub(super) fn move_occurrence(
+        &mut self,
+        entry: Entry,
+        target_date: NaiveDate,
+    ) -> io::Result<()> {
+        if target_date == self.current_date {
+            self.set_status("Entry already on target date");
+            return Ok(());
+        }
+        if target_date <= entry.source_date {
+            self.set_error(format!(
+                "Can't move before the series starts on {}",
+                entry.source_date.format("%m/%d")
+            ));
+            return Ok(());
+        }
+
+        let location = EntryLocation::Projected(entry);
+        let content = self.get_entry_content(&location)?;
+        let action = super::actions::SetOccurrence::reschedule(
+            location,
+            &content,
+            self.current_date,
+            target_date,
+        );
+        self.execute_action(Box::new(action))
+    }
 }
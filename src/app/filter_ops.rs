@@ -1,27 +1,135 @@
+use std::collections::HashSet;
 use std::io;
 
 use chrono::Local;
 
 use crate::cursor::CursorBuffer;
-use crate::storage::{self, EntryType};
+use crate::storage::{self, Entry, EntryType, Filter, FilterGroup, ProjectRegistry};
 
 use super::{App, EditContext, FilterState, InputMode, ViewMode};
 
+/// One line of the filter list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterRow {
+    /// Header of a group of results, `first` being the index of its first entry
+    Header {
+        label: String,
+        first: usize,
+        count: usize,
+        collapsed: bool,
+    },
+    /// Index of a result
+    Entry(usize),
+}
+
+impl FilterState {
+    /// Lines of the filter list: each result, under group headers when the
+    /// results are grouped. Results in a collapsed group get no line.
+    #[must_use]
+    pub fn rows(&self) -> Vec<FilterRow> {
+        let Some(group) = self.group else {
+            return (0..self.entries.len()).map(FilterRow::Entry).collect();
+        };
+
+        let mut rows = Vec::new();
+        let mut header = 0;
+        for (idx, entry) in self.entries.iter().enumerate() {
+            let label = group.label(entry);
+            let continues_group = matches!(
+                rows.get(header),
+                Some(FilterRow::Header { label: current, .. }) if *current == label
+            );
+            if !continues_group {
+                header = rows.len();
+                rows.push(FilterRow::Header {
+                    collapsed: self.collapsed.contains(&label),
+                    label,
+                    first: idx,
+                    count: 0,
+                });
+            }
+            if let Some(FilterRow::Header {
+                count, collapsed, ..
+            }) = rows.get_mut(header)
+            {
+                *count += 1;
+                if !*collapsed {
+                    rows.push(FilterRow::Entry(idx));
+                }
+            }
+        }
+        rows
+    }
+
+    /// Whether the result at `index` is hidden in a collapsed group.
+    #[must_use]
+    pub fn is_folded(&self, index: usize) -> bool {
+        self.group.is_some_and(|group| {
+            self.entries
+                .get(index)
+                .is_some_and(|entry| self.collapsed.contains(&group.label(entry)))
+        })
+    }
+
+    /// Line the selection is drawn on: its own, or its collapsed group's header.
+    #[must_use]
+    pub fn selected_row(&self, rows: &[FilterRow]) -> usize {
+        rows.iter()
+            .position(|row| match row {
+                FilterRow::Entry(idx) => *idx == self.selected,
+                FilterRow::Header {
+                    first,
+                    count,
+                    collapsed: true,
+                    ..
+                } => (*first..first + count).contains(&self.selected),
+                FilterRow::Header { .. } => false,
+            })
+            .unwrap_or(0)
+    }
+
+    /// Results the cursor can rest on, in display order. A collapsed group
+    /// is represented by its first result.
+    #[must_use]
+    pub fn selectable_indices(&self) -> Vec<usize> {
+        self.rows()
+            .into_iter()
+            .filter_map(|row| match row {
+                FilterRow::Entry(idx) => Some(idx),
+                FilterRow::Header {
+                    first,
+                    collapsed: true,
+                    ..
+                } => Some(first),
+                FilterRow::Header { .. } => None,
+            })
+            .collect()
+    }
+}
+
+/// Replaces every `<prefix>...` token of a query with `<prefix><value>`, or
+/// drops them for None.
+fn replace_query_modifier(query: &str, prefix: &str, value: Option<&str>) -> String {
+    let mut tokens: Vec<String> = query
+        .split_whitespace()
+        .filter(|token| !token.starts_with(prefix))
+        .map(str::to_string)
+        .collect();
+    if let Some(value) = value {
+        tokens.push(format!("{prefix}{value}"));
+    }
+    tokens.join(" ")
+}
+
 impl App {
     /// Switch to filter view with the given query.
     fn reset_filter_view(&mut self, query: String) -> io::Result<()> {
-        let (query, unknown_filters) = storage::expand_saved_filters(&query, &self.config.filters);
-        let mut filter = storage::parse_filter_query(&query);
-        filter.invalid_tokens.extend(unknown_filters);
-
-        if !filter.invalid_tokens.is_empty() {
-            self.set_status(format!(
-                "Unknown filter: {}",
-                filter.invalid_tokens.join(", ")
-            ));
+        let filter = storage::parse_filter_query(&query, &self.config.filters);
+        if let Some(message) = filter.error_message() {
+            self.set_status(message);
         }
 
-        let entries = storage::collect_filtered_entries(&filter, self.active_path())?;
+        let entries = self.collect_filter_results(&filter)?;
         let selected = entries.len().saturating_sub(1);
 
         self.view = ViewMode::Filter(FilterState {
@@ -30,6 +138,10 @@ impl App {
             entries,
             selected,
             scroll_offset: 0,
+            group: filter.group,
+            collapsed: HashSet::new(),
+            highlights: filter.highlight_terms(),
+            invalid_spans: filter.invalid_spans(),
         });
         self.finalize_view_switch();
         Ok(())
@@ -65,25 +177,50 @@ impl App {
         self.quick_filter(&query)
     }
 
+    /// Runs a filter over the journals its scope names, or the active journal
+    /// without one. An unknown project is reported and matches nothing.
+    fn collect_filter_results(&mut self, filter: &Filter) -> io::Result<Vec<Entry>> {
+        if filter.scopes.is_empty() {
+            return storage::collect_filtered_entries(
+                self.store.as_ref(),
+                filter,
+                self.active_path(),
+            );
+        }
+        match storage::resolve_filter_scope(
+            &filter.scopes,
+            self.active_path(),
+            self.journal_context.hub_path(),
+            &ProjectRegistry::load(),
+        ) {
+            Ok(journals) => {
+                storage::collect_filtered_entries_across(self.store.as_ref(), filter, &journals)
+            }
+            Err(message) => {
+                self.set_error(message);
+                Ok(Vec::new())
+            }
+        }
+    }
+
     pub fn refresh_filter(&mut self) -> io::Result<()> {
-        let path = self.active_path().to_path_buf();
-        let ViewMode::Filter(state) = &mut self.view else {
+        let ViewMode::Filter(state) = &self.view else {
             return Ok(());
         };
 
-        let filter = storage::parse_filter_query(&state.query);
-        let error_msg = if filter.invalid_tokens.is_empty() {
-            None
-        } else {
-            Some(format!(
-                "Unknown filter: {}",
-                filter.invalid_tokens.join(", ")
-            ))
-        };
+        let filter = storage::parse_filter_query(&state.query, &self.config.filters);
+        let error_msg = filter.error_message();
 
-        state.entries = storage::collect_filtered_entries(&filter, &path)?;
+        let entries = self.collect_filter_results(&filter)?;
+        let ViewMode::Filter(state) = &mut self.view else {
+            return Ok(());
+        };
+        state.entries = entries;
         state.selected = state.selected.min(state.entries.len().saturating_sub(1));
         state.scroll_offset = 0;
+        state.group = filter.group;
+        state.highlights = filter.highlight_terms();
+        state.invalid_spans = filter.invalid_spans();
 
         if let Some(msg) = error_msg {
             self.set_error(msg);
@@ -97,7 +234,7 @@ impl App {
         self.edit_buffer = Some(CursorBuffer::empty());
         self.input_mode = InputMode::Edit(EditContext::FilterQuickAdd {
             date: today,
-            entry_type: EntryType::Task { completed: false },
+            entry_type: EntryType::task(),
         });
     }
 
@@ -106,7 +243,10 @@ impl App {
         let ViewMode::Filter(state) = &self.view else {
             return 0;
         };
-        state.selected
+        if state.group.is_none() {
+            return state.selected;
+        }
+        state.selected_row(&state.rows())
     }
 
     #[must_use]
@@ -114,7 +254,119 @@ impl App {
         let ViewMode::Filter(state) = &self.view else {
             return 1;
         };
-        state.entries.len()
+        if state.group.is_none() {
+            return state.entries.len();
+        }
+        state.rows().len()
+    }
+
+    /// Moves to the next sort order, rewriting the query's `sort:` modifier.
+    pub fn cycle_filter_sort(&mut self) -> io::Result<()> {
+        let ViewMode::Filter(state) = &self.view else {
+            return Ok(());
+        };
+        let current = storage::parse_filter_query(&state.query, &self.config.filters).sort;
+        let next = current.next();
+        let query = self.query_with_modifier(&state.query, "sort:", &next.name(), |filter| {
+            filter.sort == next
+        });
+        self.requery_filter(query)?;
+        self.set_status(format!("Sorted by {}", next.name()));
+        Ok(())
+    }
+
+    /// Moves to the next grouping, rewriting the query's `group:` modifier.
+    pub fn cycle_filter_group(&mut self) -> io::Result<()> {
+        let ViewMode::Filter(state) = &self.view else {
+            return Ok(());
+        };
+        let current = storage::parse_filter_query(&state.query, &self.config.filters).group;
+        let next = FilterGroup::next(current);
+        let name = next.map_or("none", FilterGroup::name);
+        let query =
+            self.query_with_modifier(&state.query, "group:", name, |filter| filter.group == next);
+        if let ViewMode::Filter(state) = &mut self.view {
+            state.collapsed.clear();
+        }
+        self.requery_filter(query)?;
+        match next {
+            Some(group) => self.set_status(format!("Grouped by {}", group.name())),
+            None => self.set_status("Ungrouped"),
+        }
+        Ok(())
+    }
+
+    /// The query with its `<prefix>` modifiers replaced by `<prefix><value>`.
+    /// The modifier is left out when the rest of the query, including any
+    /// saved filters it uses, already gives the same result.
+    fn query_with_modifier(
+        &self,
+        query: &str,
+        prefix: &str,
+        value: &str,
+        already: impl Fn(&Filter) -> bool,
+    ) -> String {
+        let stripped = replace_query_modifier(query, prefix, None);
+        if already(&storage::parse_filter_query(
+            &stripped,
+            &self.config.filters,
+        )) {
+            stripped
+        } else {
+            replace_query_modifier(query, prefix, Some(value))
+        }
+    }
+
+    /// Reruns the filter view under a new query, keeping the selected entry.
+    fn requery_filter(&mut self, query: String) -> io::Result<()> {
+        let ViewMode::Filter(state) = &mut self.view else {
+            return Ok(());
+        };
+        let selected = state
+            .entries
+            .get(state.selected)
+            .map(|entry| (entry.journal.clone(), entry.source_date, entry.line_index));
+        state.query_buffer = CursorBuffer::new(query.clone());
+        state.query = query;
+        self.refresh_filter()?;
+
+        let ViewMode::Filter(state) = &mut self.view else {
+            return Ok(());
+        };
+        if let Some(index) = selected.and_then(|(journal, date, line_index)| {
+            state.entries.iter().position(|entry| {
+                entry.journal == journal
+                    && entry.source_date == date
+                    && entry.line_index == line_index
+            })
+        }) {
+            state.selected = index;
+        }
+        self.last_filter_query = Some(state.query.clone());
+        Ok(())
+    }
+
+    /// Collapses the selected result's group down to its header, or expands it.
+    pub fn toggle_group_collapse(&mut self) {
+        let ViewMode::Filter(state) = &mut self.view else {
+            return;
+        };
+        let Some(group) = state.group else {
+            self.set_error("Results aren't grouped");
+            return;
+        };
+        let Some(entry) = state.entries.get(state.selected) else {
+            return;
+        };
+        let label = group.label(entry);
+        if !state.collapsed.remove(&label) {
+            state.selected = state
+                .entries
+                .iter()
+                .position(|entry| group.label(entry) == label)
+                .unwrap_or(state.selected);
+            state.collapsed.insert(label);
+        }
     }
 
     pub fn cycle_view(&mut self) -> io::Result<()> {
@@ -152,16 +404,9 @@ impl App {
 
         let new_query = state.query_buffer.content().trim().to_string();
 
-        let (expanded, unknown_filters) =
-            storage::expand_saved_filters(&new_query, &self.config.filters);
-        let mut filter = storage::parse_filter_query(&expanded);
-        filter.invalid_tokens.extend(unknown_filters);
-
-        if !filter.invalid_tokens.is_empty() {
-            self.set_error(format!(
-                "Unknown filter: {}",
-                filter.invalid_tokens.join(", ")
-            ));
+        let filter = storage::parse_filter_query(&new_query, &self.config.filters);
+        if let Some(message) = filter.error_message() {
+            self.set_error(message);
             return Ok(());
         }
 
@@ -57,12 +57,21 @@ impl HintContext {
     }
 
     fn compute_tag_hints(input: &str, journal_tags: &[String]) -> Self {
-        if input.ends_with(' ') {
+        // Hint the term being typed, which may follow `(`, `-(` or `|` directly
+        let start = input
+            .rfind(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | '|'))
+            .map_or(0, |i| i + 1);
+        let current_token = &input[start..];
+
+        if current_token.is_empty() {
+            if input.ends_with('(') {
+                return Self::GuidanceMessage {
+                    message: "Type to search, or use ! @ # $ - for filters",
+                };
+            }
             return Self::Inactive;
         }
 
-        let current_token = input.split_whitespace().last().unwrap_or("");
-
         if let Some(tag_prefix) = current_token.strip_prefix('#')
             && let Some((prefix, matches)) = Self::match_tags(tag_prefix, journal_tags)
         {
@@ -152,12 +161,21 @@ impl HintContext {
             };
         }
 
-        if input.ends_with(' ') {
+        // Hint the term being typed, which may follow `(`, `-(` or `|` directly
+        let start = input
+            .rfind(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | '|'))
+            .map_or(0, |i| i + 1);
+        let current_token = &input[start..];
+
+        if current_token.is_empty() {
+            if input.ends_with('(') {
+                return Self::GuidanceMessage {
+                    message: "Type to search, or use ! @ # $ - for filters",
+                };
+            }
             return Self::Inactive;
         }
 
-        let current_token = input.split_whitespace().last().unwrap_or("");
-
         if let Some(neg_suffix) = current_token.strip_prefix('-') {
             let inner = Self::compute_filter_token(neg_suffix, journal_tags, saved_filters);
             if matches!(inner, Self::Inactive) && neg_suffix.is_empty() {
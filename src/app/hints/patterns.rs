@@ -47,6 +47,18 @@ fn is_valid_pattern_prefix(input: &str, regex: &Regex, pattern_str: &str) -> boo
         return is_valid_d_prefix(input);
     }
 
+    if pattern_str.contains("(days?") {
+        return is_valid_every_segments_prefix(input, &[is_count, |s, p| one_of(s, UNITS, p)]);
+    }
+
+    if pattern_str.contains("(1st") {
+        return is_valid_every_segments_prefix(input, &[|s, p| one_of(s, NTHS, p), is_weekday]);
+    }
+
+    if pattern_str.contains("(jan") {
+        return is_valid_every_segments_prefix(input, &[is_month, is_day_of_month]);
+    }
+
     if pattern_str.contains("every-") && pattern_str.contains("[1-9]") {
         return is_valid_every_number_prefix(input);
     }
@@ -78,6 +90,78 @@ fn is_valid_every_number_prefix(input: &str) -> bool {
     })
 }
 
+const UNITS: &[&str] = &["day", "days", "week", "weeks", "month", "months"];
+const NTHS: &[&str] = &["1st", "2nd", "3rd", "4th", "5th", "last"];
+const WEEKDAYS: &[&str] = &[
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+const MONTHS: &[&str] = &[
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// Segment check: `partial` is true for the segment still being typed.
+type SegmentCheck = fn(&str, bool) -> bool;
+
+/// True if `input` could still grow into `every-<seg>-<seg>...`, where every
+/// segment but the one being typed must already be complete.
+fn is_valid_every_segments_prefix(input: &str, segments: &[SegmentCheck]) -> bool {
+    let Some(rest) = input.strip_prefix("every-") else {
+        return "every-".starts_with(input);
+    };
+    let parts: Vec<&str> = rest.split('-').collect();
+    parts.len() <= segments.len()
+        && parts
+            .iter()
+            .zip(segments)
+            .enumerate()
+            .all(|(i, (part, check))| check(part, i == parts.len() - 1))
+}
+
+fn one_of(s: &str, words: &[&str], partial: bool) -> bool {
+    words
+        .iter()
+        .any(|w| if partial { w.starts_with(s) } else { *w == s })
+}
+
+fn is_count(s: &str, partial: bool) -> bool {
+    (partial && s.is_empty())
+        || (s.len() <= 2 && !s.starts_with('0') && s.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn is_weekday(s: &str, partial: bool) -> bool {
+    // Abbreviations are complete segments too
+    WEEKDAYS
+        .iter()
+        .any(|w| w.starts_with(s) && (partial || s.len() >= 3))
+}
+
+fn is_month(s: &str, partial: bool) -> bool {
+    MONTHS
+        .iter()
+        .any(|m| m.starts_with(s) && (partial || s.len() >= 3))
+}
+
+fn is_day_of_month(s: &str, partial: bool) -> bool {
+    (partial && s.is_empty()) || s.parse::<u32>().is_ok_and(|d| (1..=31).contains(&d))
+}
+
 #[allow(dead_code)]
 pub(super) fn compute_date_completion(input: &str, dv: &DateValue) -> Option<String> {
     let input_lower = input.to_lowercase();
@@ -12,7 +12,7 @@ impl App {
     fn reset_journal_view(&mut self) -> io::Result<()> {
         self.reset_daily_view(Local::now().date_naive())?;
         self.refresh_calendar_cache();
-        self.invalidate_agenda_cache();
+        self.invalidate_sidebar_caches();
         self.trigger_calendar_fetch();
         Ok(())
     }
@@ -1,6 +1,7 @@
 pub mod actions;
 mod calendar;
 mod command;
+mod conflict;
 mod content;
 mod date_picker;
 mod edit_mode;
@@ -9,33 +10,41 @@ mod filter_ops;
 pub mod hints;
 mod journal;
 mod navigation;
+mod outline;
 mod palette;
 mod reorder;
 mod selection_ops;
 mod tag_ops;
 
 pub use entry_ops::{DeleteTarget, EntryLocation, TagRemovalTarget, ToggleTarget, YankTarget};
+pub use filter_ops::FilterRow;
 pub use hints::{HintContext, HintItem, HintMode};
 
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::io;
-use std::path::Path;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
 
 use chrono::{Local, NaiveDate};
 
 use tokio::runtime::Handle;
 use tokio::sync::mpsc;
 
-use crate::calendar::{CalendarStore, fetch_all_calendars, get_visible_calendar_ids, update_store};
+use crate::calendar::{
+    CalendarStore, get_visible_calendar_ids, load_cached_calendars, refresh_calendars, update_store,
+};
 use crate::ui::agenda_widget::{AgendaCache, collect_agenda_cache};
 
 use self::calendar::CalendarState;
 
-use crate::config::{Config, SidebarDefault};
+use crate::config::{Config, SidebarDefault, get_calendar_cache_dir};
 use crate::cursor::CursorBuffer;
 use crate::dispatch::Keymap;
 use crate::storage::{
-    self, Entry, EntryType, JournalContext, JournalSlot, Line, ProjectRegistry, RawEntry,
+    self, Entry, EntryType, FilterGroup, JournalContext, JournalSlot, JournalStore, Line,
+    ProjectRegistry, RawEntry, SearchTerm, TaskState,
 };
 
 pub const DATE_SUFFIX_WIDTH: usize = " (MM/DD)".len();
@@ -77,6 +86,14 @@ pub struct FilterState {
     pub entries: Vec<Entry>,
     pub selected: usize,
     pub scroll_offset: usize,
+    /// Grouping from the query's `group:` modifier
+    pub group: Option<FilterGroup>,
+    /// Labels of groups whose entries are hidden behind their header
+    pub collapsed: HashSet<String>,
+    /// Text terms of the query, highlighted in results
+    pub highlights: Vec<SearchTerm>,
+    /// Byte spans of query terms that failed to parse, marked in the heading
+    pub invalid_spans: Vec<Range<usize>>,
 }
 
 /// Which palette is currently active
@@ -180,10 +197,26 @@ impl SelectionState {
 /// Information about a tag in the journal
 #[derive(Clone, Debug)]
 pub struct TagInfo {
+    /// Full path of the tag, such as `work/frontend`
     pub name: String,
+    /// Occurrences of the tag and the tags nested under it
     pub count: usize,
 }
 
+impl TagInfo {
+    /// Nesting level: 0 for top-level tags
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        self.name.matches('/').count()
+    }
+
+    /// Last segment of the path, shown under its parent in the tag tree
+    #[must_use]
+    pub fn leaf(&self) -> &str {
+        self.name.rsplit('/').next().unwrap_or(&self.name)
+    }
+}
+
 /// Which view is currently active and its state
 #[derive(Clone)]
 pub enum ViewMode {
@@ -280,12 +313,30 @@ pub enum ConfirmContext {
     CreateProjectJournal,
     DeleteTag(String),
     DeleteTagFromCompleted(String),
+    RepairJournal(usize),
+}
+
+/// Overlapping edits found while saving, awaiting a mine/theirs/both decision
+#[derive(Clone, Debug)]
+pub struct ConflictState {
+    /// Journal the conflict belongs to, in case the active journal changes meanwhile
+    pub path: PathBuf,
+    pub conflict: storage::DayConflict,
+}
+
+/// What the quick date picker does with the chosen date
+#[derive(Clone, Debug)]
+pub enum DatePickerPurpose {
+    GoTo,
+    /// Move a single recurring occurrence, shown on the current day
+    MoveOccurrence(Entry),
 }
 
 /// State for the quick date picker overlay
 #[derive(Clone, Debug)]
 pub struct DatePickerState {
     pub buffer: CursorBuffer,
+    pub purpose: DatePickerPurpose,
 }
 
 /// What keyboard handler to use
@@ -296,6 +347,7 @@ pub enum InputMode {
     Reorder,
     Selection(SelectionState),
     Confirm(ConfirmContext),
+    Conflict(ConflictState),
     CommandPalette(CommandPaletteState),
     FilterPrompt,
     DatePicker(DatePickerState),
@@ -313,6 +365,7 @@ pub enum InsertPosition {
 pub enum SidebarType {
     Calendar,
     Agenda,
+    Habits,
 }
 
 /// Status message with error flag for styling
@@ -344,6 +397,8 @@ pub struct App {
     pub current_date: NaiveDate,
     pub last_daily_date: NaiveDate,
     pub lines: Vec<Line>,
+    /// Current day as last read from or written to disk, used as the merge base on save
+    pub base_lines: Vec<Line>,
     pub view: ViewMode,
     pub entry_indices: Vec<usize>,
     pub input_mode: InputMode,
@@ -354,6 +409,8 @@ pub struct App {
     pub last_filter_query: Option<String>,
     pub config: Config,
     pub journal_context: JournalContext,
+    /// Backend the journals are read from and written to
+    pub store: Arc<dyn JournalStore>,
     pub in_git_repo: bool,
     pub hide_completed: bool,
     pub hint_state: HintContext,
@@ -365,9 +422,17 @@ pub struct App {
     pub calendar_state: CalendarState,
     pub active_sidebar: Option<SidebarType>,
     pub agenda_cache: Option<AgendaCache>,
+    pub habit_cache: Option<Vec<storage::Habit>>,
     pub runtime_handle: Option<Handle>,
-    pub calendar_rx: Option<mpsc::Receiver<crate::calendar::CalendarFetchResult>>,
-    pub calendar_tx: Option<mpsc::Sender<crate::calendar::CalendarFetchResult>>,
+    /// Fetch results tagged with the generation of the fetch that produced them
+    pub calendar_rx: Option<mpsc::Receiver<(u64, crate::calendar::CalendarFetchResult)>>,
+    pub calendar_tx: Option<mpsc::Sender<(u64, crate::calendar::CalendarFetchResult)>>,
+    /// Generation of the latest fetch; results from older fetches are dropped
+    pub calendar_generation: u64,
+    /// When each calendar was last sent for fetching, for refresh intervals
+    pub calendar_fetched_at: HashMap<String, Instant>,
+    /// Fetches spawned but not yet received; drives the agenda spinner
+    pub calendar_fetches_in_flight: usize,
     pub(crate) surface: crate::ui::surface::Surface,
     /// Last known modification time of the journal file (for external change detection)
     last_file_mtime: Option<std::time::SystemTime>,
@@ -398,11 +463,32 @@ impl App {
         journal_context: JournalContext,
         runtime_handle: Option<Handle>,
         surface: crate::ui::surface::Surface,
+    ) -> io::Result<Self> {
+        let store = config.journal_store();
+        Self::new_with_store(
+            config,
+            date,
+            journal_context,
+            store,
+            runtime_handle,
+            surface,
+        )
+    }
+
+    /// Creates a new App backed by the given journal store
+    pub fn new_with_store(
+        config: Config,
+        date: NaiveDate,
+        journal_context: JournalContext,
+        store: Arc<dyn JournalStore>,
+        runtime_handle: Option<Handle>,
+        surface: crate::ui::surface::Surface,
     ) -> io::Result<Self> {
         let path = journal_context.active_path().to_path_buf();
-        let lines = storage::load_day_lines(date, &path)?;
+        let lines = store.load_day_lines(date, &path)?;
         let entry_indices = Self::compute_entry_indices(&lines);
-        let projected_entries = storage::collect_projected_entries_for_date(date, &path)?;
+        let projected_entries =
+            storage::collect_projected_entries_for_date(store.as_ref(), date, &path)?;
         let in_git_repo = storage::find_git_root().is_some();
         let cached_journal_tags = Vec::new();
         let hide_completed = config.hide_completed;
@@ -419,11 +505,12 @@ impl App {
             (None, None)
         };
 
-        let last_file_mtime = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+        let last_file_mtime = store.modified(date, &path);
 
         let mut app = Self {
             current_date: date,
             last_daily_date: date,
+            base_lines: lines.clone(),
             lines,
             view: ViewMode::Daily(DailyState::new(entry_indices.len(), projected_entries)),
             entry_indices,
@@ -435,6 +522,7 @@ impl App {
             last_filter_query: None,
             config,
             journal_context,
+            store,
             in_git_repo,
             hide_completed,
             hint_state: HintContext::Inactive,
@@ -448,11 +536,16 @@ impl App {
                 SidebarDefault::None => None,
                 SidebarDefault::Agenda => Some(SidebarType::Agenda),
                 SidebarDefault::Calendar => Some(SidebarType::Calendar),
+                SidebarDefault::Habits => Some(SidebarType::Habits),
             },
             agenda_cache: None,
+            habit_cache: None,
             runtime_handle,
             calendar_rx,
             calendar_tx,
+            calendar_generation: 0,
+            calendar_fetched_at: HashMap::new(),
+            calendar_fetches_in_flight: 0,
             surface,
             last_file_mtime,
         };
@@ -479,19 +572,43 @@ impl App {
         self.journal_context.active_path()
     }
 
+    /// Returns the journal an entry lives in: its source journal for
+    /// cross-journal filter results, otherwise the active one
+    #[must_use]
+    pub fn entry_path<'a>(&'a self, entry: &'a Entry) -> &'a Path {
+        entry
+            .journal
+            .as_ref()
+            .map_or_else(|| self.active_path(), |journal| journal.path.as_path())
+    }
+
+    /// Returns the source journal's name if an entry lives outside the active journal
+    #[must_use]
+    pub fn foreign_journal<'a>(&self, entry: &'a Entry) -> Option<&'a str> {
+        entry
+            .journal
+            .as_ref()
+            .filter(|journal| journal.path != self.active_path())
+            .map(|journal| journal.name.as_str())
+    }
+
+    /// Whether an entry belongs to the day loaded in the daily view
+    #[must_use]
+    pub fn is_on_current_day(&self, entry: &Entry) -> bool {
+        entry.source_date == self.current_date && self.foreign_journal(entry).is_none()
+    }
+
     /// Returns the active journal slot
     #[must_use]
     pub fn active_journal(&self) -> JournalSlot {
         self.journal_context.active_slot()
     }
 
-    pub fn trigger_calendar_fetch(&mut self) {
-        let Some(ref handle) = self.runtime_handle else {
-            return;
-        };
-
-        if !self.config.has_calendars() {
-            return;
+    /// Calendars visible in the active journal, or None when there is
+    /// nothing to fetch. Clears the store when no calendar is visible.
+    fn calendar_fetch_targets(&mut self) -> Option<Vec<String>> {
+        if self.runtime_handle.is_none() || !self.config.has_calendars() {
+            return None;
         }
 
         let project = self.get_current_project_info();
@@ -500,43 +617,137 @@ impl App {
 
         if visible_ids.is_empty() {
             self.calendar_store.clear();
+            return None;
+        }
+        Some(visible_ids)
+    }
+
+    pub fn trigger_calendar_fetch(&mut self) {
+        let Some(visible_ids) = self.calendar_fetch_targets() else {
+            return;
+        };
+
+        // Show cached events right away; the fetch below replaces them
+        let cached = load_cached_calendars(&self.config, &visible_ids, &get_calendar_cache_dir());
+        update_store(&mut self.calendar_store, cached);
+        self.refresh_calendar_cache();
+        self.invalidate_sidebar_caches();
+
+        self.spawn_calendar_fetch(visible_ids.clone(), visible_ids);
+    }
+
+    /// Fetches every visible calendar now, regardless of refresh intervals,
+    /// superseding any fetch still running. Returns false if there are no
+    /// calendars to refresh.
+    pub fn refresh_calendars_now(&mut self) -> bool {
+        let Some(visible_ids) = self.calendar_fetch_targets() else {
+            return false;
+        };
+        self.spawn_calendar_fetch(visible_ids.clone(), visible_ids);
+        true
+    }
+
+    /// Fetches the visible calendars whose refresh interval has elapsed.
+    /// Skipped while a fetch is still running.
+    pub fn tick_calendar_refresh(&mut self) {
+        if self.calendar_fetches_in_flight > 0 {
             return;
         }
+        // The store already lists the active journal's calendars, which
+        // saves reloading the project registry every tick
+        let visible_ids: Vec<String> = self
+            .calendar_store
+            .sync_statuses
+            .iter()
+            .map(|status| status.calendar_id.clone())
+            .collect();
 
-        let config = self.config.clone();
-        let Some(tx) = self.calendar_tx.clone() else {
+        let now = Instant::now();
+        let due_ids: Vec<String> = visible_ids
+            .iter()
+            .filter(|id| {
+                self.config
+                    .refresh_interval_for(id)
+                    .is_some_and(|interval| {
+                        self.calendar_fetched_at
+                            .get(*id)
+                            .is_none_or(|fetched| now.duration_since(*fetched) >= interval)
+                    })
+            })
+            .cloned()
+            .collect();
+
+        if !due_ids.is_empty() {
+            self.spawn_calendar_fetch(visible_ids, due_ids);
+        }
+    }
+
+    fn spawn_calendar_fetch(&mut self, visible_ids: Vec<String>, due_ids: Vec<String>) {
+        let (Some(handle), Some(tx)) = (self.runtime_handle.clone(), self.calendar_tx.clone())
+        else {
             return;
         };
 
+        let now = Instant::now();
+        for id in &due_ids {
+            self.calendar_fetched_at.insert(id.clone(), now);
+        }
+        self.calendar_fetches_in_flight += 1;
+        self.calendar_generation += 1;
+        let generation = self.calendar_generation;
+
+        let config = self.config.clone();
+        let previous = self.calendar_store.sync_statuses.clone();
+        let cache_dir = get_calendar_cache_dir();
         handle.spawn(async move {
-            let result = fetch_all_calendars(&config, &visible_ids).await;
+            let result =
+                refresh_calendars(&config, &visible_ids, &due_ids, &previous, &cache_dir).await;
             // Receiver dropped is expected on app shutdown - silent discard is intentional
-            drop(tx.send(result).await);
+            drop(tx.send((generation, result)).await);
         });
     }
 
+    /// Applies a finished fetch. A result from a fetch that was superseded
+    /// (by a manual refresh or a journal switch) is dropped, so a slow older
+    /// fetch can't overwrite newer events and statuses.
     pub fn poll_calendar_results(&mut self) {
         let Some(ref mut rx) = self.calendar_rx else {
             return;
         };
-        if let Ok(result) = rx.try_recv() {
+        if let Ok((generation, result)) = rx.try_recv() {
+            self.calendar_fetches_in_flight = self.calendar_fetches_in_flight.saturating_sub(1);
+            if generation != self.calendar_generation {
+                return;
+            }
             update_store(&mut self.calendar_store, result);
             self.refresh_calendar_cache();
-            self.invalidate_agenda_cache();
+            self.invalidate_sidebar_caches();
         }
     }
 
     pub fn ensure_agenda_cache(&mut self) {
         if self.agenda_cache.is_none() {
             self.agenda_cache = Some(collect_agenda_cache(
+                self.store.as_ref(),
                 &self.calendar_store,
                 self.active_path(),
             ));
         }
     }
 
-    pub fn invalidate_agenda_cache(&mut self) {
+    pub fn ensure_habit_cache(&mut self) {
+        if self.habit_cache.is_none() {
+            let today = Local::now().date_naive();
+            self.habit_cache = Some(
+                storage::collect_habits(self.store.as_ref(), self.active_path(), today)
+                    .unwrap_or_default(),
+            );
+        }
+    }
+
+    pub fn invalidate_sidebar_caches(&mut self) {
         self.agenda_cache = None;
+        self.habit_cache = None;
     }
 
     /// Returns the number of calendar events for the current date.
@@ -655,34 +866,62 @@ impl App {
     }
 
     /// Saves current day's lines to storage, displaying any error as a status message.
+    /// If the day changed on disk since it was loaded, non-overlapping edits are merged
+    /// and overlapping ones open the conflict overlay.
     pub fn save(&mut self) {
-        if let Err(e) = storage::save_day_lines(self.current_date, self.active_path(), &self.lines)
-        {
-            self.set_status(format!("Failed to save: {e}"));
+        let path = self.active_path().to_path_buf();
+        match storage::save_day_lines_merged(
+            self.store.as_ref(),
+            self.current_date,
+            &path,
+            &self.base_lines,
+            &self.lines,
+        ) {
+            Ok(storage::SaveOutcome::Saved(stored)) => self.base_lines = stored,
+            Ok(storage::SaveOutcome::Merged(stored)) => {
+                self.apply_merged_lines(stored);
+                self.set_status("Merged changes made outside caliber");
+            }
+            Ok(storage::SaveOutcome::Conflict(conflict)) => {
+                self.input_mode = InputMode::Conflict(ConflictState { path, conflict });
+            }
+            Err(e) => self.set_error(format!("Failed to save: {e}")),
         }
         self.update_file_mtime();
-        self.invalidate_agenda_cache();
+        self.invalidate_sidebar_caches();
         self.refresh_calendar_cache();
     }
 
+    /// Replaces the current day with lines loaded from disk, resetting the merge base.
+    pub(crate) fn set_loaded_lines(&mut self, lines: Vec<Line>) {
+        self.base_lines = lines.clone();
+        self.lines = lines;
+        self.entry_indices = Self::compute_entry_indices(&self.lines);
+    }
+
+    /// Returns true if the current day has in-memory changes not yet written to disk.
+    #[must_use]
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.lines != self.base_lines
+    }
+
     /// Updates the tracked file modification time to the current value.
     fn update_file_mtime(&mut self) {
-        self.last_file_mtime = std::fs::metadata(self.active_path())
-            .and_then(|m| m.modified())
-            .ok();
+        self.last_file_mtime = self.store.modified(self.current_date, self.active_path());
     }
 
     /// Checks if the journal file was modified externally and reloads if so.
     /// Returns true if a reload occurred.
     pub fn check_external_changes(&mut self) -> bool {
-        // Don't reload while editing - user might lose work
-        if matches!(self.input_mode, InputMode::Edit(_)) {
+        // Don't reload while editing or resolving a conflict - user might lose work
+        if matches!(
+            self.input_mode,
+            InputMode::Edit(_) | InputMode::Reorder | InputMode::Conflict(_)
+        ) {
             return false;
         }
 
-        let current_mtime = std::fs::metadata(self.active_path())
-            .and_then(|m| m.modified())
-            .ok();
+        let current_mtime = self.store.modified(self.current_date, self.active_path());
 
         // Check if file was modified externally
         let was_modified = match (self.last_file_mtime, current_mtime) {
@@ -692,7 +931,12 @@ impl App {
         };
 
         if was_modified {
-            self.reload_current_view();
+            if self.has_unsaved_changes() {
+                // Merge rather than reload so unsaved lines aren't dropped
+                self.save();
+            } else {
+                self.reload_current_view();
+            }
             self.last_file_mtime = current_mtime;
             true
         } else {
@@ -704,10 +948,13 @@ impl App {
     fn reload_current_view(&mut self) {
         match &self.view {
             ViewMode::Daily(_) => {
-                if let Ok(lines) = storage::load_day_lines(self.current_date, self.active_path()) {
-                    self.lines = lines;
-                    self.entry_indices = Self::compute_entry_indices(&self.lines);
+                if let Ok(lines) = self
+                    .store
+                    .load_day_lines(self.current_date, self.active_path())
+                {
+                    self.set_loaded_lines(lines);
                     if let Ok(projected) = storage::collect_projected_entries_for_date(
+                        self.store.as_ref(),
                         self.current_date,
                         self.active_path(),
                     ) && let ViewMode::Daily(state) = &mut self.view
@@ -722,7 +969,7 @@ impl App {
                 let _ = self.refresh_filter();
             }
         }
-        self.invalidate_agenda_cache();
+        self.invalidate_sidebar_caches();
         self.refresh_calendar_cache();
     }
 
@@ -774,26 +1021,38 @@ impl App {
             let Line::Entry(entry) = line else {
                 return tidy_order.len();
             };
-            for (i, type_name) in tidy_order.iter().enumerate() {
-                match (type_name.as_str(), &entry.entry_type) {
-                    ("completed", EntryType::Task { completed: true }) => return i,
-                    ("uncompleted", EntryType::Task { completed: false }) => return i,
-                    ("notes", EntryType::Note) => return i,
-                    ("events", EntryType::Event) => return i,
-                    _ => {}
-                }
-            }
-            tidy_order.len()
+            // Task states without their own slot sort with completed/uncompleted
+            let names: &[&str] = match entry.entry_type {
+                EntryType::Task { state } => match state {
+                    TaskState::Open => &["uncompleted"],
+                    TaskState::InProgress => &["in_progress", "uncompleted"],
+                    TaskState::Done => &["completed"],
+                    TaskState::Cancelled => &["cancelled", "completed"],
+                    TaskState::Migrated => &["migrated", "completed"],
+                },
+                EntryType::Note => &["notes"],
+                EntryType::Event => &["events"],
+            };
+            names
+                .iter()
+                .find_map(|name| tidy_order.iter().position(|t| t == name))
+                .unwrap_or(tidy_order.len())
         };
 
-        let mut entries: Vec<Line> = entry_positions
-            .iter()
-            .map(|&i| self.lines[i].clone())
-            .collect();
+        // Sort top-level entries, keeping each one's sub-entries beneath it
+        let mut blocks: Vec<Vec<Line>> = Vec::new();
+        for &i in &entry_positions {
+            let line = self.lines[i].clone();
+            let is_child = matches!(&line, Line::Entry(entry) if entry.depth > 0);
+            match blocks.last_mut() {
+                Some(block) if is_child => block.push(line),
+                _ => blocks.push(vec![line]),
+            }
+        }
 
-        entries.sort_by_key(|line| get_priority(line));
+        blocks.sort_by_key(|block| get_priority(&block[0]));
 
-        for (pos, entry) in entry_positions.iter().zip(entries.into_iter()) {
+        for (pos, entry) in entry_positions.iter().zip(blocks.into_iter().flatten()) {
             self.lines[*pos] = entry;
         }
 
@@ -802,8 +1061,10 @@ impl App {
     }
 
     pub(crate) fn reload_current_day(&mut self) -> io::Result<()> {
-        self.lines = storage::load_day_lines(self.current_date, self.active_path())?;
-        self.entry_indices = Self::compute_entry_indices(&self.lines);
+        let lines = self
+            .store
+            .load_day_lines(self.current_date, self.active_path())?;
+        self.set_loaded_lines(lines);
         Ok(())
     }
 
@@ -867,20 +1128,28 @@ impl App {
     }
 
     fn collect_all_tags(&self) -> io::Result<Vec<TagInfo>> {
-        let journal = storage::load_journal(self.active_path())?;
         let mut tag_counts: HashMap<String, usize> = HashMap::new();
 
-        for cap in storage::TAG_REGEX.captures_iter(&journal) {
-            let tag = cap[1].to_lowercase();
-            *tag_counts.entry(tag).or_insert(0) += 1;
-        }
+        self.store.visit_days(self.active_path(), &mut |_, lines| {
+            let content = storage::serialize_lines(lines);
+            for cap in storage::TAG_REGEX.captures_iter(&content) {
+                let tag = cap[1].to_lowercase();
+                // Count the tag towards each of its ancestors too, so parents
+                // that are never used on their own still appear in the tree
+                for (end, _) in tag.match_indices('/') {
+                    *tag_counts.entry(tag[..end].to_string()).or_insert(0) += 1;
+                }
+                *tag_counts.entry(tag).or_insert(0) += 1;
+            }
+        })?;
 
         let mut tags: Vec<TagInfo> = tag_counts
             .into_iter()
             .map(|(name, count)| TagInfo { name, count })
             .collect();
 
-        tags.sort_by(|a, b| a.name.cmp(&b.name));
+        // Segment-wise so children follow their parent (`work`, `work/ui`, `work-log`)
+        tags.sort_by(|a, b| a.name.split('/').cmp(b.name.split('/')));
 
         Ok(tags)
     }
@@ -2,19 +2,54 @@ use std::io;
 
 use chrono::{Days, Local, Months, NaiveDate};
 
-use crate::storage::{self, Entry, EntryType, Line, RawEntry};
+use crate::storage::{self, Entry, Line, RawEntry};
 
 use super::{App, DailyState, InputMode, SelectedItem, ViewMode};
 
 impl App {
     #[must_use]
     pub fn should_show_raw_entry(&self, entry: &RawEntry) -> bool {
-        !self.hide_completed || !matches!(entry.entry_type, EntryType::Task { completed: true })
+        !self.hide_completed || !entry.entry_type.is_closed_task()
     }
 
     #[must_use]
     pub fn should_show_entry(&self, entry: &Entry) -> bool {
-        !self.hide_completed || !matches!(entry.entry_type, EntryType::Task { completed: true })
+        !self.hide_completed || !entry.entry_type.is_closed_task()
+    }
+
+    /// Returns true if an entry of the current day sits under a collapsed parent.
+    #[must_use]
+    pub fn is_under_collapsed(&self, line_idx: usize) -> bool {
+        let Some(Line::Entry(entry)) = self.lines.get(line_idx) else {
+            return false;
+        };
+        let mut depth = entry.depth;
+        for line in self.lines[..line_idx].iter().rev() {
+            if depth == 0 {
+                break;
+            }
+            if let Line::Entry(ancestor) = line
+                && ancestor.depth < depth
+            {
+                if ancestor.collapsed {
+                    return true;
+                }
+                depth = ancestor.depth;
+            }
+        }
+        false
+    }
+
+    /// Whether the line of the current day is shown in the daily view, taking
+    /// hidden completed tasks and collapsed parents into account.
+    #[must_use]
+    pub fn is_daily_line_visible(&self, line_idx: usize) -> bool {
+        match &self.lines[line_idx] {
+            Line::Entry(raw_entry) => {
+                self.should_show_raw_entry(raw_entry) && !self.is_under_collapsed(line_idx)
+            }
+            Line::Raw(_) => true,
+        }
     }
 
     #[must_use]
@@ -44,18 +79,9 @@ impl App {
 
     #[must_use]
     pub fn visible_entries_before(&self, entry_index: usize) -> usize {
-        if !self.hide_completed {
-            return entry_index;
-        }
         self.entry_indices[..entry_index]
             .iter()
-            .filter(|&&i| {
-                if let Line::Entry(raw_entry) = &self.lines[i] {
-                    self.should_show_raw_entry(raw_entry)
-                } else {
-                    true
-                }
-            })
+            .filter(|&&i| self.is_daily_line_visible(i))
             .count()
     }
 
@@ -75,10 +101,16 @@ impl App {
     }
 
     pub fn move_up(&mut self) {
+        if self.step_folded_filter(|selectable, at| at.checked_sub(1).map(|i| selectable[i])) {
+            return;
+        }
         self.view.move_up();
     }
 
     pub fn move_down(&mut self) {
+        if self.step_folded_filter(|selectable, at| selectable.get(at + 1).copied()) {
+            return;
+        }
         let total = self.visible_entry_count();
         self.view.move_down(total);
     }
@@ -88,10 +120,34 @@ impl App {
     }
 
     pub fn jump_to_last(&mut self) {
+        if self.step_folded_filter(|selectable, _| selectable.last().copied()) {
+            return;
+        }
         let total = self.visible_entry_count();
         self.view.jump_to_last(total);
     }
 
+    /// Moves the filter cursor among the results that aren't folded away,
+    /// given the selectable indices and the cursor's position among them.
+    /// Returns false when no group is collapsed and plain movement applies.
+    fn step_folded_filter(&mut self, step: impl Fn(&[usize], usize) -> Option<usize>) -> bool {
+        let ViewMode::Filter(state) = &mut self.view else {
+            return false;
+        };
+        if state.collapsed.is_empty() {
+            return false;
+        }
+        let selectable = state.selectable_indices();
+        let at = selectable
+            .iter()
+            .rposition(|&idx| idx <= state.selected)
+            .unwrap_or(0);
+        if let Some(idx) = step(&selectable, at) {
+            state.selected = idx;
+        }
+        true
+    }
+
     pub fn toggle_hide_completed(&mut self) {
         let ViewMode::Daily(state) = &mut self.view else {
             self.hide_completed = !self.hide_completed;
@@ -131,7 +187,7 @@ impl App {
 
         // Check projected entries
         for entry in &state.projected_entries {
-            let is_completed = matches!(entry.entry_type, EntryType::Task { completed: true });
+            let is_completed = entry.entry_type.is_closed_task();
             if !is_completed {
                 if visible_idx == old_visible_idx {
                     return actual_idx;
@@ -144,8 +200,10 @@ impl App {
         // Check regular entries
         for &line_idx in &self.entry_indices {
             if let Line::Entry(raw_entry) = &self.lines[line_idx] {
-                let is_completed =
-                    matches!(raw_entry.entry_type, EntryType::Task { completed: true });
+                if self.is_under_collapsed(line_idx) {
+                    continue;
+                }
+                let is_completed = raw_entry.entry_type.is_closed_task();
                 if !is_completed {
                     if visible_idx == old_visible_idx {
                         return actual_idx;
@@ -195,6 +253,9 @@ impl App {
         // Check regular entries
         for &line_idx in &self.entry_indices {
             if let Line::Entry(raw_entry) = &self.lines[line_idx] {
+                if self.is_under_collapsed(line_idx) {
+                    continue;
+                }
                 let is_visible_now = self.should_show_raw_entry(raw_entry);
 
                 if actual_idx == old_visible_idx {
@@ -255,7 +316,7 @@ impl App {
 
                 for (actual_idx, &line_idx) in self.entry_indices.iter().enumerate() {
                     if let Line::Entry(raw_entry) = &self.lines[line_idx] {
-                        if !self.should_show_raw_entry(raw_entry) {
+                        if !self.is_daily_line_visible(line_idx) {
                             continue;
                         }
                         if visible_idx == state.selected {
@@ -271,6 +332,8 @@ impl App {
 
                 SelectedItem::None
             }
+            // A collapsed group's header stands for its results but acts on none
+            ViewMode::Filter(state) if state.is_folded(state.selected) => SelectedItem::None,
             ViewMode::Filter(state) => match state.entries.get(state.selected) {
                 Some(entry) => SelectedItem::Filter {
                     index: state.selected,
@@ -285,28 +348,9 @@ impl App {
     pub fn visible_entry_count(&self) -> usize {
         match &self.view {
             ViewMode::Filter(state) => state.entries.len(),
-            ViewMode::Daily(state) => {
-                if !self.hide_completed {
-                    return state.projected_entries.len() + self.entry_indices.len();
-                }
-
-                let visible_projected = state
-                    .projected_entries
-                    .iter()
-                    .filter(|e| self.should_show_entry(e))
-                    .count();
-                let visible_regular = self
-                    .entry_indices
-                    .iter()
-                    .filter(|&&i| {
-                        if let Line::Entry(raw_entry) = &self.lines[i] {
-                            self.should_show_raw_entry(raw_entry)
-                        } else {
-                            true
-                        }
-                    })
-                    .count();
-                visible_projected + visible_regular
+            ViewMode::Daily(_) => {
+                self.visible_projected_count()
+                    + self.visible_entries_before(self.entry_indices.len())
             }
         }
     }
@@ -326,14 +370,14 @@ impl App {
         let hidden_projected = state
             .projected_entries
             .iter()
-            .filter(|e| matches!(e.entry_type, EntryType::Task { completed: true }))
+            .filter(|e| e.entry_type.is_closed_task())
             .count();
         let hidden_regular = self
             .entry_indices
             .iter()
             .filter(|&&i| {
                 if let Line::Entry(raw_entry) = &self.lines[i] {
-                    matches!(raw_entry.entry_type, EntryType::Task { completed: true })
+                    raw_entry.entry_type.is_closed_task()
                 } else {
                     false
                 }
@@ -358,36 +402,25 @@ impl App {
             state.projected_entries.len()
         };
 
-        let visible_before = self.entry_indices[..actual_entry_idx]
-            .iter()
-            .filter(|&&i| {
-                if self.hide_completed {
-                    if let Line::Entry(raw_entry) = &self.lines[i] {
-                        self.should_show_raw_entry(raw_entry)
-                    } else {
-                        true
-                    }
-                } else {
-                    true
-                }
-            })
-            .count();
-
-        visible_projected + visible_before
+        visible_projected + self.visible_entries_before(actual_entry_idx)
     }
 
     pub(super) fn load_day(&mut self, date: NaiveDate) -> io::Result<Vec<Entry>> {
         self.current_date = date;
         let path = self.active_path().to_path_buf();
-        self.lines = storage::load_day_lines(date, &path)?;
-        self.entry_indices = Self::compute_entry_indices(&self.lines);
-        storage::collect_projected_entries_for_date(date, &path)
+        let lines = self.store.load_day_lines(date, &path)?;
+        self.set_loaded_lines(lines);
+        self.update_file_mtime();
+        storage::collect_projected_entries_for_date(self.store.as_ref(), date, &path)
     }
 
     pub fn refresh_projected_entries(&mut self) {
-        let projected =
-            storage::collect_projected_entries_for_date(self.current_date, self.active_path())
-                .unwrap_or_default();
+        let projected = storage::collect_projected_entries_for_date(
+            self.store.as_ref(),
+            self.current_date,
+            self.active_path(),
+        )
+        .unwrap_or_default();
         if let ViewMode::Daily(state) = &mut self.view {
             state.projected_entries = projected;
         }
@@ -410,9 +443,12 @@ impl App {
     }
 
     pub(super) fn restore_daily_view(&mut self) {
-        let projected_entries =
-            storage::collect_projected_entries_for_date(self.current_date, self.active_path())
-                .unwrap_or_default();
+        let projected_entries = storage::collect_projected_entries_for_date(
+            self.store.as_ref(),
+            self.current_date,
+            self.active_path(),
+        )
+        .unwrap_or_default();
         self.view = ViewMode::Daily(DailyState::new(self.entry_indices.len(), projected_entries));
         if self.hide_completed {
             self.clamp_selection_to_visible();
@@ -0,0 +1,93 @@
+use std::io;
+
+use crate::storage::{Line, subtree_end};
+
+use super::actions::{DepthTarget, SetDepths};
+use super::{App, SelectedItem};
+
+impl App {
+    /// Nests the selected entry and its sub-entries under the entry above.
+    pub fn indent_current_entry(&mut self) -> io::Result<()> {
+        let Some((index, line_idx, depth)) = self.selected_daily_depth() else {
+            return Ok(());
+        };
+
+        let Some(above) = index.checked_sub(1).and_then(|i| self.get_daily_entry(i)) else {
+            self.set_error("No entry above to nest under");
+            return Ok(());
+        };
+        if depth > above.depth {
+            self.set_error("Entry is already nested under the one above");
+            return Ok(());
+        }
+
+        // Keep the entry in view when its new parent is collapsed
+        let parent_line_idx = self.entry_indices[..index]
+            .iter()
+            .rev()
+            .copied()
+            .find(|&i| matches!(&self.lines[i], Line::Entry(e) if e.depth <= depth));
+        if let Some(i) = parent_line_idx
+            && let Line::Entry(parent) = &mut self.lines[i]
+        {
+            parent.collapsed = false;
+        }
+
+        let targets = self.subtree_depths(line_idx, |d| d + 1);
+        self.execute_action(Box::new(SetDepths::indent(targets)))
+    }
+
+    /// Moves the selected entry and its sub-entries one level out.
+    pub fn outdent_current_entry(&mut self) -> io::Result<()> {
+        let Some((_, line_idx, depth)) = self.selected_daily_depth() else {
+            return Ok(());
+        };
+        if depth == 0 {
+            return Ok(());
+        }
+
+        let targets = self.subtree_depths(line_idx, |d| d - 1);
+        self.execute_action(Box::new(SetDepths::outdent(targets)))
+    }
+
+    /// Shows or hides the sub-entries of the selected entry.
+    pub fn toggle_collapse_current(&mut self) {
+        let Some((_, line_idx, _)) = self.selected_daily_depth() else {
+            return;
+        };
+        if subtree_end(&self.lines, line_idx) == line_idx + 1 {
+            self.set_error("Entry has no sub-entries");
+            return;
+        }
+        if let Line::Entry(raw_entry) = &mut self.lines[line_idx] {
+            raw_entry.collapsed = !raw_entry.collapsed;
+        }
+    }
+
+    fn selected_daily_depth(&mut self) -> Option<(usize, usize, usize)> {
+        match self.get_selected_item() {
+            SelectedItem::Daily {
+                index,
+                line_idx,
+                entry,
+            } => Some((index, line_idx, entry.depth)),
+            SelectedItem::Projected { .. } | SelectedItem::Filter { .. } => {
+                self.set_error("Nesting is only available for entries of the current day");
+                None
+            }
+            SelectedItem::None => None,
+        }
+    }
+
+    fn subtree_depths(&self, line_idx: usize, shift: impl Fn(usize) -> usize) -> Vec<DepthTarget> {
+        (line_idx..subtree_end(&self.lines, line_idx))
+            .filter_map(|i| match &self.lines[i] {
+                Line::Entry(raw_entry) => Some(DepthTarget {
+                    line_idx: i,
+                    depth: shift(raw_entry.depth),
+                }),
+                Line::Raw(_) => None,
+            })
+            .collect()
+    }
+}
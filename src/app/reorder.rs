@@ -1,4 +1,4 @@
-use crate::storage::Line;
+use crate::storage::{Line, subtree_end};
 
 use super::{App, InputMode, SelectedItem, ViewMode};
 
@@ -21,11 +21,11 @@ impl App {
         if !matches!(self.view, ViewMode::Daily(_)) {
             return;
         }
-        self.save();
         if let ViewMode::Daily(state) = &mut self.view {
             state.original_lines = None;
         }
         self.input_mode = InputMode::Normal;
+        self.save();
     }
 
     pub fn cancel_reorder_mode(&mut self) {
@@ -44,49 +44,34 @@ impl App {
         self.input_mode = InputMode::Normal;
     }
 
-    fn is_entry_visible_at(&self, line_idx: usize) -> bool {
-        if !self.hide_completed {
-            return true;
-        }
-        if let Line::Entry(raw_entry) = &self.lines[line_idx] {
-            self.should_show_raw_entry(raw_entry)
+    /// Entry index of the nearest visible sibling before or after `entry_idx`,
+    /// skipping over the sibling's sub-entries. None at the edge of the parent.
+    fn find_sibling(&self, entry_idx: usize, forward: bool) -> Option<usize> {
+        let depth = self.entry_depth(entry_idx);
+        let candidates: Box<dyn Iterator<Item = usize>> = if forward {
+            Box::new(entry_idx + 1..self.entry_indices.len())
         } else {
-            true
-        }
-    }
-
-    pub fn reorder_move_up(&mut self) {
-        let SelectedItem::Daily {
-            index: curr_entry_idx,
-            ..
-        } = self.get_selected_item()
-        else {
-            return;
-        };
-
-        let prev_entry_idx = self.entry_indices[..curr_entry_idx]
-            .iter()
-            .enumerate()
-            .rev()
-            .find(|&(_, &line_idx)| self.is_entry_visible_at(line_idx))
-            .map(|(idx, _)| idx);
-
-        let Some(prev_entry_idx) = prev_entry_idx else {
-            return;
+            Box::new((0..entry_idx).rev())
         };
 
-        let curr_line_idx = self.entry_indices[curr_entry_idx];
-        let prev_line_idx = self.entry_indices[prev_entry_idx];
-        let entry = self.lines.remove(curr_line_idx);
-        self.lines.insert(prev_line_idx, entry);
-        self.entry_indices = Self::compute_entry_indices(&self.lines);
-
-        if let ViewMode::Daily(state) = &mut self.view {
-            state.selected -= 1;
+        for idx in candidates {
+            let other_depth = self.entry_depth(idx);
+            if other_depth < depth {
+                return None;
+            }
+            if other_depth == depth && self.is_daily_line_visible(self.entry_indices[idx]) {
+                return Some(idx);
+            }
         }
+        None
     }
 
-    pub fn reorder_move_down(&mut self) {
+    fn entry_depth(&self, entry_idx: usize) -> usize {
+        self.get_daily_entry(entry_idx).map_or(0, |e| e.depth)
+    }
+
+    /// Moves the selected entry and its sub-entries past the neighbouring sibling.
+    fn reorder_move(&mut self, forward: bool) {
         let SelectedItem::Daily {
             index: curr_entry_idx,
             ..
@@ -95,29 +80,40 @@ impl App {
             return;
         };
 
-        let next_entry_idx = self.entry_indices[curr_entry_idx + 1..]
-            .iter()
-            .enumerate()
-            .find(|&(_, &line_idx)| self.is_entry_visible_at(line_idx))
-            .map(|(offset, _)| curr_entry_idx + 1 + offset);
-
-        let Some(next_entry_idx) = next_entry_idx else {
+        let Some(sibling_entry_idx) = self.find_sibling(curr_entry_idx, forward) else {
             return;
         };
 
         let curr_line_idx = self.entry_indices[curr_entry_idx];
-        let next_line_idx = self.entry_indices[next_entry_idx];
-        let entry = self.lines.remove(curr_line_idx);
-        let insert_pos = if next_line_idx > curr_line_idx {
-            next_line_idx
+        let sibling_line_idx = self.entry_indices[sibling_entry_idx];
+        let curr_end = subtree_end(&self.lines, curr_line_idx);
+        let block: Vec<Line> = self.lines.drain(curr_line_idx..curr_end).collect();
+        let block_len = block.len();
+
+        let insert_pos = if forward {
+            subtree_end(&self.lines, sibling_line_idx - block_len)
         } else {
-            next_line_idx + 1
+            sibling_line_idx
         };
-        self.lines.insert(insert_pos, entry);
+        self.lines.splice(insert_pos..insert_pos, block);
         self.entry_indices = Self::compute_entry_indices(&self.lines);
 
+        let new_entry_idx = self
+            .entry_indices
+            .iter()
+            .position(|&idx| idx == insert_pos)
+            .unwrap_or(curr_entry_idx);
+        let visible_idx = self.actual_to_visible_index(new_entry_idx);
         if let ViewMode::Daily(state) = &mut self.view {
-            state.selected += 1;
+            state.selected = visible_idx;
         }
     }
+
+    pub fn reorder_move_up(&mut self) {
+        self.reorder_move(false);
+    }
+
+    pub fn reorder_move_down(&mut self) {
+        self.reorder_move(true);
+    }
 }
@@ -144,7 +144,7 @@ impl App {
         let mut current_visible = 0;
         for &line_idx in &self.entry_indices {
             if let Line::Entry(raw_entry) = &self.lines[line_idx] {
-                if !self.should_show_raw_entry(raw_entry) {
+                if !self.is_daily_line_visible(line_idx) {
                     continue;
                 }
                 if current_visible == visible_entry_idx {
@@ -396,6 +396,25 @@ impl App {
         self.execute_action(Box::new(action))
     }
 
+    /// Cycle task state on all selected tasks, skipping recurring entries
+    pub fn cycle_selected_task_states(&mut self) -> io::Result<()> {
+        let targets: Vec<_> = self
+            .collect_cycle_targets_from_selected()
+            .into_iter()
+            .filter(|t| {
+                matches!(t.original_type, EntryType::Task { .. })
+                    && !matches!(&t.location, TagRemovalTarget::Projected(e)
+                        if e.source_type == SourceType::Recurring)
+            })
+            .collect();
+        if targets.is_empty() {
+            return Ok(());
+        }
+
+        let action = super::actions::CycleEntryType::task_states(targets);
+        self.execute_action(Box::new(action))
+    }
+
     /// Check if in selection mode and get selection state
     pub fn get_selection_state(&self) -> Option<&SelectionState> {
         if let InputMode::Selection(ref state) = self.input_mode {
@@ -413,29 +432,36 @@ impl App {
                 if matches!(projected.source_type, SourceType::Recurring) {
                     return None;
                 }
-                Some(RawEntry {
-                    entry_type: projected.entry_type.clone(),
-                    content: projected.content.clone(),
-                })
+                Some(RawEntry::new(
+                    projected.entry_type.clone(),
+                    projected.content.clone(),
+                ))
             }
             SelectedEntry::Daily { entry, .. } => Some(entry.clone()),
             SelectedEntry::Filter { entry, .. } => {
                 let content = storage::get_entry_content(
+                    self.store.as_ref(),
                     entry.source_date,
-                    self.active_path(),
+                    self.entry_path(entry),
                     entry.line_index,
                 )
                 .unwrap_or_default();
-                Some(RawEntry {
-                    entry_type: entry.entry_type.clone(),
-                    content,
-                })
+                Some(RawEntry::new(entry.entry_type.clone(), content).with_depth(entry.depth))
             }
         })
     }
 
     /// Move all selected entries to a target date
     fn move_selected_to_date(&mut self, target_date: NaiveDate) -> io::Result<()> {
+        let foreign = self.collect_targets_from_selected(|entry| match entry {
+            SelectedEntry::Filter { entry, .. } => self.foreign_journal(entry).map(str::to_string),
+            _ => None,
+        });
+        if let Some(name) = foreign.first() {
+            self.set_status(format!("Switch to {name} to move its entries"));
+            return Ok(());
+        }
+
         let raw_entries = self.collect_raw_entries_from_selected();
         if raw_entries.is_empty() {
             self.cancel_selection_mode();
@@ -4,7 +4,7 @@ use regex::Regex;
 
 use crate::storage;
 
-use super::{App, ViewMode};
+use super::{App, Line, ViewMode};
 
 fn is_valid_tag_boundary(journal: &str, end_pos: usize) -> bool {
     end_pos >= journal.len() || {
@@ -12,35 +12,42 @@ fn is_valid_tag_boundary(journal: &str, end_pos: usize) -> bool {
         remaining
             .chars()
             .next()
-            .is_none_or(|c| !c.is_ascii_alphanumeric() && c != '_' && c != '-')
+            .is_none_or(|c| !c.is_alphanumeric() && c != '_' && c != '-')
     }
 }
 
+/// Counts occurrences of `tag` and the tags nested under it
 fn count_tag_occurrences(journal: &str, tag: &str, line_filter: Option<fn(&str) -> bool>) -> usize {
-    let tag_lower = tag.to_lowercase();
     let lines: Box<dyn Iterator<Item = &str>> = match line_filter {
         Some(f) => Box::new(journal.lines().filter(move |line| f(line))),
         None => Box::new(std::iter::once(journal)),
     };
     lines
         .flat_map(|text| storage::TAG_REGEX.captures_iter(text))
-        .filter(|cap| cap[1].to_lowercase() == tag_lower)
+        .filter(|cap| storage::tag_matches(&cap[1], tag))
         .count()
 }
 
+/// Done, cancelled and migrated tasks all count as completed here
 fn is_completed_task(line: &str) -> bool {
-    line.trim_start().starts_with("- [x] ")
+    matches!(
+        storage::parse_lines(line).first(),
+        Some(Line::Entry(entry)) if entry.entry_type.is_closed_task()
+    )
 }
 
 fn replace_tag_matches(journal: &str, regex: &Regex, replacement: Option<&str>) -> String {
     let mut result = String::with_capacity(journal.len());
     let mut last_end = 0;
 
-    for mat in regex.find_iter(journal) {
+    for caps in regex.captures_iter(journal) {
+        let mat = caps.get(0).expect("group 0 is the whole match");
         if is_valid_tag_boundary(journal, mat.end()) {
             result.push_str(&journal[last_end..mat.start()]);
             if let Some(rep) = replacement {
+                // Nested tags move along with their parent
                 result.push_str(rep);
+                result.push_str(caps.get(1).map_or("", |m| m.as_str()));
             }
             last_end = mat.end();
         }
@@ -65,16 +72,12 @@ impl App {
     }
 
     pub(super) fn delete_all_tag_occurrences(&mut self, tag: &str) -> io::Result<usize> {
-        let path = self.active_path().to_path_buf();
-        let journal = storage::load_journal(&path)?;
-        let count = count_tag_occurrences(&journal, tag, None);
-
         let tag_regex = storage::create_tag_delete_regex(tag).map_err(io::Error::other)?;
-        let new_journal = replace_tag_matches(&journal, &tag_regex, None);
-        let cleaned = Self::clean_empty_entries(&new_journal);
-
-        storage::save_journal(&path, &cleaned)?;
-        Ok(count)
+        self.rewrite_journal_text(|journal| {
+            let count = count_tag_occurrences(journal, tag, None);
+            let new_journal = replace_tag_matches(journal, &tag_regex, None);
+            (Self::clean_empty_entries(&new_journal), count)
+        })
     }
 
     pub fn confirm_delete_tag(&mut self, tag: &str) -> io::Result<()> {
@@ -86,26 +89,22 @@ impl App {
     }
 
     pub(super) fn delete_tag_from_completed(&mut self, tag: &str) -> io::Result<usize> {
-        let path = self.active_path().to_path_buf();
-        let journal = storage::load_journal(&path)?;
-        let count = count_tag_occurrences(&journal, tag, Some(is_completed_task));
-
         let tag_regex = storage::create_tag_delete_regex(tag).map_err(io::Error::other)?;
-        let new_journal = journal
-            .lines()
-            .map(|line| {
-                if is_completed_task(line) {
-                    replace_tag_matches(line, &tag_regex, None)
-                } else {
-                    line.to_string()
-                }
-            })
-            .collect::<Vec<_>>()
-            .join("\n");
-        let cleaned = Self::clean_empty_entries(&new_journal);
-
-        storage::save_journal(&path, &cleaned)?;
-        Ok(count)
+        self.rewrite_journal_text(|journal| {
+            let count = count_tag_occurrences(journal, tag, Some(is_completed_task));
+            let new_journal = journal
+                .lines()
+                .map(|line| {
+                    if is_completed_task(line) {
+                        replace_tag_matches(line, &tag_regex, None)
+                    } else {
+                        line.to_string()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            (Self::clean_empty_entries(&new_journal), count)
+        })
     }
 
     pub fn confirm_delete_tag_from_completed(&mut self, tag: &str) -> io::Result<()> {
@@ -122,17 +121,30 @@ impl App {
         old_tag: &str,
         new_tag: &str,
     ) -> io::Result<usize> {
-        let path = self.active_path().to_path_buf();
-        let journal = storage::load_journal(&path)?;
-        let count = count_tag_occurrences(&journal, old_tag, None);
-
         let tag_regex = storage::create_tag_match_regex(old_tag).map_err(io::Error::other)?;
         let replacement = format!("#{new_tag}");
-        let new_journal = replace_tag_matches(&journal, &tag_regex, Some(&replacement));
-        let cleaned = Self::clean_empty_entries(&new_journal);
+        self.rewrite_journal_text(|journal| {
+            let count = count_tag_occurrences(journal, old_tag, None);
+            let new_journal = replace_tag_matches(journal, &tag_regex, Some(&replacement));
+            (Self::clean_empty_entries(&new_journal), count)
+        })
+    }
 
-        storage::save_journal(&path, &cleaned)?;
-        Ok(count)
+    /// Rewrites each day's text in one atomic update, summing the counts `f` returns.
+    fn rewrite_journal_text<F>(&self, mut f: F) -> io::Result<usize>
+    where
+        F: FnMut(&str) -> (String, usize),
+    {
+        let mut total = 0;
+        self.store
+            .modify_days(self.active_path(), &mut |_, lines| {
+                let (text, count) = f(&storage::serialize_lines(lines));
+                total += count;
+                if count > 0 {
+                    *lines = storage::parse_lines(&text);
+                }
+            })?;
+        Ok(total)
     }
 
     /// Remove entries that became empty after tag operations
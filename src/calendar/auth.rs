@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+use reqwest::RequestBuilder;
+use tokio::process::Command;
+
+use crate::config::CalendarConfig;
+
+/// How long a secret command may run, e.g. while a pinentry waits for input,
+/// before the fetch gives up on it.
+const SECRET_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Adds a calendar's credentials and custom headers to a request. Secrets
+/// come from `password_command`/`token_command`, run on every fetch so a
+/// rotated secret is picked up without restarting.
+pub async fn apply_auth(
+    mut request: RequestBuilder,
+    config: &CalendarConfig,
+) -> Result<RequestBuilder, String> {
+    for (name, value) in &config.headers {
+        request = request.header(name, value);
+    }
+
+    if let Some(command) = &config.token_command {
+        let token = run_secret_command(command, "token_command", SECRET_COMMAND_TIMEOUT).await?;
+        request = request.bearer_auth(token);
+    } else if let Some(username) = &config.username {
+        let password = match &config.password_command {
+            Some(command) => {
+                Some(run_secret_command(command, "password_command", SECRET_COMMAND_TIMEOUT).await?)
+            }
+            None => None,
+        };
+        request = request.basic_auth(username, password);
+    }
+
+    Ok(request)
+}
+
+/// Runs a secret command through the shell and returns its first line of
+/// output, the way `pass show` prints the password first. `field` names the
+/// config key in errors. The command is killed if it outlives `timeout`.
+pub async fn run_secret_command(
+    command: &str,
+    field: &str,
+    timeout: Duration,
+) -> Result<String, String> {
+    let child = shell(command).kill_on_drop(true).output();
+    let output = tokio::time::timeout(timeout, child)
+        .await
+        .map_err(|_| format!("{} timed out", field))?
+        .map_err(|e| format!("Failed to run {}: {}", field, e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let detail = stderr.lines().next().unwrap_or_default().trim();
+        return Err(if detail.is_empty() {
+            format!("{} failed with {}", field, output.status)
+        } else {
+            format!("{} failed: {}", field, detail)
+        });
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let secret = stdout.lines().next().unwrap_or_default().trim();
+    if secret.is_empty() {
+        return Err(format!("{} printed nothing", field));
+    }
+    Ok(secret.to_string())
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(command);
+    cmd
+}
+
+#[cfg(not(unix))]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C").arg(command);
+    cmd
+}
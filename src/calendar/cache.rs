@@ -0,0 +1,64 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::write_private_file;
+
+/// A feed body saved from the last successful fetch, with the validators the
+/// server sent so the next fetch can ask whether it changed. Both live in one
+/// file, so they are always replaced together.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedFeed {
+    #[serde(flatten)]
+    pub meta: FeedMeta,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedMeta {
+    /// URL the body came from; a different URL in config ignores the cache
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    /// When the server last confirmed the body, by sending it or a 304
+    pub synced_at: DateTime<Local>,
+}
+
+/// Calendar ids become file names. Anything but ASCII letters, digits and `-`
+/// is escaped as `_XX` hex bytes, so no two ids share a file and none can
+/// point outside the cache directory.
+fn file_stem(calendar_id: &str) -> String {
+    let mut stem = String::with_capacity(calendar_id.len());
+    for byte in calendar_id.bytes() {
+        if byte.is_ascii_alphanumeric() || byte == b'-' {
+            stem.push(char::from(byte));
+        } else {
+            stem.push_str(&format!("_{byte:02X}"));
+        }
+    }
+    stem
+}
+
+fn feed_path(cache_dir: &Path, calendar_id: &str) -> PathBuf {
+    cache_dir.join(format!("{}.json", file_stem(calendar_id)))
+}
+
+/// Loads the cached feed for a calendar, if one was saved from `url`.
+#[must_use]
+pub fn load_feed(cache_dir: &Path, calendar_id: &str, url: &str) -> Option<CachedFeed> {
+    let feed: CachedFeed =
+        serde_json::from_str(&fs::read_to_string(feed_path(cache_dir, calendar_id)).ok()?).ok()?;
+    (feed.meta.url == url).then_some(feed)
+}
+
+/// Saves a fetched feed, replacing any earlier copy. Private feeds may hold
+/// anything, so the file is readable only by its owner.
+pub fn save_feed(cache_dir: &Path, calendar_id: &str, feed: &CachedFeed) -> io::Result<()> {
+    let json = serde_json::to_string(feed).map_err(io::Error::other)?;
+    write_private_file(&feed_path(cache_dir, calendar_id), &json)
+}
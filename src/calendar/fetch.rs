@@ -1,9 +1,14 @@
 use reqwest::Client;
+use reqwest::StatusCode;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
 use std::path::Path;
 use std::sync::LazyLock;
 use std::time::Duration;
 
-use crate::config::get_config_dir;
+use crate::config::{CalendarConfig, get_config_dir};
+
+use super::auth::apply_auth;
+use super::cache::FeedMeta;
 
 static HTTP_CLIENT: LazyLock<Option<Client>> = LazyLock::new(|| {
     Client::builder()
@@ -13,22 +18,64 @@ static HTTP_CLIENT: LazyLock<Option<Client>> = LazyLock::new(|| {
         .ok()
 });
 
-pub async fn fetch_calendar(url: &str) -> Result<String, String> {
+/// Result of fetching a feed that may already be cached.
+#[derive(Debug)]
+pub enum FetchOutcome {
+    /// A new body, with the validators to send next time
+    Modified {
+        body: String,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
+    /// The server confirmed the cached body is current (304)
+    NotModified,
+}
+
+pub async fn fetch_calendar(config: &CalendarConfig) -> Result<String, String> {
+    match fetch_calendar_conditional(config, None).await? {
+        FetchOutcome::Modified { body, .. } => Ok(body),
+        FetchOutcome::NotModified => Err("Calendar fetch returned 304 without validators".into()),
+    }
+}
+
+/// Fetches a feed, sending `If-None-Match`/`If-Modified-Since` from `cached`
+/// so an unchanged feed costs a 304 instead of a full download.
+pub async fn fetch_calendar_conditional(
+    config: &CalendarConfig,
+    cached: Option<&FeedMeta>,
+) -> Result<FetchOutcome, String> {
     // Handle file:// URLs for local ICS files
-    if let Some(file_path) = url.strip_prefix("file://") {
-        return fetch_local_file(file_path);
+    if let Some(file_path) = config.url.strip_prefix("file://") {
+        return fetch_local_file(file_path).map(|body| FetchOutcome::Modified {
+            body,
+            etag: None,
+            last_modified: None,
+        });
     }
 
     let client = HTTP_CLIENT
         .as_ref()
         .ok_or_else(|| "HTTP client unavailable (TLS initialization failed)".to_string())?;
 
-    let response = client
-        .get(url)
+    let mut request = apply_auth(client.get(&config.url), config).await?;
+    if let Some(meta) = cached {
+        if let Some(etag) = &meta.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &meta.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    let response = request
         .send()
         .await
         .map_err(|e| format!("Failed to fetch calendar: {}", e))?;
 
+    if response.status() == StatusCode::NOT_MODIFIED && cached.is_some() {
+        return Ok(FetchOutcome::NotModified);
+    }
+
     if !response.status().is_success() {
         return Err(format!(
             "Calendar fetch failed with status: {}",
@@ -36,10 +83,26 @@ pub async fn fetch_calendar(url: &str) -> Result<String, String> {
         ));
     }
 
-    response
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    };
+    let etag = header(ETAG);
+    let last_modified = header(LAST_MODIFIED);
+
+    let body = response
         .text()
         .await
-        .map_err(|e| format!("Failed to read calendar response: {}", e))
+        .map_err(|e| format!("Failed to read calendar response: {}", e))?;
+
+    Ok(FetchOutcome::Modified {
+        body,
+        etag,
+        last_modified,
+    })
 }
 
 /// Fetch a local ICS file.
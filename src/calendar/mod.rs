@@ -1,19 +1,26 @@
+mod auth;
+mod cache;
 mod fetch;
 mod parse;
 mod store;
 
-pub use fetch::fetch_calendar;
+pub use auth::run_secret_command;
+pub use cache::{CachedFeed, FeedMeta, load_feed, save_feed};
+pub use fetch::{FetchOutcome, fetch_calendar, fetch_calendar_conditional};
 pub use parse::{IcsParseResult, ParseContext, parse_ics};
-pub use store::{CalendarEvent, CalendarStore};
+pub use store::{CalendarEvent, CalendarStore, CalendarSyncStatus};
 
-use chrono::{Duration, Local, NaiveDate};
+use std::path::Path;
 
-use crate::config::{CalendarConfig, CalendarVisibilityConfig, CalendarVisibilityMode, Config};
+use chrono::{Duration, Local};
+
+use crate::config::{CalendarConfig, CalendarVisibilityMode, Config};
 use crate::storage::{JournalSlot, ProjectInfo};
 
 pub struct CalendarFetchResult {
     pub events: Vec<CalendarEvent>,
     pub visible_count: usize,
+    pub statuses: Vec<CalendarSyncStatus>,
 }
 
 #[must_use]
@@ -42,62 +49,197 @@ pub fn get_visible_calendar_ids(
     }
 }
 
-pub async fn fetch_all_calendars(config: &Config, visible_ids: &[String]) -> CalendarFetchResult {
-    let today = Local::now().date_naive();
-    let range_start = today - Duration::days(180);
-    let range_end = today + Duration::days(365);
-    let visibility = &config.calendar_visibility;
+/// Builds the agenda from cached feeds alone, so it renders at launch (or
+/// offline) before any network round trip.
+#[must_use]
+pub fn load_cached_calendars(
+    config: &Config,
+    visible_ids: &[String],
+    cache_dir: &Path,
+) -> CalendarFetchResult {
+    let mut all_events = Vec::new();
+    let mut statuses = Vec::new();
+
+    for cal_id in visible_ids {
+        let Some(cal_config) = config.get_calendar(cal_id) else {
+            continue;
+        };
+        let (body, status) = cached_calendar(cal_id, cal_config, cache_dir);
+        collect_calendar(cal_id, config, body, status, &mut all_events, &mut statuses);
+    }
 
+    CalendarFetchResult {
+        events: all_events,
+        visible_count: visible_ids.len(),
+        statuses,
+    }
+}
+
+/// Fetches every visible calendar, revalidating against the on-disk cache.
+/// A calendar that fails to fetch falls back to its cached copy and reports
+/// the error in its status.
+pub async fn fetch_all_calendars(
+    config: &Config,
+    visible_ids: &[String],
+    cache_dir: &Path,
+) -> CalendarFetchResult {
+    refresh_calendars(config, visible_ids, visible_ids, &[], cache_dir).await
+}
+
+/// Fetches the calendars in `due_ids` and reads the rest of `visible_ids`
+/// from the cache, keeping their `previous` status, so calendars can refresh
+/// on their own intervals while the store is still rebuilt as a whole.
+pub async fn refresh_calendars(
+    config: &Config,
+    visible_ids: &[String],
+    due_ids: &[String],
+    previous: &[CalendarSyncStatus],
+    cache_dir: &Path,
+) -> CalendarFetchResult {
     let mut all_events = Vec::new();
+    let mut statuses = Vec::new();
 
     for cal_id in visible_ids {
         let Some(cal_config) = config.get_calendar(cal_id) else {
             continue;
         };
 
-        let color = config.calendar_color(cal_id);
-        if let Ok(events) = fetch_and_parse_calendar(
-            cal_id,
-            cal_config,
-            range_start,
-            range_end,
-            visibility,
-            color,
-        )
-        .await
-        {
-            all_events.extend(events);
-        }
+        let (body, status) = if due_ids.contains(cal_id) {
+            sync_calendar(cal_id, cal_config, cache_dir).await
+        } else {
+            let (body, status) = cached_calendar(cal_id, cal_config, cache_dir);
+            let status = previous
+                .iter()
+                .find(|prev| prev.calendar_id == *cal_id)
+                .cloned()
+                .unwrap_or(status);
+            (body, status)
+        };
+        collect_calendar(cal_id, config, body, status, &mut all_events, &mut statuses);
     }
 
     CalendarFetchResult {
         events: all_events,
         visible_count: visible_ids.len(),
+        statuses,
     }
 }
 
-async fn fetch_and_parse_calendar(
+/// Parses a calendar's body into `events`, recording parse errors in its status.
+fn collect_calendar(
     cal_id: &str,
-    config: &CalendarConfig,
-    range_start: NaiveDate,
-    range_end: NaiveDate,
-    visibility: &CalendarVisibilityConfig,
-    color: ratatui::style::Color,
+    config: &Config,
+    body: Option<String>,
+    mut status: CalendarSyncStatus,
+    events: &mut Vec<CalendarEvent>,
+    statuses: &mut Vec<CalendarSyncStatus>,
+) {
+    if let Some(body) = body {
+        match parse_calendar(cal_id, config, &body) {
+            Ok(parsed) => events.extend(parsed),
+            Err(e) => status.error = Some(e),
+        }
+    }
+    statuses.push(status);
+}
+
+/// Reads one feed from the cache without touching the network.
+fn cached_calendar(
+    cal_id: &str,
+    cal_config: &CalendarConfig,
+    cache_dir: &Path,
+) -> (Option<String>, CalendarSyncStatus) {
+    let cached = cache::load_feed(cache_dir, cal_id, &cal_config.url);
+    let status = CalendarSyncStatus {
+        calendar_id: cal_id.to_string(),
+        last_synced: cached.as_ref().map(|feed| feed.meta.synced_at),
+        error: None,
+    };
+    (cached.map(|feed| feed.body), status)
+}
+
+/// Fetches one feed, updating the cache. Returns the body to parse (fresh or
+/// cached) along with the calendar's sync status.
+async fn sync_calendar(
+    cal_id: &str,
+    cal_config: &CalendarConfig,
+    cache_dir: &Path,
+) -> (Option<String>, CalendarSyncStatus) {
+    let cached = cache::load_feed(cache_dir, cal_id, &cal_config.url);
+    let now = Local::now();
+    let mut status = CalendarSyncStatus {
+        calendar_id: cal_id.to_string(),
+        last_synced: cached.as_ref().map(|feed| feed.meta.synced_at),
+        error: None,
+    };
+
+    let outcome =
+        fetch_calendar_conditional(cal_config, cached.as_ref().map(|feed| &feed.meta)).await;
+    let body = match (outcome, cached) {
+        (
+            Ok(FetchOutcome::Modified {
+                body,
+                etag,
+                last_modified,
+            }),
+            _,
+        ) => {
+            let feed = CachedFeed {
+                body,
+                meta: FeedMeta {
+                    url: cal_config.url.clone(),
+                    etag,
+                    last_modified,
+                    synced_at: now,
+                },
+            };
+            if let Err(e) = cache::save_feed(cache_dir, cal_id, &feed) {
+                status.error = Some(format!("Failed to cache calendar: {}", e));
+            }
+            status.last_synced = Some(now);
+            Some(feed.body)
+        }
+        (Ok(FetchOutcome::NotModified), Some(mut feed)) => {
+            feed.meta.synced_at = now;
+            if let Err(e) = cache::save_feed(cache_dir, cal_id, &feed) {
+                status.error = Some(format!("Failed to cache calendar: {}", e));
+            }
+            status.last_synced = Some(now);
+            Some(feed.body)
+        }
+        // A 304 is only accepted when validators were sent, so there is
+        // always a cached body for it
+        (Ok(FetchOutcome::NotModified), None) => None,
+        (Err(e), cached) => {
+            status.error = Some(e);
+            cached.map(|feed| feed.body)
+        }
+    };
+
+    (body, status)
+}
+
+fn parse_calendar(
+    cal_id: &str,
+    config: &Config,
+    ics_content: &str,
 ) -> Result<Vec<CalendarEvent>, String> {
-    let ics_content = fetch_calendar(&config.url).await?;
+    let today = Local::now().date_naive();
+    let visibility = &config.calendar_visibility;
     let ctx = ParseContext {
         calendar_id: cal_id,
         calendar_name: cal_id,
-        range_start,
-        range_end,
+        range_start: today - Duration::days(180),
+        range_end: today + Duration::days(365),
         display_cancelled: visibility.display_cancelled,
         display_declined: visibility.display_declined,
-        color,
+        color: config.calendar_color(cal_id),
     };
-    let result = parse_ics(&ics_content, &ctx)?;
+    let result = parse_ics(ics_content, &ctx)?;
     Ok(result.events)
 }
 
 pub fn update_store(store: &mut CalendarStore, result: CalendarFetchResult) {
     store.update(result.events, result.visible_count);
+    store.sync_statuses = result.statuses;
 }
@@ -24,10 +24,21 @@ impl CalendarEvent {
     }
 }
 
+/// Outcome of the last sync for one calendar, shown in the agenda header.
+#[derive(Debug, Clone)]
+pub struct CalendarSyncStatus {
+    pub calendar_id: String,
+    /// When the feed was last fetched or confirmed unchanged; None if never
+    pub last_synced: Option<DateTime<Local>>,
+    /// Why the last fetch failed, when events come from the cache (or nowhere)
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Default)]
 pub struct CalendarStore {
     events_by_date: HashMap<NaiveDate, Vec<CalendarEvent>>,
     pub visible_calendar_count: usize,
+    pub sync_statuses: Vec<CalendarSyncStatus>,
 }
 
 impl CalendarStore {
@@ -54,6 +65,7 @@ impl CalendarStore {
     pub fn clear(&mut self) {
         self.events_by_date.clear();
         self.visible_calendar_count = 0;
+        self.sync_statuses.clear();
     }
 
     pub fn update(&mut self, events: Vec<CalendarEvent>, visible_count: usize) {
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead};
+use std::path::Path;
+
+use chrono::NaiveDate;
+
+use crate::storage::{
+    self, EntryType, JournalSlot, JournalStore, Line, ParseContext, RawEntry, expand_favorite_tags,
+    normalize_entry_structure, parse_lines,
+};
+
+use super::{parse_slot_flag, resolve_journal, usage_error};
+
+pub const USAGE: &str = "\
+Usage: caliber add [--task|--note|--event] [--hub|--project] [--date <expr>] <text>...
+       caliber add [options] -      Read entries from stdin, one per line";
+
+/// Parsed arguments for `caliber add`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AddArgs {
+    /// Entry type override; lines with their own markdown prefix keep it otherwise
+    pub entry_type: Option<EntryType>,
+    pub slot: Option<JournalSlot>,
+    /// Date expression, parsed with entry (future-biased) semantics
+    pub date: Option<String>,
+    pub text: Vec<String>,
+    pub help: bool,
+}
+
+impl AddArgs {
+    pub fn parse(args: &[String]) -> io::Result<Self> {
+        let mut parsed = Self::default();
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            // Once the text starts, flag-like words belong to it
+            if !parsed.text.is_empty() {
+                parsed.text.push(arg.clone());
+                continue;
+            }
+            if let Some(slot) = parse_slot_flag(arg) {
+                parsed.slot = Some(slot);
+                continue;
+            }
+            match arg.as_str() {
+                "--task" | "-t" => parsed.entry_type = Some(EntryType::task()),
+                "--note" | "-n" => parsed.entry_type = Some(EntryType::Note),
+                "--event" | "-e" => parsed.entry_type = Some(EntryType::Event),
+                "--date" | "-d" => {
+                    let value = iter
+                        .next()
+                        .ok_or_else(|| usage_error("--date requires a value", USAGE))?;
+                    parsed.date = Some(value.clone());
+                }
+                "--help" | "-h" => parsed.help = true,
+                "--" => {
+                    parsed.text.extend(iter.by_ref().cloned());
+                }
+                "-" => parsed.text.push(arg.clone()),
+                flag if flag.starts_with('-') => {
+                    return Err(usage_error(format!("Unknown option: {flag}"), USAGE));
+                }
+                _ => parsed.text.push(arg.clone()),
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    /// Returns true if entries should be read from stdin (`caliber add -`).
+    #[must_use]
+    pub fn reads_stdin(&self) -> bool {
+        self.text.len() == 1 && self.text[0] == "-"
+    }
+
+    /// Resolves the target date, defaulting to today.
+    pub fn resolve_date(&self, today: NaiveDate) -> io::Result<NaiveDate> {
+        let Some(expr) = &self.date else {
+            return Ok(today);
+        };
+        storage::parse_date(expr, ParseContext::Entry, today).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid date: {expr}"))
+        })
+    }
+}
+
+/// Builds an entry from a line of input.
+///
+/// Lines that already carry a markdown prefix (`- [ ] `, `- `, `* `) keep their type
+/// unless `entry_type` overrides it; bare text becomes a task by default.
+#[must_use]
+pub fn build_entry(
+    input: &str,
+    entry_type: Option<&EntryType>,
+    favorite_tags: &HashMap<String, String>,
+) -> Option<RawEntry> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+
+    let mut entry = match parse_lines(input).into_iter().next() {
+        Some(Line::Entry(raw)) => raw,
+        _ => RawEntry::new_task(input),
+    };
+    if let Some(entry_type) = entry_type {
+        entry.entry_type = entry_type.clone();
+    }
+
+    let content = expand_favorite_tags(&entry.content, favorite_tags);
+    let (content, _) = normalize_entry_structure(&content);
+    entry.content = content.trim_end().to_string();
+
+    (!entry.content.is_empty()).then_some(entry)
+}
+
+/// Appends entries to the end of a day, returning the line index of the first one.
+pub fn append_entries(
+    store: &dyn JournalStore,
+    date: NaiveDate,
+    path: &Path,
+    entries: Vec<RawEntry>,
+) -> io::Result<usize> {
+    storage::modify_day_lines(store, date, path, |lines| {
+        let first_index = lines.len();
+        lines.extend(entries.into_iter().map(Line::Entry));
+        first_index
+    })
+}
+
+pub fn run(args: &[String]) -> io::Result<()> {
+    let args = AddArgs::parse(args)?;
+    if args.help {
+        println!("{USAGE}");
+        return Ok(());
+    }
+
+    let inputs: Vec<String> = if args.reads_stdin() {
+        io::stdin().lock().lines().collect::<io::Result<_>>()?
+    } else if args.text.is_empty() {
+        return Err(usage_error("Missing entry text", USAGE));
+    } else {
+        vec![args.text.join(" ")]
+    };
+
+    let (context, config) = resolve_journal(args.slot)?;
+    let date = args.resolve_date(chrono::Local::now().date_naive())?;
+
+    let today = chrono::Local::now().date_naive();
+    let entries: Vec<RawEntry> = inputs
+        .iter()
+        .filter_map(|input| build_entry(input, args.entry_type.as_ref(), &config.favorite_tags))
+        .map(|mut entry| {
+            if config.record_dates {
+                entry.content = storage::add_created_date(&entry.content, today);
+            }
+            entry
+        })
+        .collect();
+
+    if entries.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Nothing to add",
+        ));
+    }
+
+    let count = entries.len();
+    let path = context.active_path();
+    append_entries(config.journal_store().as_ref(), date, path, entries)?;
+
+    let noun = if count == 1 { "entry" } else { "entries" };
+    println!(
+        "Added {count} {noun} to {} ({})",
+        path.display(),
+        date.format("%Y/%m/%d")
+    );
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use std::io;
+
+use crate::config::JournalLayout;
+use crate::storage::{self, JournalSlot};
+
+use super::{parse_slot_flag, resolve_journal, usage_error};
+
+pub const USAGE: &str = "\
+Usage: caliber doctor [--fix] [--hub|--project]
+       Report structural problems in the journal; --fix rewrites it into canonical form";
+
+pub fn run(args: &[String]) -> io::Result<()> {
+    let mut slot: Option<JournalSlot> = None;
+    let mut fix = false;
+    for arg in args {
+        if let Some(s) = parse_slot_flag(arg) {
+            slot = Some(s);
+            continue;
+        }
+        match arg.as_str() {
+            "--fix" => fix = true,
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            other => return Err(usage_error(format!("Unknown option: {other}"), USAGE)),
+        }
+    }
+
+    let (context, config) = resolve_journal(slot)?;
+    if config.journal_layout == JournalLayout::DailyFiles {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "doctor checks single-file journals; daily files have no headers to repair",
+        ));
+    }
+    let path = context.active_path();
+    let journal = storage::load_journal(path)?;
+    let issues = storage::diagnose_journal(&journal);
+
+    if issues.is_empty() {
+        println!("{}: no issues found", path.display());
+        return Ok(());
+    }
+
+    for issue in &issues {
+        println!("{issue}");
+    }
+
+    let unfixable = issues.iter().filter(|i| !i.is_fixable()).count();
+    if fix {
+        storage::modify_journal(path, |journal| *journal = storage::repair_journal(journal))?;
+        println!(
+            "Repaired {} issue(s) in {}",
+            issues.len() - unfixable,
+            path.display()
+        );
+        if unfixable == 0 {
+            return Ok(());
+        }
+        return Err(io::Error::other(format!(
+            "{unfixable} issue(s) need manual attention"
+        )));
+    }
+
+    Err(io::Error::other(format!(
+        "{} issue(s) found; run `caliber doctor --fix` to repair",
+        issues.len()
+    )))
+}
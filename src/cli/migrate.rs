@@ -0,0 +1,111 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::config::JournalLayout;
+use crate::storage::{self, DailyFilesStore, JournalSlot, JournalStore, MarkdownStore};
+
+use super::{parse_slot_flag, resolve_journal, usage_error};
+
+pub const USAGE: &str = "\
+Usage: caliber migrate <daily_files|single_file> [--hub|--project]
+       Convert the journal to another layout; the old copy is kept with a .bak suffix";
+
+fn parse_layout(s: &str) -> Option<JournalLayout> {
+    match s {
+        "daily_files" => Some(JournalLayout::DailyFiles),
+        "single_file" => Some(JournalLayout::SingleFile),
+        _ => None,
+    }
+}
+
+fn layout_name(layout: JournalLayout) -> &'static str {
+    match layout {
+        JournalLayout::SingleFile => "single_file",
+        JournalLayout::DailyFiles => "daily_files",
+    }
+}
+
+/// Where a layout keeps a journal on disk: the file itself, or the folder of day files.
+fn location(layout: JournalLayout, path: &Path) -> PathBuf {
+    match layout {
+        JournalLayout::SingleFile => path.to_path_buf(),
+        JournalLayout::DailyFiles => DailyFilesStore::journal_dir(path),
+    }
+}
+
+fn store(layout: JournalLayout) -> &'static dyn JournalStore {
+    match layout {
+        JournalLayout::SingleFile => &MarkdownStore,
+        JournalLayout::DailyFiles => &DailyFilesStore,
+    }
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!("{name}.bak"))
+}
+
+pub fn run(args: &[String]) -> io::Result<()> {
+    let mut slot: Option<JournalSlot> = None;
+    let mut target: Option<JournalLayout> = None;
+    for arg in args {
+        if let Some(s) = parse_slot_flag(arg) {
+            slot = Some(s);
+            continue;
+        }
+        match arg.as_str() {
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            other => match parse_layout(other) {
+                Some(layout) if target.is_none() => target = Some(layout),
+                _ => return Err(usage_error(format!("Unknown argument: {other}"), USAGE)),
+            },
+        }
+    }
+    let Some(target) = target else {
+        return Err(usage_error("Missing target layout", USAGE));
+    };
+    let source = match target {
+        JournalLayout::SingleFile => JournalLayout::DailyFiles,
+        JournalLayout::DailyFiles => JournalLayout::SingleFile,
+    };
+
+    let (context, config) = resolve_journal(slot)?;
+    let path = context.active_path();
+    let from = location(source, path);
+    let to = location(target, path);
+    if !store(source).journal_exists(path) {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("No {} journal at {}", layout_name(source), from.display()),
+        ));
+    }
+    let backup = backup_path(&from);
+    if backup.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists; move it away first", backup.display()),
+        ));
+    }
+
+    let _lock = storage::lock_journal(&from)?;
+    let count = storage::copy_journal(store(source), store(target), path)?;
+    fs::rename(&from, &backup)?;
+    store(target).create_journal(path)?;
+
+    println!("Migrated {count} day(s) to {}", to.display());
+    println!("Old journal kept at {}", backup.display());
+    if config.journal_layout != target {
+        println!(
+            "Set journal_layout = \"{}\" in config.toml to use it",
+            layout_name(target)
+        );
+    }
+    Ok(())
+}
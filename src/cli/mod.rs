@@ -0,0 +1,117 @@
+//! Headless subcommands that operate on the journal without launching the TUI.
+
+pub mod add;
+pub mod doctor;
+pub mod migrate;
+pub mod query;
+pub mod serve;
+
+use std::collections::BTreeMap;
+use std::io;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::storage::{self, Entry, EntryType, JournalContext, JournalSlot, TaskState};
+
+/// Serializable view of an entry for machine-readable output.
+#[derive(Debug, Clone, Serialize)]
+pub struct EntryRecord {
+    pub date: NaiveDate,
+    pub line_index: usize,
+    #[serde(rename = "type")]
+    pub entry_type: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<&'static str>,
+    pub content: String,
+    pub tags: Vec<String>,
+    /// `key:value` properties; the first value wins when a key repeats
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub properties: BTreeMap<String, String>,
+    /// Source journal, for queries spanning several journals
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub journal: Option<String>,
+}
+
+impl EntryRecord {
+    #[must_use]
+    pub fn from_entry(entry: &Entry) -> Self {
+        let content = storage::strip_entry_meta(&entry.content);
+        Self {
+            date: entry.source_date,
+            line_index: entry.line_index,
+            entry_type: entry_type_name(&entry.entry_type),
+            completed: entry
+                .entry_type
+                .task_state()
+                .map(|state| state == TaskState::Done),
+            state: entry.entry_type.task_state().map(TaskState::name),
+            tags: storage::extract_tags(&content),
+            properties: storage::extract_properties(&content)
+                .into_iter()
+                .rev()
+                .collect(),
+            content,
+            journal: entry.journal.as_ref().map(|journal| journal.name.clone()),
+        }
+    }
+}
+
+/// Lowercase name of an entry type as used in CLI output.
+#[must_use]
+pub fn entry_type_name(entry_type: &EntryType) -> &'static str {
+    match entry_type {
+        EntryType::Task { .. } => "task",
+        EntryType::Note => "note",
+        EntryType::Event => "event",
+    }
+}
+
+/// Resolves the active journal the same way the TUI does on launch: the project
+/// journal when one is detected, otherwise the hub. An explicit slot overrides detection.
+pub fn resolve_journal(slot: Option<JournalSlot>) -> io::Result<(JournalContext, Config)> {
+    let project_path = storage::detect_project_with_profile();
+
+    let active_slot = match slot {
+        Some(JournalSlot::Project) if project_path.is_none() => {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "No project journal found",
+            ));
+        }
+        Some(slot) => slot,
+        None if project_path.is_some() => JournalSlot::Project,
+        None => JournalSlot::Hub,
+    };
+
+    let config_load = match active_slot {
+        JournalSlot::Hub => Config::load_hub()?,
+        JournalSlot::Project => Config::load_merged()?,
+    };
+    if let Some(warning) = config_load.warning {
+        eprintln!("Warning: {warning}");
+    }
+
+    let hub_path = config_load.config.get_hub_journal_path();
+    let context = JournalContext::new(hub_path, project_path, active_slot);
+    Ok((context, config_load.config))
+}
+
+/// Parses a `--hub` / `--project` flag, returning None for any other argument.
+fn parse_slot_flag(arg: &str) -> Option<JournalSlot> {
+    match arg {
+        "--hub" => Some(JournalSlot::Hub),
+        "--project" => Some(JournalSlot::Project),
+        _ => None,
+    }
+}
+
+fn usage_error(message: impl Into<String>, usage: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("{}\n{usage}", message.into()),
+    )
+}
@@ -0,0 +1,221 @@
+use std::io::{self, Write};
+use std::path::Path;
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::config::Config;
+use crate::storage::{self, Entry, JournalSlot, ProjectRegistry};
+
+use super::{EntryRecord, parse_slot_flag, resolve_journal, usage_error};
+
+pub const USAGE: &str = "\
+Usage: caliber query [--hub|--project] [--format table|markdown|json] [<query>...]
+       Uses the same syntax as the filter prompt; defaults to `default_filter`";
+
+/// Output format for query results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Aligned columns for reading in a terminal
+    #[default]
+    Table,
+    /// GitHub-flavored markdown table
+    Markdown,
+    /// One JSON object per line
+    Json,
+}
+
+impl OutputFormat {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "table" => Some(Self::Table),
+            "markdown" | "md" => Some(Self::Markdown),
+            "json" | "jsonl" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Parsed arguments for `caliber query`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryArgs {
+    pub slot: Option<JournalSlot>,
+    pub format: OutputFormat,
+    pub query: Vec<String>,
+    pub help: bool,
+}
+
+impl QueryArgs {
+    /// Filter tokens may themselves start with `-` (e.g. `-#done`), so only known
+    /// flags are consumed and everything else becomes part of the query.
+    pub fn parse(args: &[String]) -> io::Result<Self> {
+        let mut parsed = Self::default();
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            if let Some(slot) = parse_slot_flag(arg) {
+                parsed.slot = Some(slot);
+                continue;
+            }
+            match arg.as_str() {
+                "--format" | "-f" => {
+                    let value = iter
+                        .next()
+                        .ok_or_else(|| usage_error("--format requires a value", USAGE))?;
+                    parsed.format = OutputFormat::parse(value)
+                        .ok_or_else(|| usage_error(format!("Unknown format: {value}"), USAGE))?;
+                }
+                "--json" => parsed.format = OutputFormat::Json,
+                "--help" | "-h" => parsed.help = true,
+                "--" => parsed.query.extend(iter.by_ref().cloned()),
+                _ => parsed.query.push(arg.clone()),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// Runs a filter query against a journal, expanding saved filters from config.
+/// Scope modifiers like `@all` widen it to the hub and registered projects.
+/// Unknown filters and invalid tokens are reported as errors rather than empty results.
+pub fn execute_query(
+    query: &str,
+    config: &Config,
+    path: &Path,
+    hub_path: &Path,
+) -> io::Result<Vec<Entry>> {
+    let query = if query.trim().is_empty() {
+        config.default_filter.as_str()
+    } else {
+        query
+    };
+
+    let filter = storage::parse_filter_query(query, &config.filters);
+    if let Some(message) = filter.error_message() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, message));
+    }
+
+    let store = config.journal_store();
+    if filter.scopes.is_empty() {
+        return storage::collect_filtered_entries(store.as_ref(), &filter, path);
+    }
+    let journals =
+        storage::resolve_filter_scope(&filter.scopes, path, hub_path, &ProjectRegistry::load())
+            .map_err(|message| io::Error::new(io::ErrorKind::InvalidInput, message))?;
+    storage::collect_filtered_entries_across(store.as_ref(), &filter, &journals)
+}
+
+/// Writes query results in the requested format.
+pub fn write_entries(
+    out: &mut impl Write,
+    entries: &[Entry],
+    format: OutputFormat,
+) -> io::Result<()> {
+    let records: Vec<EntryRecord> = entries.iter().map(EntryRecord::from_entry).collect();
+    match format {
+        OutputFormat::Table => write_table(out, &records),
+        OutputFormat::Markdown => write_markdown(out, &records),
+        OutputFormat::Json => {
+            for record in &records {
+                serde_json::to_writer(&mut *out, record)?;
+                writeln!(out)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn type_label(record: &EntryRecord) -> &'static str {
+    match (record.entry_type, record.state) {
+        ("task", Some(state)) if state != "open" => state,
+        (name, _) => name,
+    }
+}
+
+fn write_table(out: &mut impl Write, records: &[EntryRecord]) -> io::Result<()> {
+    let line_width = records
+        .iter()
+        .map(|r| r.line_index.to_string().len())
+        .max()
+        .unwrap_or(1);
+    let content_width = records.iter().map(|r| r.content.width()).max().unwrap_or(0);
+    let type_width = records
+        .iter()
+        .map(|r| type_label(r).len())
+        .fold("event".len(), usize::max);
+    let journal_width = records
+        .iter()
+        .filter_map(|r| r.journal.as_ref())
+        .map(|journal| journal.width() + 2)
+        .max()
+        .unwrap_or(0);
+
+    for record in records {
+        let padding = content_width.saturating_sub(record.content.width());
+        let journal = record.journal.as_deref().unwrap_or_default();
+        let line = format!(
+            "{}  {:<journal_width$}{:>line_width$}  {:<type_width$}  {}{}  {}",
+            record.date.format("%Y/%m/%d"),
+            if journal.is_empty() {
+                String::new()
+            } else {
+                format!("{journal}  ")
+            },
+            record.line_index,
+            type_label(record),
+            record.content,
+            " ".repeat(padding),
+            record.tags.join(","),
+        );
+        writeln!(out, "{}", line.trim_end())?;
+    }
+    Ok(())
+}
+
+fn write_markdown(out: &mut impl Write, records: &[EntryRecord]) -> io::Result<()> {
+    let with_journal = records.iter().any(|r| r.journal.is_some());
+    if with_journal {
+        writeln!(out, "| Date | Journal | Line | Type | Entry | Tags |")?;
+        writeln!(out, "|------|---------|------|------|-------|------|")?;
+    } else {
+        writeln!(out, "| Date | Line | Type | Entry | Tags |")?;
+        writeln!(out, "|------|------|------|-------|------|")?;
+    }
+    for record in records {
+        let journal = if with_journal {
+            format!(" {} |", record.journal.as_deref().unwrap_or_default())
+        } else {
+            String::new()
+        };
+        writeln!(
+            out,
+            "| {} |{} {} | {} | {} | {} |",
+            record.date.format("%Y/%m/%d"),
+            journal,
+            record.line_index,
+            type_label(record),
+            record.content.replace('|', "\\|"),
+            record.tags.join(", "),
+        )?;
+    }
+    Ok(())
+}
+
+pub fn run(args: &[String]) -> io::Result<()> {
+    let args = QueryArgs::parse(args)?;
+    if args.help {
+        println!("{USAGE}");
+        return Ok(());
+    }
+
+    let (context, config) = resolve_journal(args.slot)?;
+    let entries = execute_query(
+        &args.query.join(" "),
+        &config,
+        context.active_path(),
+        context.hub_path(),
+    )?;
+
+    let mut stdout = io::stdout().lock();
+    write_entries(&mut stdout, &entries, args.format)
+}
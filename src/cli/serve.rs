@@ -0,0 +1,426 @@
+//! Stdio JSON-RPC server exposing journal tools with an MCP-compatible schema.
+//!
+//! Messages are newline-delimited JSON-RPC 2.0. The server answers `initialize`,
+//! `ping`, `tools/list` and `tools/call`; tool failures are reported as tool results
+//! with `isError` set so agents can recover, while protocol errors use JSON-RPC errors.
+
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use serde_json::{Value, json};
+
+use crate::config::Config;
+use crate::storage::{self, EntryType, JournalContext, JournalSlot, JournalStore, ParseContext};
+
+use super::add::{append_entries, build_entry};
+use super::query::execute_query;
+use super::{EntryRecord, parse_slot_flag, resolve_journal, usage_error};
+
+pub const USAGE: &str = "\
+Usage: caliber serve --stdio [--hub|--project]
+       Speak JSON-RPC (MCP tools) over stdin/stdout";
+
+const PROTOCOL_VERSION: &str = "2025-06-18";
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Journal server state: the resolved hub/project paths and their configs.
+pub struct Server {
+    context: JournalContext,
+    hub_config: Config,
+    project_config: Config,
+}
+
+impl Server {
+    #[must_use]
+    pub fn new(context: JournalContext, hub_config: Config, project_config: Config) -> Self {
+        Self {
+            context,
+            hub_config,
+            project_config,
+        }
+    }
+
+    /// Handles one line of input, returning the response line (None for notifications).
+    pub fn handle_line(&self, line: &str) -> Option<Value> {
+        let request: Value = match serde_json::from_str(line) {
+            Ok(value) => value,
+            Err(e) => return Some(error_response(Value::Null, PARSE_ERROR, &e.to_string())),
+        };
+
+        let id = request.get("id").cloned();
+        let Some(method) = request.get("method").and_then(Value::as_str) else {
+            return Some(error_response(
+                id.unwrap_or(Value::Null),
+                INVALID_REQUEST,
+                "Missing method",
+            ));
+        };
+        let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = match method {
+            "initialize" => Ok(json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": "caliber", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => self.call_tool(&params),
+            _ if method.starts_with("notifications/") => return None,
+            _ => Err((METHOD_NOT_FOUND, format!("Unknown method: {method}"))),
+        };
+
+        // Requests without an id are notifications and never get a response
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, &message),
+        })
+    }
+
+    fn call_tool(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or((INVALID_PARAMS, "Missing tool name".to_string()))?;
+        let empty = json!({});
+        let args = params.get("arguments").unwrap_or(&empty);
+
+        let outcome = match name {
+            "list_days" => self.list_days(args),
+            "query" => self.query(args),
+            "add_entry" => self.add_entry(args),
+            "toggle_complete" => self.toggle_complete(args),
+            "list_tags" => self.list_tags(args),
+            _ => return Err((INVALID_PARAMS, format!("Unknown tool: {name}"))),
+        };
+
+        Ok(match outcome {
+            Ok(value) => json!({
+                "content": [{ "type": "text", "text": value.to_string() }],
+                "structuredContent": value,
+                "isError": false,
+            }),
+            Err(message) => json!({
+                "content": [{ "type": "text", "text": message }],
+                "isError": true,
+            }),
+        })
+    }
+
+    /// Resolves the journal path and config for a tool call's optional `journal` argument.
+    fn journal(&self, args: &Value) -> Result<(PathBuf, &Config), String> {
+        let slot = match optional_str(args, "journal")? {
+            None => self.context.active_slot(),
+            Some("hub") => JournalSlot::Hub,
+            Some("project") => JournalSlot::Project,
+            Some(other) => return Err(format!("Unknown journal: {other}")),
+        };
+        match slot {
+            JournalSlot::Hub => Ok((self.context.hub_path().to_path_buf(), &self.hub_config)),
+            JournalSlot::Project => self
+                .context
+                .project_path()
+                .map(|path| (path.to_path_buf(), &self.project_config))
+                .ok_or_else(|| "No project journal found".to_string()),
+        }
+    }
+
+    fn list_days(&self, args: &Value) -> Result<Value, String> {
+        let (path, config) = self.journal(args)?;
+        let today = chrono::Local::now().date_naive();
+        let from = optional_date(args, "from", ParseContext::Filter, today)?;
+        let to = optional_date(args, "to", ParseContext::Filter, today)?;
+
+        let days = config
+            .journal_store()
+            .scan_days_in_range(
+                from.unwrap_or(NaiveDate::MIN),
+                to.unwrap_or(NaiveDate::MAX),
+                &path,
+            )
+            .map_err(|e| e.to_string())?;
+
+        let mut days: Vec<_> = days.into_iter().collect();
+        days.sort_by_key(|(date, _)| *date);
+        Ok(json!({
+            "days": days
+                .into_iter()
+                .map(|(date, info)| json!({
+                    "date": date,
+                    "has_incomplete_tasks": info.has_incomplete_tasks,
+                    "has_events": info.has_events,
+                }))
+                .collect::<Vec<_>>(),
+        }))
+    }
+
+    fn query(&self, args: &Value) -> Result<Value, String> {
+        let (path, config) = self.journal(args)?;
+        let query = optional_str(args, "query")?.unwrap_or_default();
+        let entries = execute_query(query, config, &path, self.context.hub_path())
+            .map_err(|e| e.to_string())?;
+        Ok(json!({
+            "entries": entries.iter().map(EntryRecord::from_entry).collect::<Vec<_>>(),
+        }))
+    }
+
+    fn add_entry(&self, args: &Value) -> Result<Value, String> {
+        let (path, config) = self.journal(args)?;
+        let text = optional_str(args, "text")?.ok_or("Missing required argument: text")?;
+        let entry_type = match optional_str(args, "type")? {
+            None => None,
+            Some("task") => Some(EntryType::task()),
+            Some("note") => Some(EntryType::Note),
+            Some("event") => Some(EntryType::Event),
+            Some(other) => return Err(format!("Unknown entry type: {other}")),
+        };
+        let today = chrono::Local::now().date_naive();
+        let date = optional_date(args, "date", ParseContext::Entry, today)?.unwrap_or(today);
+
+        let mut entry = build_entry(text, entry_type.as_ref(), &config.favorite_tags)
+            .ok_or("Entry text is empty")?;
+        if config.record_dates {
+            entry.content = storage::add_created_date(&entry.content, today);
+        }
+        let line_index = append_entries(
+            config.journal_store().as_ref(),
+            date,
+            &path,
+            vec![entry.clone()],
+        )
+        .map_err(|e| e.to_string())?;
+        let record = EntryRecord::from_entry(&storage::Entry::from_raw(
+            &entry,
+            date,
+            line_index,
+            storage::SourceType::Local,
+        ));
+        Ok(json!({ "entry": record }))
+    }
+
+    fn toggle_complete(&self, args: &Value) -> Result<Value, String> {
+        let (path, config) = self.journal(args)?;
+        let today = chrono::Local::now().date_naive();
+        let date = optional_date(args, "date", ParseContext::Filter, today)?
+            .ok_or("Missing required argument: date")?;
+        let line_index = args
+            .get("line_index")
+            .and_then(Value::as_u64)
+            .ok_or("Missing required argument: line_index")? as usize;
+
+        let completed_on = config
+            .record_dates
+            .then(|| chrono::Local::now().date_naive());
+        let entry = toggle_task(
+            config.journal_store().as_ref(),
+            date,
+            &path,
+            line_index,
+            completed_on,
+        )
+        .map_err(|e| e.to_string())?;
+        Ok(json!({ "entry": entry }))
+    }
+
+    fn list_tags(&self, args: &Value) -> Result<Value, String> {
+        let (path, config) = self.journal(args)?;
+        let tags = storage::collect_journal_tags(config.journal_store().as_ref(), &path)
+            .map_err(|e| e.to_string())?;
+        Ok(json!({ "tags": tags }))
+    }
+}
+
+/// Toggles a task, refusing anything that is not a task so agents can't silently
+/// change notes or events by using a stale line index.
+fn toggle_task(
+    store: &dyn JournalStore,
+    date: NaiveDate,
+    path: &Path,
+    line_index: usize,
+    completed_on: Option<NaiveDate>,
+) -> io::Result<EntryRecord> {
+    // Checked on the locked lines so a concurrent edit can't swap the entry
+    let entry = storage::mutate_entry(store, date, path, line_index, |entry| {
+        if !matches!(entry.entry_type, EntryType::Task { .. }) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Entry at {line_index} on {date} is not a task"),
+            ));
+        }
+        entry.toggle_complete();
+        entry.content = storage::record_completion(&entry.content, &entry.entry_type, completed_on);
+        Ok(entry.clone())
+    })?
+    .unwrap_or_else(|| {
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("No entry at {line_index} on {date}"),
+        ))
+    })?;
+    Ok(EntryRecord::from_entry(&storage::Entry::from_raw(
+        &entry,
+        date,
+        line_index,
+        storage::SourceType::Local,
+    )))
+}
+
+fn optional_str<'a>(args: &'a Value, key: &str) -> Result<Option<&'a str>, String> {
+    match args.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s)),
+        Some(_) => Err(format!("Argument {key} must be a string")),
+    }
+}
+
+fn optional_date(
+    args: &Value,
+    key: &str,
+    ctx: ParseContext,
+    today: NaiveDate,
+) -> Result<Option<NaiveDate>, String> {
+    let Some(expr) = optional_str(args, key)? else {
+        return Ok(None);
+    };
+    // Accept ISO dates as emitted by the tools themselves, plus the usual date syntax
+    NaiveDate::parse_from_str(expr, "%Y-%m-%d")
+        .ok()
+        .or_else(|| storage::parse_date(expr, ctx, today))
+        .map(Some)
+        .ok_or_else(|| format!("Invalid date: {expr}"))
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+fn journal_property() -> Value {
+    json!({
+        "type": "string",
+        "enum": ["hub", "project"],
+        "description": "Journal to use (defaults to the journal caliber would open here)",
+    })
+}
+
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "list_days",
+            "description": "List days that have entries, with open-task and event markers.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "journal": journal_property(),
+                    "from": { "type": "string", "description": "Start date (YYYY-MM-DD, MM/DD, d7, ...)" },
+                    "to": { "type": "string", "description": "End date (inclusive)" },
+                },
+            },
+        },
+        {
+            "name": "query",
+            "description": "Run a caliber filter query (e.g. \"!tasks #work d7..\") and return matching entries.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "journal": journal_property(),
+                    "query": { "type": "string", "description": "Filter syntax; empty uses default_filter" },
+                },
+            },
+        },
+        {
+            "name": "add_entry",
+            "description": "Append an entry to a day (today by default).",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "journal": journal_property(),
+                    "text": { "type": "string", "description": "Entry text, may include #tags" },
+                    "type": { "type": "string", "enum": ["task", "note", "event"] },
+                    "date": { "type": "string", "description": "Target date (YYYY-MM-DD, tomorrow, mon, ...)" },
+                },
+                "required": ["text"],
+            },
+        },
+        {
+            "name": "toggle_complete",
+            "description": "Toggle completion of the task at date/line_index as returned by query.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "journal": journal_property(),
+                    "date": { "type": "string", "description": "Day the entry lives on (YYYY-MM-DD)" },
+                    "line_index": { "type": "integer", "minimum": 0 },
+                },
+                "required": ["date", "line_index"],
+            },
+        },
+        {
+            "name": "list_tags",
+            "description": "List all tags used in the journal.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "journal": journal_property() },
+            },
+        },
+    ])
+}
+
+pub fn run(args: &[String]) -> io::Result<()> {
+    let mut slot = None;
+    let mut stdio = false;
+    for arg in args {
+        if let Some(s) = parse_slot_flag(arg) {
+            slot = Some(s);
+            continue;
+        }
+        match arg.as_str() {
+            "--stdio" => stdio = true,
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            other => return Err(usage_error(format!("Unknown option: {other}"), USAGE)),
+        }
+    }
+    if !stdio {
+        return Err(usage_error("Only --stdio transport is supported", USAGE));
+    }
+
+    let (context, config) = resolve_journal(slot)?;
+    let (hub_config, project_config) = match context.active_slot() {
+        JournalSlot::Hub => {
+            let project_config = Config::load_merged().map(|c| c.config).unwrap_or_default();
+            (config, project_config)
+        }
+        JournalSlot::Project => {
+            let hub_config = Config::load_hub().map(|c| c.config).unwrap_or_default();
+            (hub_config, config)
+        }
+    };
+    let server = Server::new(context, hub_config, project_config);
+
+    let stdin = io::stdin();
+    let mut stdout = io::stdout().lock();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = server.handle_line(&line) {
+            serde_json::to_writer(&mut stdout, &response)?;
+            writeln!(stdout)?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
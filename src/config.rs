@@ -2,14 +2,22 @@ use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock};
 
 use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
 
-use crate::storage::find_git_root;
+use crate::storage::{DailyFilesStore, JournalStore, MarkdownStore, find_git_root};
 
-const VALID_TIDY_TYPES: &[&str] = &["completed", "uncompleted", "notes", "events"];
+const VALID_TIDY_TYPES: &[&str] = &[
+    "completed",
+    "uncompleted",
+    "in_progress",
+    "cancelled",
+    "migrated",
+    "notes",
+    "events",
+];
 
 // Global profile context, initialized at startup
 static PROFILE: OnceLock<ProfileContext> = OnceLock::new();
@@ -116,6 +124,21 @@ pub struct CalendarConfig {
     /// Optional color override (ANSI color name)
     #[serde(default, skip_serializing, deserialize_with = "deserialize_color")]
     pub color: Option<Color>,
+    /// Minutes between background refreshes, overriding `calendar_refresh_interval`
+    #[serde(default)]
+    pub refresh_interval: Option<u64>,
+    /// Username for HTTP basic auth
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Shell command printing the basic auth password (e.g. `pass show calendar`)
+    #[serde(default)]
+    pub password_command: Option<String>,
+    /// Shell command printing a bearer token; takes precedence over basic auth
+    #[serde(default)]
+    pub token_command: Option<String>,
+    /// Extra HTTP headers sent with every fetch
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
 }
 
 fn deserialize_color<'de, D>(deserializer: D) -> Result<Option<Color>, D::Error>
@@ -181,6 +204,19 @@ pub enum SidebarDefault {
     /// Calendar sidebar (default)
     #[default]
     Calendar,
+    /// Habit tracker sidebar
+    Habits,
+}
+
+/// How journals are laid out on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalLayout {
+    /// One markdown file with a `# YYYY/MM/DD` header per day
+    #[default]
+    SingleFile,
+    /// One file per day at `<journal>/YYYY/MM/DD.md`
+    DailyFiles,
 }
 
 /// Global calendar visibility settings.
@@ -230,6 +266,10 @@ fn default_auto_init_project() -> bool {
     true
 }
 
+fn default_calendar_refresh_interval() -> u64 {
+    15
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Config {
     #[serde(default)]
@@ -260,6 +300,9 @@ pub struct Config {
     /// Calendar visibility settings
     #[serde(default)]
     pub calendar_visibility: CalendarVisibilityConfig,
+    /// Minutes between background calendar refreshes (0 disables them)
+    #[serde(default = "default_calendar_refresh_interval")]
+    pub calendar_refresh_interval: u64,
     /// Default sidebar to show on launch
     #[serde(default)]
     pub sidebar_default: SidebarDefault,
@@ -269,6 +312,12 @@ pub struct Config {
     /// Whether to hide footer help hints
     #[serde(default)]
     pub hide_footer_help: bool,
+    /// Whether new entries and completed tasks record the date in metadata
+    #[serde(default)]
+    pub record_dates: bool,
+    /// On-disk layout of every journal
+    #[serde(default)]
+    pub journal_layout: JournalLayout,
 }
 
 /// Raw config for deserialization - all fields are Option to distinguish "not set" from "set to default".
@@ -291,12 +340,18 @@ struct RawConfig {
     pub calendars: Option<HashMap<String, CalendarConfig>>,
     /// Calendar visibility settings
     pub calendar_visibility: Option<CalendarVisibilityConfig>,
+    /// Minutes between background calendar refreshes
+    pub calendar_refresh_interval: Option<u64>,
     /// Default sidebar to show on launch
     pub sidebar_default: Option<SidebarDefault>,
     /// Whether defer action should skip weekends
     pub defer_skip_weekends: Option<bool>,
     /// Whether to hide footer help hints
     pub hide_footer_help: Option<bool>,
+    /// Whether new entries and completed tasks record the date in metadata
+    pub record_dates: Option<bool>,
+    /// On-disk layout of every journal
+    pub journal_layout: Option<JournalLayout>,
 }
 
 impl RawConfig {
@@ -319,9 +374,14 @@ impl RawConfig {
                 .unwrap_or_else(default_auto_init_project),
             calendars: self.calendars.unwrap_or_default(),
             calendar_visibility: self.calendar_visibility.unwrap_or_default(),
+            calendar_refresh_interval: self
+                .calendar_refresh_interval
+                .unwrap_or_else(default_calendar_refresh_interval),
             sidebar_default: self.sidebar_default.unwrap_or_default(),
             defer_skip_weekends: self.defer_skip_weekends.unwrap_or(false),
             hide_footer_help: self.hide_footer_help.unwrap_or(false),
+            record_dates: self.record_dates.unwrap_or(false),
+            journal_layout: self.journal_layout.unwrap_or_default(),
         }
     }
 
@@ -330,8 +390,11 @@ impl RawConfig {
     /// - hub_file: base only (hub-specific)
     /// - journal_file: overlay only (project-specific)
     /// - auto_init_project: base only (global setting)
-    /// - calendars: base only (security - URLs shouldn't be in repos)
+    /// - calendars: base only (security - URLs, credentials and secret commands
+    ///   shouldn't come from repos)
     /// - calendar_visibility: base only (global setting)
+    /// - calendar_refresh_interval: base only (global setting)
+    /// - journal_layout: base only (hub and project journals share one store)
     fn merge_over(self, base: RawConfig) -> RawConfig {
         RawConfig {
             hub_file: base.hub_file,
@@ -347,9 +410,12 @@ impl RawConfig {
             auto_init_project: base.auto_init_project,
             calendars: base.calendars,
             calendar_visibility: base.calendar_visibility,
+            calendar_refresh_interval: base.calendar_refresh_interval,
             sidebar_default: base.sidebar_default,
             defer_skip_weekends: self.defer_skip_weekends.or(base.defer_skip_weekends),
             hide_footer_help: self.hide_footer_help.or(base.hide_footer_help),
+            record_dates: self.record_dates.or(base.record_dates),
+            journal_layout: base.journal_layout,
         }
     }
 }
@@ -432,6 +498,17 @@ impl Config {
         self.calendars.get(id)
     }
 
+    /// How often a calendar refreshes in the background, or None if it only
+    /// refreshes on launch and on demand.
+    #[must_use]
+    pub fn refresh_interval_for(&self, id: &str) -> Option<std::time::Duration> {
+        let minutes = self
+            .get_calendar(id)
+            .and_then(|cal| cal.refresh_interval)
+            .unwrap_or(self.calendar_refresh_interval);
+        (minutes > 0).then(|| std::time::Duration::from_secs(minutes * 60))
+    }
+
     /// Get the color for a calendar, using explicit color or cycling through defaults.
     #[must_use]
     pub fn calendar_color(&self, id: &str) -> Color {
@@ -451,6 +528,15 @@ impl Config {
         CALENDAR_COLORS[index % CALENDAR_COLORS.len()]
     }
 
+    /// Store for the configured journal layout.
+    #[must_use]
+    pub fn journal_store(&self) -> Arc<dyn JournalStore> {
+        match self.journal_layout {
+            JournalLayout::SingleFile => Arc::new(MarkdownStore),
+            JournalLayout::DailyFiles => Arc::new(DailyFilesStore),
+        }
+    }
+
     /// Check if any calendars are configured.
     #[must_use]
     pub fn has_calendars(&self) -> bool {
@@ -568,6 +654,12 @@ pub fn get_hub_config_path() -> PathBuf {
     get_config_dir().join("hub_config.toml")
 }
 
+/// Directory holding cached calendar feeds, one `<id>.ics` and `<id>.json`
+/// per calendar.
+pub fn get_calendar_cache_dir() -> PathBuf {
+    get_config_dir().join("calendar_cache")
+}
+
 pub fn get_default_journal_path() -> PathBuf {
     get_config_dir().join("hub_journal.md")
 }
@@ -490,6 +490,7 @@ pub fn parse_action_id(s: &str) -> Option<KeyActionId> {
         "remove_last_tag" => Some(KeyActionId::RemoveLastTag),
         "remove_all_tags" => Some(KeyActionId::RemoveAllTags),
         "cycle_entry_type" => Some(KeyActionId::CycleEntryType),
+        "cycle_task_state" => Some(KeyActionId::CycleTaskState),
         "selection" => Some(KeyActionId::Selection),
         "selection_extend_range" => Some(KeyActionId::SelectionExtendRange),
         "toggle_filter_view" => Some(KeyActionId::ToggleFilterView),
@@ -497,9 +498,17 @@ pub fn parse_action_id(s: &str) -> Option<KeyActionId> {
         "toggle_journal" => Some(KeyActionId::ToggleJournal),
         "command_palette" => Some(KeyActionId::CommandPalette),
         "refresh" => Some(KeyActionId::Refresh),
+        "cycle_filter_sort" => Some(KeyActionId::CycleFilterSort),
+        "cycle_filter_group" => Some(KeyActionId::CycleFilterGroup),
+        "toggle_group_collapse" => Some(KeyActionId::ToggleGroupCollapse),
         "save_and_new" => Some(KeyActionId::SaveAndNew),
         "reorder_mode" => Some(KeyActionId::ReorderMode),
         "tidy_entries" => Some(KeyActionId::TidyEntries),
+        "indent_entry" => Some(KeyActionId::IndentEntry),
+        "outdent_entry" => Some(KeyActionId::OutdentEntry),
+        "toggle_collapse" => Some(KeyActionId::ToggleCollapse),
+        "skip_occurrence" => Some(KeyActionId::SkipOccurrence),
+        "move_occurrence" => Some(KeyActionId::MoveOccurrence),
         "hide" => Some(KeyActionId::Hide),
         "autocomplete" => Some(KeyActionId::Autocomplete),
         "toggle_calendar_sidebar" => Some(KeyActionId::ToggleCalendarSidebar),
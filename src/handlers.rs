@@ -9,7 +9,7 @@ use crate::config::Config;
 use crate::cursor::CursorBuffer;
 use crate::dispatch::KeySpec;
 use crate::registry::{KeyActionId, KeyContext};
-use crate::storage;
+use crate::storage::{self, ConflictResolution};
 
 fn shifted_char_to_digit(c: char) -> Option<char> {
     match c {
@@ -127,6 +127,13 @@ fn dispatch_entry_operation(app: &mut App, action: KeyActionId) -> io::Result<()
                 app.cycle_current_entry_type()?;
             }
         },
+        CycleTaskState => {
+            dispatch_entry_op(
+                app,
+                App::cycle_selected_task_states,
+                App::cycle_current_task_state,
+            )?;
+        }
         _ => {}
     }
     Ok(())
@@ -164,6 +171,7 @@ fn dispatch_action(app: &mut App, action: KeyActionId) -> io::Result<bool> {
             | RemoveLastTag
             | RemoveAllTags
             | CycleEntryType
+            | CycleTaskState
     ) {
         dispatch_entry_operation(app, action)?;
         return Ok(true);
@@ -189,7 +197,7 @@ fn dispatch_action(app: &mut App, action: KeyActionId) -> io::Result<bool> {
             InputMode::CommandPalette(_) => app.close_command_palette(),
             InputMode::FilterPrompt => app.cancel_filter_prompt(),
             InputMode::DatePicker(_) => app.close_date_picker(),
-            InputMode::Normal | InputMode::Confirm(_) => {}
+            InputMode::Normal | InputMode::Confirm(_) | InputMode::Conflict(_) => {}
         },
         MoveDown => match &app.input_mode {
             InputMode::Reorder => app.reorder_move_down(),
@@ -239,6 +247,9 @@ fn dispatch_action(app: &mut App, action: KeyActionId) -> io::Result<bool> {
         ToggleCalendarSidebar => app.toggle_calendar_sidebar(),
         ToggleAgenda => app.toggle_agenda(),
         Refresh => app.refresh_filter()?,
+        CycleFilterSort => app.cycle_filter_sort()?,
+        CycleFilterGroup => app.cycle_filter_group()?,
+        ToggleGroupCollapse => app.toggle_group_collapse(),
         SaveAndNew => {
             app.accept_hint();
             app.clear_hints();
@@ -252,6 +263,11 @@ fn dispatch_action(app: &mut App, action: KeyActionId) -> io::Result<bool> {
             }
         }
         TidyEntries => app.tidy_entries(),
+        IndentEntry => app.indent_current_entry()?,
+        OutdentEntry => app.outdent_current_entry()?,
+        ToggleCollapse => app.toggle_collapse_current(),
+        SkipOccurrence => app.skip_current_occurrence()?,
+        MoveOccurrence => app.open_move_occurrence_picker(),
         Hide => app.toggle_hide_completed(),
         Autocomplete => {
             app.accept_hint();
@@ -342,7 +358,9 @@ pub fn handle_command_palette_key(app: &mut App, key: KeyEvent) -> io::Result<()
             KeyActionId::MoveRight => app.command_palette_next_tab(),
             KeyActionId::Submit => {
                 app.execute_selected_palette_item()?;
-                app.close_command_palette();
+                if matches!(app.input_mode, InputMode::CommandPalette(_)) {
+                    app.close_command_palette();
+                }
             }
             KeyActionId::Delete => {
                 app.palette_delete_selected()?;
@@ -440,20 +458,10 @@ pub fn handle_confirm_key(app: &mut App, key: KeyCode) -> io::Result<()> {
                 let config_load = Config::load_merged_from(&root).unwrap_or_default();
                 let journal_path = config_load.config.get_project_journal_path(&root);
 
-                if !journal_path.exists() {
-                    // Create parent directories if journal is at custom location
-                    if let Some(parent) = journal_path.parent()
-                        && let Err(e) = std::fs::create_dir_all(parent)
-                    {
-                        app.set_error(format!("Failed to create journal directory: {e}"));
-                        app.input_mode = InputMode::Normal;
-                        return Ok(());
-                    }
-                    if let Err(e) = std::fs::write(&journal_path, "") {
-                        app.set_error(format!("Failed to create journal: {e}"));
-                        app.input_mode = InputMode::Normal;
-                        return Ok(());
-                    }
+                if let Err(e) = app.store.create_journal(&journal_path) {
+                    app.set_error(format!("Failed to create journal: {e}"));
+                    app.input_mode = InputMode::Normal;
+                    return Ok(());
                 }
 
                 if std::env::var("CALIBER_SKIP_REGISTRY").is_err() {
@@ -475,6 +483,9 @@ pub fn handle_confirm_key(app: &mut App, key: KeyCode) -> io::Result<()> {
             ConfirmContext::DeleteTagFromCompleted(tag) => {
                 app.confirm_delete_tag_from_completed(&tag)?;
             }
+            ConfirmContext::RepairJournal(_) => {
+                app.confirm_repair_journal()?;
+            }
         },
         KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => match context {
             ConfirmContext::CreateProjectJournal => {
@@ -484,6 +495,9 @@ pub fn handle_confirm_key(app: &mut App, key: KeyCode) -> io::Result<()> {
             ConfirmContext::DeleteTag(_) | ConfirmContext::DeleteTagFromCompleted(_) => {
                 app.open_palette(CommandPaletteMode::Tags);
             }
+            ConfirmContext::RepairJournal(_) => {
+                app.input_mode = InputMode::Normal;
+            }
         },
         _ => {}
     }
@@ -491,6 +505,23 @@ pub fn handle_confirm_key(app: &mut App, key: KeyCode) -> io::Result<()> {
     Ok(())
 }
 
+pub fn handle_conflict_key(app: &mut App, key: KeyCode) -> io::Result<()> {
+    match key {
+        KeyCode::Char('m') | KeyCode::Char('M') => {
+            app.resolve_conflict(ConflictResolution::Mine)?
+        }
+        KeyCode::Char('t') | KeyCode::Char('T') => {
+            app.resolve_conflict(ConflictResolution::Theirs)?;
+        }
+        KeyCode::Char('b') | KeyCode::Char('B') => {
+            app.resolve_conflict(ConflictResolution::Both)?
+        }
+        KeyCode::Esc => app.defer_conflict(),
+        _ => {}
+    }
+    Ok(())
+}
+
 pub fn handle_selection_key(app: &mut App, key: KeyEvent) -> io::Result<()> {
     let KeyEvent {
         code, modifiers, ..
@@ -555,10 +586,10 @@ pub fn handle_date_picker_key(app: &mut App, key: KeyEvent) -> io::Result<()> {
                 return Ok(());
             };
             match key.code {
-                KeyCode::Char(c) if c.is_ascii_digit() || c == '/' => {
-                    if state.buffer.content().len() < 10 {
-                        state.buffer.insert_char(c);
-                    }
+                KeyCode::Char(c)
+                    if (c.is_ascii_digit() || c == '/') && state.buffer.content().len() < 10 =>
+                {
+                    state.buffer.insert_char(c);
                 }
                 KeyCode::Backspace => {
                     state.buffer.delete_char_before();
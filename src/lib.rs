@@ -1,5 +1,6 @@
 pub mod app;
 pub mod calendar;
+pub mod cli;
 pub mod config;
 pub mod cursor;
 pub mod dispatch;
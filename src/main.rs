@@ -10,10 +10,10 @@ use crossterm::{
 use ratatui::{Terminal, backend::CrosstermBackend};
 
 use caliber::app::{App, InputMode};
-use caliber::config::{self, Config, get_profile_project_root, has_custom_profile, init_profile};
+use caliber::config::{self, Config, has_custom_profile, init_profile};
 use caliber::storage::{JournalContext, JournalSlot};
 use caliber::ui::surface::Surface;
-use caliber::{handlers, storage, testrun, ui};
+use caliber::{cli, handlers, storage, testrun, ui};
 
 fn main() -> Result<(), io::Error> {
     let args: Vec<String> = std::env::args().collect();
@@ -31,11 +31,17 @@ fn main() -> Result<(), io::Error> {
 
     init_profile(temp_dir.as_deref().or(testrun_path.as_deref()));
 
-    if remaining_args.first().map(String::as_str) == Some("init") {
-        return init_config();
+    match remaining_args.first().map(String::as_str) {
+        Some("init") => return init_config(),
+        Some("add") => return run_headless(cli::add::run(&remaining_args[1..]), temp_dir),
+        Some("query") => return run_headless(cli::query::run(&remaining_args[1..]), temp_dir),
+        Some("doctor") => return run_headless(cli::doctor::run(&remaining_args[1..]), temp_dir),
+        Some("migrate") => return run_headless(cli::migrate::run(&remaining_args[1..]), temp_dir),
+        Some("serve") => return run_headless(cli::serve::run(&remaining_args[1..]), temp_dir),
+        _ => {}
     }
 
-    let (project_path, active_slot) = if let Some(path) = detect_project_with_profile() {
+    let (project_path, active_slot) = if let Some(path) = storage::detect_project_with_profile() {
         (Some(path), JournalSlot::Project)
     } else {
         (None, JournalSlot::Hub)
@@ -116,6 +122,19 @@ fn init_config() -> io::Result<()> {
     }
 }
 
+/// Runs a headless subcommand, reporting errors on stderr with a non-zero exit code.
+fn run_headless(result: io::Result<()>, temp_dir: Option<PathBuf>) -> io::Result<()> {
+    if let Some(temp) = temp_dir {
+        testrun::cleanup(temp);
+    }
+
+    if let Err(err) = result {
+        eprintln!("Error: {err}");
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
 fn run_app<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
     config: Config,
@@ -158,12 +177,7 @@ fn run_app<B: ratatui::backend::Backend>(
             fs::create_dir_all(&caliber_dir)?;
 
             let journal_path = app.config.get_project_journal_path(&git_root);
-            if !journal_path.exists() {
-                if let Some(parent) = journal_path.parent() {
-                    fs::create_dir_all(parent)?;
-                }
-                fs::write(&journal_path, "")?;
-            }
+            app.store.create_journal(&journal_path)?;
 
             let mut registry = storage::ProjectRegistry::load();
             if registry.find_by_path(&caliber_dir).is_none() {
@@ -190,10 +204,12 @@ fn run_app<B: ratatui::backend::Backend>(
 
         app.poll_calendar_results();
 
-        // Periodically check for external file changes (~1 second intervals)
+        // Periodically check for external file changes and due calendar
+        // refreshes (~1 second intervals)
         tick_counter = tick_counter.wrapping_add(1);
         if tick_counter.is_multiple_of(60) {
             app.check_external_changes();
+            app.tick_calendar_refresh();
         }
 
         if event::poll(std::time::Duration::from_millis(16))? {
@@ -205,21 +221,34 @@ fn run_app<B: ratatui::backend::Backend>(
 
                     app.status_message = None;
 
-                    match &app.input_mode {
-                        InputMode::Normal => handlers::handle_normal_key(&mut app, key)?,
-                        InputMode::Edit(_) => handlers::handle_edit_key(&mut app, key),
-                        InputMode::Reorder => handlers::handle_reorder_key(&mut app, key),
-                        InputMode::Confirm(_) => handlers::handle_confirm_key(&mut app, key.code)?,
-                        InputMode::Selection(_) => handlers::handle_selection_key(&mut app, key)?,
+                    let result = match &app.input_mode {
+                        InputMode::Normal => handlers::handle_normal_key(&mut app, key),
+                        InputMode::Edit(_) => {
+                            handlers::handle_edit_key(&mut app, key);
+                            Ok(())
+                        }
+                        InputMode::Reorder => {
+                            handlers::handle_reorder_key(&mut app, key);
+                            Ok(())
+                        }
+                        InputMode::Confirm(_) => handlers::handle_confirm_key(&mut app, key.code),
+                        InputMode::Conflict(_) => handlers::handle_conflict_key(&mut app, key.code),
+                        InputMode::Selection(_) => handlers::handle_selection_key(&mut app, key),
                         InputMode::CommandPalette(_) => {
-                            handlers::handle_command_palette_key(&mut app, key)?;
+                            handlers::handle_command_palette_key(&mut app, key)
                         }
                         InputMode::FilterPrompt => {
-                            handlers::handle_filter_prompt_key(&mut app, key)?;
+                            handlers::handle_filter_prompt_key(&mut app, key)
                         }
-                        InputMode::DatePicker(_) => {
-                            handlers::handle_date_picker_key(&mut app, key)?;
+                        InputMode::DatePicker(_) => handlers::handle_date_picker_key(&mut app, key),
+                    };
+
+                    // Another process holding the journal lock is transient; report it and keep running
+                    match result {
+                        Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                            app.set_error(e.to_string())
                         }
+                        other => other?,
                     }
                 }
                 Event::Paste(text) => {
@@ -247,18 +276,6 @@ fn run_app<B: ratatui::backend::Backend>(
     Ok(())
 }
 
-fn detect_project_with_profile() -> Option<PathBuf> {
-    if let Some(project_root) = get_profile_project_root() {
-        let config_load = Config::load_merged_from(project_root).ok()?;
-        let journal_path = config_load.config.get_project_journal_path(project_root);
-        if journal_path.exists() {
-            return Some(journal_path);
-        }
-    }
-
-    storage::detect_project_journal()
-}
-
 fn parse_record_arg(args: &[String]) -> (Option<String>, Vec<String>) {
     let Some(record_pos) = args.iter().position(|a| a == "--record") else {
         return (None, args.to_vec());
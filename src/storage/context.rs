@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use crate::config::Config;
+use crate::config::{Config, get_profile_project_root};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum JournalSlot {
@@ -89,9 +89,31 @@ pub fn detect_project_journal() -> Option<PathBuf> {
     let config_load = Config::load_merged_from(&root).ok()?;
     let journal_path = config_load.config.get_project_journal_path(&root);
 
-    if journal_path.exists() {
+    if config_load
+        .config
+        .journal_store()
+        .journal_exists(&journal_path)
+    {
         Some(journal_path)
     } else {
         None
     }
 }
+
+/// Detect project journal path, preferring the profile's fake project root if one is set.
+#[must_use]
+pub fn detect_project_with_profile() -> Option<PathBuf> {
+    if let Some(project_root) = get_profile_project_root() {
+        let config_load = Config::load_merged_from(project_root).ok()?;
+        let journal_path = config_load.config.get_project_journal_path(project_root);
+        if config_load
+            .config
+            .journal_store()
+            .journal_exists(&journal_path)
+        {
+            return Some(journal_path);
+        }
+    }
+
+    detect_project_journal()
+}
@@ -0,0 +1,175 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use chrono::{Datelike, NaiveDate};
+
+use super::entries::{Line, parse_lines, serialize_lines};
+use super::lock::lock_journal;
+use super::persistence::write_atomic;
+use super::store::{JournalDay, JournalStore, is_empty_day};
+
+/// Keeps each day in its own markdown file at `<journal>/YYYY/MM/DD.md`, where
+/// `<journal>` is the journal path without its extension. Day files hold only the
+/// entries, with no date header, like a daily-notes folder.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DailyFilesStore;
+
+impl DailyFilesStore {
+    /// Folder holding the day files for a journal path (`journal.md` -> `journal/`).
+    #[must_use]
+    pub fn journal_dir(path: &Path) -> PathBuf {
+        path.with_extension("")
+    }
+
+    #[must_use]
+    pub fn day_path(date: NaiveDate, path: &Path) -> PathBuf {
+        Self::journal_dir(path)
+            .join(format!("{:04}", date.year()))
+            .join(format!("{:02}", date.month()))
+            .join(format!("{:02}.md", date.day()))
+    }
+}
+
+fn read_day_file(file: &Path) -> io::Result<Vec<Line>> {
+    match fs::read_to_string(file) {
+        Ok(content) => Ok(parse_lines(content.replace("\r\n", "\n").trim_end())),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Writes a day file, removing it (and any folders it leaves empty) once the day is empty.
+fn write_day_file(file: &Path, root: &Path, lines: &[Line]) -> io::Result<()> {
+    if !is_empty_day(lines) {
+        return write_atomic(file, &format!("{}\n", serialize_lines(lines).trim_end()));
+    }
+
+    match fs::remove_file(file) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    }
+    let mut dir = file.parent();
+    while let Some(d) = dir
+        && d != root
+        && fs::remove_dir(d).is_ok()
+    {
+        dir = d.parent();
+    }
+    Ok(())
+}
+
+/// Numeric folder or file stem with exactly `width` digits.
+fn parse_component(name: &str, width: usize) -> Option<u32> {
+    (name.len() == width && name.bytes().all(|b| b.is_ascii_digit()))
+        .then(|| name.parse().ok())
+        .flatten()
+}
+
+fn subdirs(dir: &Path, width: usize) -> io::Result<Vec<(u32, PathBuf)>> {
+    let mut result = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir()
+            && let Some(n) = parse_component(&entry.file_name().to_string_lossy(), width)
+        {
+            result.push((n, entry.path()));
+        }
+    }
+    Ok(result)
+}
+
+/// Dates with a day file, sorted. Files that don't follow the layout are ignored.
+fn day_files(root: &Path) -> io::Result<Vec<(NaiveDate, PathBuf)>> {
+    if !root.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut days = Vec::new();
+    for (year, year_dir) in subdirs(root, 4)? {
+        for (month, month_dir) in subdirs(&year_dir, 2)? {
+            for entry in fs::read_dir(&month_dir)? {
+                let entry = entry?;
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if let Some(day) = name.strip_suffix(".md").and_then(|s| parse_component(s, 2))
+                    && let Some(date) = NaiveDate::from_ymd_opt(year as i32, month, day)
+                    && entry.file_type()?.is_file()
+                {
+                    days.push((date, entry.path()));
+                }
+            }
+        }
+    }
+    days.sort_by_key(|(date, _)| *date);
+    Ok(days)
+}
+
+impl JournalStore for DailyFilesStore {
+    fn load_day_lines(&self, date: NaiveDate, path: &Path) -> io::Result<Vec<Line>> {
+        read_day_file(&Self::day_path(date, path))
+    }
+
+    fn modify_day(
+        &self,
+        date: NaiveDate,
+        path: &Path,
+        f: &mut dyn FnMut(&mut Vec<Line>),
+    ) -> io::Result<()> {
+        let root = Self::journal_dir(path);
+        let _lock = lock_journal(&root)?;
+        let file = Self::day_path(date, path);
+        let mut lines = read_day_file(&file)?;
+        let original = lines.clone();
+        f(&mut lines);
+        if lines != original {
+            write_day_file(&file, &root, &lines)?;
+        }
+        Ok(())
+    }
+
+    fn journal_exists(&self, path: &Path) -> bool {
+        Self::journal_dir(path).is_dir()
+    }
+
+    fn create_journal(&self, path: &Path) -> io::Result<()> {
+        fs::create_dir_all(Self::journal_dir(path))
+    }
+
+    fn modified(&self, date: NaiveDate, path: &Path) -> Option<SystemTime> {
+        fs::metadata(Self::day_path(date, path))
+            .and_then(|m| m.modified())
+            .ok()
+    }
+
+    fn days(&self, path: &Path) -> io::Result<Vec<JournalDay>> {
+        day_files(&Self::journal_dir(path))?
+            .into_iter()
+            .map(|(date, file)| {
+                Ok(JournalDay {
+                    date,
+                    lines: read_day_file(&file)?,
+                })
+            })
+            .collect()
+    }
+
+    fn modify_days(
+        &self,
+        path: &Path,
+        f: &mut dyn FnMut(NaiveDate, &mut Vec<Line>),
+    ) -> io::Result<()> {
+        let root = Self::journal_dir(path);
+        let _lock = lock_journal(&root)?;
+        for (date, file) in day_files(&root)? {
+            let original = read_day_file(&file)?;
+            let mut lines = original.clone();
+            f(date, &mut lines);
+            if lines != original {
+                write_day_file(&file, &root, &lines)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::LazyLock;
+
+use chrono::NaiveDate;
+use regex::Regex;
+
+use super::entries::{Line, parse_lines};
+use super::filter::{
+    DONE_META_REGEX, MOVED_META_REGEX, RECURRING_REGEX, SKIP_META_REGEX, extract_recurring_pattern,
+};
+use super::persistence::parse_day_header;
+
+/// Lines that look like a day header but don't use the `# YYYY/MM/DD` format
+static HEADER_LIKE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^#\s*(\d{2,4}[/.-]\d{1,2}[/.-]\d{1,4})\s*$").unwrap());
+
+/// Any @every-* token, valid or not
+static EVERY_TOKEN_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)@every-\S*").unwrap());
+
+/// Kind of structural problem found in a journal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IssueKind {
+    /// A second `# YYYY/MM/DD` header for a date that already appeared
+    DuplicateHeader { date: NaiveDate, first_line: usize },
+    /// A day header dated before an earlier header in the file
+    OutOfOrderHeader { date: NaiveDate },
+    /// A line that looks like a day header but can't be parsed as one
+    UnparseableHeader { repaired: Option<NaiveDate> },
+    /// `- [X]` instead of `- [x]`
+    UppercaseCheckbox,
+    /// Lines ending in `\r\n`
+    CrlfLineEndings { count: usize },
+    /// `<!-- done: -->`, `<!-- skip: -->` or `<!-- moved: -->` metadata on an
+    /// entry without an @every-* pattern
+    OrphanedOccurrenceMeta,
+    /// An @every-* token that isn't a known recurring pattern
+    UnknownRecurringPattern(String),
+}
+
+/// A problem found in a journal, with its 1-based line number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournalIssue {
+    pub line: usize,
+    pub kind: IssueKind,
+}
+
+impl JournalIssue {
+    /// Returns true if `repair_journal` fixes this issue.
+    #[must_use]
+    pub fn is_fixable(&self) -> bool {
+        match &self.kind {
+            IssueKind::UnparseableHeader { repaired } => repaired.is_some(),
+            IssueKind::UnknownRecurringPattern(_) => false,
+            _ => true,
+        }
+    }
+}
+
+impl fmt::Display for JournalIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            IssueKind::DuplicateHeader { date, first_line } => write!(
+                f,
+                "duplicate day header {} (first at line {first_line})",
+                date.format("%Y/%m/%d")
+            ),
+            IssueKind::OutOfOrderHeader { date } => {
+                write!(f, "day {} is out of order", date.format("%Y/%m/%d"))
+            }
+            IssueKind::UnparseableHeader {
+                repaired: Some(date),
+            } => write!(
+                f,
+                "malformed day header (should be # {})",
+                date.format("%Y/%m/%d")
+            ),
+            IssueKind::UnparseableHeader { repaired: None } => {
+                write!(f, "unparseable day header")
+            }
+            IssueKind::UppercaseCheckbox => write!(f, "uppercase checkbox [X]"),
+            IssueKind::CrlfLineEndings { count } => {
+                write!(f, "{count} line(s) end with CRLF")
+            }
+            IssueKind::OrphanedOccurrenceMeta => {
+                write!(f, "occurrence metadata on an entry that isn't recurring")
+            }
+            IssueKind::UnknownRecurringPattern(token) => {
+                write!(f, "unknown recurring pattern {token}")
+            }
+        }
+    }
+}
+
+/// Tries to read a header-like line with a non-canonical date (ISO dashes, missing padding).
+fn parse_lenient_header(line: &str) -> Option<NaiveDate> {
+    let caps = HEADER_LIKE_REGEX.captures(line)?;
+    let date_str = caps[1].replace(['-', '.'], "/");
+    NaiveDate::parse_from_str(&date_str, "%Y/%m/%d").ok()
+}
+
+fn is_uppercase_checkbox(line: &str) -> bool {
+    line.trim_start().starts_with("- [X] ")
+}
+
+fn entry_content(line: &str) -> Option<String> {
+    match parse_lines(line).into_iter().next() {
+        Some(Line::Entry(entry)) => Some(entry.content),
+        _ => None,
+    }
+}
+
+const OCCURRENCE_META_REGEXES: [&LazyLock<Regex>; 3] =
+    [&DONE_META_REGEX, &SKIP_META_REGEX, &MOVED_META_REGEX];
+
+fn has_orphaned_occurrence_meta(content: &str) -> bool {
+    OCCURRENCE_META_REGEXES
+        .iter()
+        .any(|regex| regex.is_match(content))
+        && !RECURRING_REGEX.is_match(content)
+}
+
+/// Scans a journal for structural problems that can cause entries to be
+/// silently hidden or lost.
+#[must_use]
+pub fn diagnose_journal(journal: &str) -> Vec<JournalIssue> {
+    let mut issues = Vec::new();
+    let mut first_seen: HashMap<NaiveDate, usize> = HashMap::new();
+    let mut latest: Option<NaiveDate> = None;
+    let mut current_day: Option<NaiveDate> = None;
+    let mut crlf_count = 0;
+    let mut first_crlf_line = 0;
+
+    for (idx, raw_line) in journal.split('\n').enumerate() {
+        let line_no = idx + 1;
+        let line = match raw_line.strip_suffix('\r') {
+            Some(stripped) => {
+                crlf_count += 1;
+                if first_crlf_line == 0 {
+                    first_crlf_line = line_no;
+                }
+                stripped
+            }
+            None => raw_line,
+        };
+
+        if let Some(date) = parse_day_header(line) {
+            current_day = Some(date);
+            if let Some(&first_line) = first_seen.get(&date) {
+                issues.push(JournalIssue {
+                    line: line_no,
+                    kind: IssueKind::DuplicateHeader { date, first_line },
+                });
+            } else {
+                if latest.is_some_and(|latest| date < latest) {
+                    issues.push(JournalIssue {
+                        line: line_no,
+                        kind: IssueKind::OutOfOrderHeader { date },
+                    });
+                }
+                first_seen.insert(date, line_no);
+            }
+            latest = latest.max(Some(date));
+            continue;
+        }
+
+        if HEADER_LIKE_REGEX.is_match(line) {
+            issues.push(JournalIssue {
+                line: line_no,
+                kind: IssueKind::UnparseableHeader {
+                    repaired: parse_lenient_header(line),
+                },
+            });
+            continue;
+        }
+
+        let Some(day) = current_day else {
+            continue;
+        };
+
+        if is_uppercase_checkbox(line) {
+            issues.push(JournalIssue {
+                line: line_no,
+                kind: IssueKind::UppercaseCheckbox,
+            });
+        }
+
+        let Some(content) = entry_content(line) else {
+            continue;
+        };
+        if has_orphaned_occurrence_meta(&content) {
+            issues.push(JournalIssue {
+                line: line_no,
+                kind: IssueKind::OrphanedOccurrenceMeta,
+            });
+        }
+        for token in EVERY_TOKEN_REGEX.find_iter(&content) {
+            if extract_recurring_pattern(token.as_str(), day).is_none() {
+                issues.push(JournalIssue {
+                    line: line_no,
+                    kind: IssueKind::UnknownRecurringPattern(token.as_str().to_string()),
+                });
+            }
+        }
+    }
+
+    if crlf_count > 0 {
+        issues.push(JournalIssue {
+            line: first_crlf_line,
+            kind: IssueKind::CrlfLineEndings { count: crlf_count },
+        });
+    }
+
+    issues.sort_by_key(|issue| issue.line);
+    issues
+}
+
+/// Normalizes a single line: LF endings, lowercase checkboxes, canonical headers,
+/// and no done metadata on non-recurring entries.
+fn repair_line(line: &str) -> String {
+    let line = line.strip_suffix('\r').unwrap_or(line);
+
+    if parse_day_header(line).is_none()
+        && let Some(date) = parse_lenient_header(line)
+    {
+        return format!("# {}", date.format("%Y/%m/%d"));
+    }
+
+    let mut line = if is_uppercase_checkbox(line) {
+        line.replacen("- [X] ", "- [x] ", 1)
+    } else {
+        line.to_string()
+    };
+
+    if entry_content(&line).is_some_and(|content| has_orphaned_occurrence_meta(&content)) {
+        for regex in OCCURRENCE_META_REGEXES {
+            line = regex.replace(&line, "").trim_end().to_string();
+        }
+    }
+    line
+}
+
+/// Rewrites a journal into canonical form: one header per day in ascending order,
+/// duplicate days merged in file order, empty days dropped, and fixable line-level
+/// issues repaired. Text before the first day header is preserved.
+#[must_use]
+pub fn repair_journal(journal: &str) -> String {
+    let mut preamble: Vec<String> = Vec::new();
+    let mut days: Vec<(NaiveDate, String, Vec<String>)> = Vec::new();
+    let mut day_index: HashMap<NaiveDate, usize> = HashMap::new();
+    let mut current: Option<usize> = None;
+
+    for raw_line in journal.lines() {
+        let line = repair_line(raw_line);
+
+        if let Some(date) = parse_day_header(&line) {
+            let idx = *day_index.entry(date).or_insert_with(|| {
+                days.push((date, line.trim_end().to_string(), Vec::new()));
+                days.len() - 1
+            });
+            // Merged chunks continue directly after the previous chunk's last line
+            let lines = &mut days[idx].2;
+            while lines.last().is_some_and(|l| l.trim().is_empty()) {
+                lines.pop();
+            }
+            current = Some(idx);
+            continue;
+        }
+
+        match current {
+            Some(idx) => days[idx].2.push(line),
+            None => preamble.push(line),
+        }
+    }
+
+    days.sort_by_key(|(date, _, _)| *date);
+
+    let mut sections: Vec<String> = Vec::new();
+    let preamble = preamble.join("\n");
+    if !preamble.trim().is_empty() {
+        sections.push(preamble.trim_end().to_string());
+    }
+    for (_, header, lines) in days {
+        let content = lines.join("\n");
+        let content = content.trim_end();
+        if content.trim().is_empty() {
+            continue;
+        }
+        sections.push(format!("{header}\n{content}"));
+    }
+
+    if sections.is_empty() {
+        String::new()
+    } else {
+        sections.join("\n\n") + "\n"
+    }
+}
@@ -1,18 +1,82 @@
+use std::path::PathBuf;
+
 use chrono::{Datelike, NaiveDate, Weekday};
 
+/// Lifecycle of a task, written as the character between the brackets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TaskState {
+    #[default]
+    Open,
+    InProgress,
+    Done,
+    Cancelled,
+    Migrated,
+}
+
+impl TaskState {
+    pub const ALL: [TaskState; 5] = [
+        Self::Open,
+        Self::InProgress,
+        Self::Done,
+        Self::Cancelled,
+        Self::Migrated,
+    ];
+
+    /// Done, cancelled and migrated tasks need no further attention.
+    #[must_use]
+    pub fn is_closed(self) -> bool {
+        matches!(self, Self::Done | Self::Cancelled | Self::Migrated)
+    }
+
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::Open => Self::InProgress,
+            Self::InProgress => Self::Done,
+            Self::Done => Self::Cancelled,
+            Self::Cancelled => Self::Migrated,
+            Self::Migrated => Self::Open,
+        }
+    }
+
+    /// Stable lowercase name used by tidy order, the CLI and JSON output.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::InProgress => "in_progress",
+            Self::Done => "done",
+            Self::Cancelled => "cancelled",
+            Self::Migrated => "migrated",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum EntryType {
-    Task { completed: bool },
+    Task { state: TaskState },
     Note,
     Event,
 }
 
 impl EntryType {
+    #[must_use]
+    pub fn task() -> Self {
+        Self::Task {
+            state: TaskState::Open,
+        }
+    }
+
     #[must_use]
     pub fn prefix(&self) -> &'static str {
         match self {
-            Self::Task { completed: false } => "- [ ] ",
-            Self::Task { completed: true } => "- [x] ",
+            Self::Task { state } => match state {
+                TaskState::Open => "- [ ] ",
+                TaskState::InProgress => "- [/] ",
+                TaskState::Done => "- [x] ",
+                TaskState::Cancelled => "- [-] ",
+                TaskState::Migrated => "- [>] ",
+            },
             Self::Note => "- ",
             Self::Event => "* ",
         }
@@ -23,9 +87,22 @@ impl EntryType {
         match self {
             Self::Task { .. } => Self::Note,
             Self::Note => Self::Event,
-            Self::Event => Self::Task { completed: false },
+            Self::Event => Self::task(),
         }
     }
+
+    #[must_use]
+    pub fn task_state(&self) -> Option<TaskState> {
+        match self {
+            Self::Task { state } => Some(*state),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn is_closed_task(&self) -> bool {
+        self.task_state().is_some_and(TaskState::is_closed)
+    }
 }
 
 /// Where an entry originates from relative to the viewed day.
@@ -35,6 +112,8 @@ pub enum SourceType {
     Local,
     /// Projected via @every-* pattern, read-only
     Recurring,
+    /// Projected onto its @due date, editable at the source
+    Due,
     /// From external calendar (ICS), read-only
     Calendar {
         calendar_id: String,
@@ -42,34 +121,75 @@ pub enum SourceType {
     },
 }
 
+/// Indentation written per nesting level.
+pub const INDENT: &str = "  ";
+
 /// Raw entry as parsed from markdown, without location metadata.
 /// Used internally for parsing and serialization.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct RawEntry {
     pub entry_type: EntryType,
     pub content: String,
+    /// Nesting level; sub-entries sit one level deeper than their parent
+    pub depth: usize,
+    /// Whether sub-entries are hidden in the daily view. View state only:
+    /// never written to disk and ignored when comparing entries.
+    pub collapsed: bool,
+}
+
+impl PartialEq for RawEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.entry_type == other.entry_type
+            && self.content == other.content
+            && self.depth == other.depth
+    }
 }
 
 impl RawEntry {
     #[must_use]
-    pub fn new_task(content: &str) -> Self {
+    pub fn new(entry_type: EntryType, content: impl Into<String>) -> Self {
         Self {
-            entry_type: EntryType::Task { completed: false },
-            content: content.to_string(),
+            entry_type,
+            content: content.into(),
+            depth: 0,
+            collapsed: false,
         }
     }
 
+    #[must_use]
+    pub fn new_task(content: &str) -> Self {
+        Self::new(EntryType::task(), content)
+    }
+
+    #[must_use]
+    pub fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+
     pub fn prefix(&self) -> &'static str {
         self.entry_type.prefix()
     }
 
+    /// Closes an open or in-progress task; reopens a closed one.
     pub fn toggle_complete(&mut self) {
-        if let EntryType::Task { completed } = &mut self.entry_type {
-            *completed = !*completed;
+        if let EntryType::Task { state } = &mut self.entry_type {
+            *state = if state.is_closed() {
+                TaskState::Open
+            } else {
+                TaskState::Done
+            };
         }
     }
 }
 
+/// Journal an entry was collected from, for results spanning several journals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceJournal {
+    pub name: String,
+    pub path: PathBuf,
+}
+
 /// Entry with full location and source metadata.
 /// This is the unified entry type used throughout the application.
 #[derive(Debug, Clone, PartialEq)]
@@ -79,6 +199,10 @@ pub struct Entry {
     pub source_date: NaiveDate,
     pub line_index: usize,
     pub source_type: SourceType,
+    /// Nesting level within the source day
+    pub depth: usize,
+    /// Set when the entry came from a cross-journal filter; None means the active journal
+    pub journal: Option<SourceJournal>,
 }
 
 impl Entry {
@@ -96,17 +220,21 @@ impl Entry {
             source_date,
             line_index,
             source_type,
+            depth: raw.depth,
+            journal: None,
         }
     }
 
     #[must_use]
     pub fn new_task(content: &str, source_date: NaiveDate, line_index: usize) -> Self {
         Self {
-            entry_type: EntryType::Task { completed: false },
+            entry_type: EntryType::task(),
             content: content.to_string(),
             source_date,
             line_index,
             source_type: SourceType::Local,
+            depth: 0,
+            journal: None,
         }
     }
 
@@ -114,19 +242,21 @@ impl Entry {
         self.entry_type.prefix()
     }
 
+    /// Closes an open or in-progress task; reopens a closed one.
     pub fn toggle_complete(&mut self) {
-        if let EntryType::Task { completed } = &mut self.entry_type {
-            *completed = !*completed;
+        if let EntryType::Task { state } = &mut self.entry_type {
+            *state = if state.is_closed() {
+                TaskState::Open
+            } else {
+                TaskState::Done
+            };
         }
     }
 
     /// Convert back to RawEntry for serialization.
     #[must_use]
     pub fn to_raw(&self) -> RawEntry {
-        RawEntry {
-            entry_type: self.entry_type.clone(),
-            content: self.content.clone(),
-        }
+        RawEntry::new(self.entry_type.clone(), self.content.clone()).with_depth(self.depth)
     }
 
     /// Returns true if this entry can be edited/deleted.
@@ -145,6 +275,43 @@ pub enum Line {
     Raw(String),
 }
 
+/// Returns the line index just past the last sub-entry of the entry at
+/// `line_idx`. Non-entry lines between sub-entries belong to the subtree;
+/// trailing ones do not.
+#[must_use]
+pub fn subtree_end(lines: &[Line], line_idx: usize) -> usize {
+    let Some(Line::Entry(parent)) = lines.get(line_idx) else {
+        return line_idx + 1;
+    };
+    let mut end = line_idx + 1;
+    for (i, line) in lines.iter().enumerate().skip(line_idx + 1) {
+        if let Line::Entry(entry) = line {
+            if entry.depth <= parent.depth {
+                break;
+            }
+            end = i + 1;
+        }
+    }
+    end
+}
+
+/// Completed and total task counts among all sub-entries of the entry at `line_idx`.
+#[must_use]
+pub fn subtask_progress(lines: &[Line], line_idx: usize) -> (usize, usize) {
+    let end = subtree_end(lines, line_idx);
+    lines
+        .get(line_idx + 1..end)
+        .unwrap_or_default()
+        .iter()
+        .fold((0, 0), |(done, total), line| match line {
+            Line::Entry(RawEntry {
+                entry_type: EntryType::Task { state },
+                ..
+            }) => (done + usize::from(state.is_closed()), total + 1),
+            _ => (done, total),
+        })
+}
+
 /// Recurring pattern for @every-* syntax.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RecurringPattern {
@@ -156,28 +323,86 @@ pub enum RecurringPattern {
     Weekly(Weekday),
     /// @every-1 through @every-31 (day of month)
     Monthly(u8),
+    /// @every-3-days, @every-other-day - every n days from the anchor
+    EveryDays(u32),
+    /// @every-2-weeks (anchor's weekday), @every-other-fri - every n weeks
+    EveryWeeks(u32, Option<Weekday>),
+    /// @every-3-months, @every-other-month - anchor's day of month, every n months
+    EveryMonths(u32),
+    /// @every-1st-mon through @every-5th-sun - nth weekday of the month
+    NthWeekday(u8, Weekday),
+    /// @every-last-fri - last weekday of the month
+    LastWeekday(Weekday),
+    /// @every-jan-15 - once a year
+    Yearly(u32, u8),
 }
 
 impl RecurringPattern {
-    /// Returns true if this pattern matches the given date.
+    /// Returns true if this pattern matches the given date. Interval patterns
+    /// count from `anchor`, the day the entry was written, and never match before it.
     #[must_use]
-    pub fn matches(&self, date: NaiveDate) -> bool {
+    pub fn matches(&self, date: NaiveDate, anchor: NaiveDate) -> bool {
         match self {
             Self::Daily => true,
             Self::Weekday => !matches!(date.weekday(), Weekday::Sat | Weekday::Sun),
             Self::Weekly(day) => date.weekday() == *day,
-            Self::Monthly(day) => {
-                let last_day = last_day_of_month(date);
-                if u32::from(*day) > last_day {
-                    date.day() == last_day
-                } else {
-                    date.day() == u32::from(*day)
-                }
+            Self::Monthly(day) => day_of_month_matches(date, u32::from(*day)),
+            Self::EveryDays(n) => date >= anchor && (date - anchor).num_days() % i64::from(*n) == 0,
+            Self::EveryWeeks(n, weekday) => {
+                let weekday = weekday.unwrap_or(anchor.weekday());
+                let weeks = (week_start(date) - week_start(anchor)).num_weeks();
+                date >= anchor && date.weekday() == weekday && weeks % i64::from(*n) == 0
+            }
+            Self::EveryMonths(n) => {
+                let months = (date.year() - anchor.year()) * 12 + date.month() as i32
+                    - anchor.month() as i32;
+                date >= anchor
+                    && months % *n as i32 == 0
+                    && day_of_month_matches(date, anchor.day())
+            }
+            Self::NthWeekday(nth, weekday) => {
+                date.weekday() == *weekday && (date.day() - 1) / 7 + 1 == u32::from(*nth)
+            }
+            Self::LastWeekday(weekday) => {
+                date.weekday() == *weekday && date.day() + 7 > last_day_of_month(date)
+            }
+            Self::Yearly(month, day) => {
+                date.month() == *month && day_of_month_matches(date, u32::from(*day))
             }
         }
     }
 }
 
+/// A recurring pattern with optional @from-/@until- bounds, anchored to the
+/// day its entry was written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Recurrence {
+    pub pattern: RecurringPattern,
+    pub anchor: NaiveDate,
+    pub from: Option<NaiveDate>,
+    pub until: Option<NaiveDate>,
+}
+
+impl Recurrence {
+    /// Returns true if the entry recurs on `date`.
+    #[must_use]
+    pub fn matches(&self, date: NaiveDate) -> bool {
+        self.from.is_none_or(|from| date >= from)
+            && self.until.is_none_or(|until| date <= until)
+            && self.pattern.matches(date, self.anchor)
+    }
+}
+
+/// Day-of-month match that falls back to the last day for short months.
+fn day_of_month_matches(date: NaiveDate, day: u32) -> bool {
+    date.day() == day.min(last_day_of_month(date))
+}
+
+/// Monday of the week containing `date`.
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - chrono::Days::new(u64::from(date.weekday().num_days_from_monday()))
+}
+
 /// Returns the last day of the month for the given date.
 #[must_use]
 fn last_day_of_month(date: NaiveDate) -> u32 {
@@ -193,39 +418,52 @@ fn last_day_of_month(date: NaiveDate) -> u32 {
         .unwrap_or(28)
 }
 
+/// Nesting depth from leading indentation: one level per tab or two spaces.
+fn indent_depth(line: &str) -> usize {
+    let mut depth = 0;
+    let mut spaces = 0;
+    for c in line.chars() {
+        match c {
+            '\t' => {
+                depth += 1;
+                spaces = 0;
+            }
+            ' ' => {
+                spaces += 1;
+                if spaces == 2 {
+                    depth += 1;
+                    spaces = 0;
+                }
+            }
+            _ => break,
+        }
+    }
+    depth
+}
+
 /// Parses a line into a RawEntry, treating unparsed lines as notes.
 #[must_use]
 pub fn parse_to_raw_entry(line: &str) -> RawEntry {
+    let depth = indent_depth(line);
     let trimmed = line.trim_start();
 
-    if let Some(content) = trimmed.strip_prefix("- [ ] ") {
-        return RawEntry {
-            entry_type: EntryType::Task { completed: false },
-            content: content.to_string(),
-        };
-    }
-    if let Some(content) = trimmed.strip_prefix("- [x] ") {
-        return RawEntry {
-            entry_type: EntryType::Task { completed: true },
-            content: content.to_string(),
-        };
-    }
-    if let Some(content) = trimmed.strip_prefix("* ") {
-        return RawEntry {
-            entry_type: EntryType::Event,
-            content: content.to_string(),
-        };
-    }
-    if let Some(content) = trimmed.strip_prefix("- ") {
-        return RawEntry {
-            entry_type: EntryType::Note,
-            content: content.to_string(),
-        };
-    }
-    RawEntry {
-        entry_type: EntryType::Note,
-        content: trimmed.to_string(),
-    }
+    let task = TaskState::ALL.into_iter().find_map(|state| {
+        let entry_type = EntryType::Task { state };
+        trimmed
+            .strip_prefix(entry_type.prefix())
+            .map(|content| (entry_type, content))
+    });
+
+    let (entry_type, content) = if let Some(task) = task {
+        task
+    } else if let Some(content) = trimmed.strip_prefix("* ") {
+        (EntryType::Event, content)
+    } else if let Some(content) = trimmed.strip_prefix("- ") {
+        (EntryType::Note, content)
+    } else {
+        (EntryType::Note, trimmed)
+    };
+    RawEntry::new(entry_type, content).with_depth(depth)
 }
 
 fn parse_line(line: &str) -> Line {
@@ -248,7 +486,12 @@ pub fn parse_lines(content: &str) -> Vec<Line> {
 
 fn serialize_line(line: &Line) -> String {
     match line {
-        Line::Entry(raw_entry) => format!("{}{}", raw_entry.prefix(), raw_entry.content),
+        Line::Entry(raw_entry) => format!(
+            "{}{}{}",
+            INDENT.repeat(raw_entry.depth),
+            raw_entry.prefix(),
+            raw_entry.content
+        ),
         Line::Raw(s) => s.clone(),
     }
 }
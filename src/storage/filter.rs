@@ -1,5 +1,6 @@
 use std::collections::{HashMap, HashSet};
 use std::io;
+use std::ops::Range;
 use std::path::Path;
 use std::sync::LazyLock;
 
@@ -7,8 +8,19 @@ use chrono::NaiveDate;
 use regex::Regex;
 
 use super::date_parsing::{ParseContext, parse_date, parse_weekday};
-use super::entries::{Entry, EntryType, Line, RawEntry, RecurringPattern, SourceType, parse_lines};
-use super::persistence::{load_journal, parse_day_header};
+use super::entries::{
+    Entry, EntryType, Line, RawEntry, Recurrence, RecurringPattern, SourceJournal, SourceType,
+    TaskState, serialize_lines,
+};
+use super::filter_expr::{FilterExpr, InvalidToken};
+use super::filter_order::{FilterGroup, FilterSort, order_filter_results};
+use super::project_registry::{ProjectInfo, ProjectRegistry};
+use super::properties::{
+    PROPERTY_KEY_PATTERN, PROPERTY_VALUE_PATTERN, PropertyFilter, extract_properties,
+    find_properties,
+};
+use super::search::SearchTerm;
+use super::store::JournalStore;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FilterType {
@@ -17,44 +29,156 @@ pub enum FilterType {
     Event,
 }
 
+/// Terms joined by juxtaposition. Entry types and task states widen the match
+/// (any of them); every other term narrows it.
 #[derive(Debug, Clone, Default)]
-pub struct Filter {
+pub struct FilterClause {
     pub entry_types: Vec<FilterType>,
-    pub completed: Option<bool>,
+    /// Task states to keep; empty keeps every state
+    pub task_states: Vec<TaskState>,
     pub tags: Vec<String>,
     pub exclude_tags: Vec<String>,
-    pub search_terms: Vec<String>,
-    pub exclude_terms: Vec<String>,
+    pub search_terms: Vec<SearchTerm>,
+    pub exclude_terms: Vec<SearchTerm>,
+    /// `key:value` property terms, such as `client:acme` or `est:>1h`
+    pub properties: Vec<PropertyFilter>,
+    pub exclude_properties: Vec<PropertyFilter>,
     pub exclude_types: Vec<FilterType>,
     pub before_date: Option<NaiveDate>,
     pub after_date: Option<NaiveDate>,
+    /// Due date range (before, after) from `due:` or `!overdue`; entries without
+    /// a due date never match
+    pub due_range: Option<(Option<NaiveDate>, Option<NaiveDate>)>,
+    /// Completion date range (before, after) from `done:`; entries without
+    /// completed metadata never match
+    pub done_range: Option<(Option<NaiveDate>, Option<NaiveDate>)>,
+    /// Creation date range (before, after) from `created:` and `age:`, against
+    /// created metadata or else the day the entry is written on
+    pub created_range: Option<(Option<NaiveDate>, Option<NaiveDate>)>,
+}
+
+/// Journals a filter runs over besides the active one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterScope {
+    /// `@hub`
+    Hub,
+    /// `@project:<id>`, a registered project
+    Project(String),
+    /// `@all`: the hub, the active journal and every available project
+    All,
+}
+
+/// A parsed filter query: a boolean expression over clauses.
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub expr: FilterExpr,
+    /// `@recurring` anywhere in the query shows only recurring entries;
+    /// otherwise they're excluded
     pub recurring: bool,
-    pub invalid_tokens: Vec<String>,
+    /// Journals named by scope modifiers; empty runs over the active journal
+    pub scopes: Vec<FilterScope>,
+    pub sort: FilterSort,
+    pub group: Option<FilterGroup>,
+    pub invalid_tokens: Vec<InvalidToken>,
+}
+
+/// Modifiers that apply to the whole query wherever they appear in it.
+#[derive(Debug, Default)]
+pub(super) struct QueryModifiers {
+    pub recurring: bool,
+    pub scopes: Vec<FilterScope>,
+    pub sort: Option<FilterSort>,
+    pub group: Option<FilterGroup>,
+}
+
+impl Filter {
+    /// Text terms results must match, outside any negation. Their matches
+    /// are highlighted in results, and fuzzy ones rank them.
+    #[must_use]
+    pub fn highlight_terms(&self) -> Vec<SearchTerm> {
+        let mut terms = Vec::new();
+        self.expr.collect_search_terms(&mut terms);
+        terms
+    }
+
+    /// Byte spans of the invalid tokens within the query.
+    #[must_use]
+    pub fn invalid_spans(&self) -> Vec<Range<usize>> {
+        self.invalid_tokens
+            .iter()
+            .map(|token| token.span.clone())
+            .collect()
+    }
+
+    /// Error message naming every invalid token, or None if the query parsed.
+    #[must_use]
+    pub fn error_message(&self) -> Option<String> {
+        if self.invalid_tokens.is_empty() {
+            return None;
+        }
+        let tokens: Vec<&str> = self
+            .invalid_tokens
+            .iter()
+            .map(|token| token.text.as_str())
+            .collect();
+        Some(format!("Unknown filter: {}", tokens.join(", ")))
+    }
 }
 
+/// A tag name: a letter, then letters, digits, `_` or `-`, with further
+/// `/`-separated segments for nested tags (`#work/frontend`). Letters and
+/// digits may be any script (`#café`, `#日本`).
+const TAG_NAME_PATTERN: &str = r"\p{L}[\p{L}\p{M}\p{N}_-]*(?:/[\p{L}\p{M}\p{N}_-]+)*";
+
 pub static TAG_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"#([a-zA-Z][a-zA-Z0-9_-]*)").unwrap());
+    LazyLock::new(|| Regex::new(&format!("#({TAG_NAME_PATTERN})")).unwrap());
 
 /// The character class for valid tag characters (after the first letter)
-pub const TAG_CHAR_CLASS: &str = "[a-zA-Z0-9_-]";
+pub const TAG_CHAR_CLASS: &str = r"[\p{L}\p{M}\p{N}_-]";
 
-/// Create a regex that matches a specific tag (case-insensitive) with word boundary
+/// Whether an entry's `tag` satisfies a `#query` filter tag, ignoring case.
+/// `work` matches `work` and any tag nested under it; `work/` matches only
+/// the nested tags.
+#[must_use]
+pub fn tag_matches(tag: &str, query: &str) -> bool {
+    let tag = tag.to_lowercase();
+    let query = query.to_lowercase();
+    if query.ends_with('/') {
+        tag.starts_with(&query)
+    } else {
+        tag == query
+            || tag
+                .strip_prefix(&query)
+                .is_some_and(|rest| rest.starts_with('/'))
+    }
+}
+
+/// Create a regex that matches a specific tag and the tags nested under it
+/// (case-insensitive). Group 1 holds the nested part. Callers check the
+/// character after a match, so `#work` doesn't match inside `#workshop`.
 pub fn create_tag_match_regex(tag: &str) -> Result<Regex, regex::Error> {
-    Regex::new(&format!(r"(?i)#{}", regex::escape(tag)))
+    Regex::new(&format!(
+        r"(?i)#{}((?:/{TAG_CHAR_CLASS}+)*)",
+        regex::escape(tag)
+    ))
 }
 
 /// Create a regex for tag deletion - includes preceding space to avoid double spaces
 pub fn create_tag_delete_regex(tag: &str) -> Result<Regex, regex::Error> {
-    Regex::new(&format!(r"(?i)\s?#{}", regex::escape(tag)))
+    Regex::new(&format!(
+        r"(?i)\s?#{}((?:/{TAG_CHAR_CLASS}+)*)",
+        regex::escape(tag)
+    ))
 }
 
-/// Matches trailing tags (one or more tags at end of line)
-pub static TRAILING_TAGS_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(&format!(r"(\s+#[a-zA-Z]{}*)+\s*$", TAG_CHAR_CLASS)).unwrap());
-
-/// Matches the last trailing tag at end of line
-pub static LAST_TRAILING_TAG_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(&format!(r"\s+#[a-zA-Z]{}*\s*$", TAG_CHAR_CLASS)).unwrap());
+/// Matches the trailing section: the tags and `key:value` properties at the
+/// end of a line
+pub static TRAILING_SECTION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(\s+(?:#{TAG_NAME_PATTERN}|{PROPERTY_KEY_PATTERN}:{PROPERTY_VALUE_PATTERN}))+\s*$"
+    ))
+    .unwrap()
+});
 
 /// Matches favorite tag shortcuts: #1 through #9 and #0
 pub static FAVORITE_TAG_REGEX: LazyLock<Regex> =
@@ -65,81 +189,297 @@ pub static SAVED_FILTER_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"\$(\w+)\b").unwrap());
 
 /// Matches @every-* patterns for recurring entries:
-/// @every-day, @every-weekday, @every-mon..sun (or full names), @every-1..31
+/// @every-day, @every-weekday, @every-mon..sun (or full names), @every-1..31,
+/// @every-3-days/weeks/months, @every-other-fri, @every-2nd-tue, @every-last-fri,
+/// @every-jan-15, each optionally bounded by @from-<date> and @until-<date>.
+/// Group 1 is the pattern, groups 2 and 3 the bounds.
 pub static RECURRING_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(?i)@every-(day|weekday|monday|tuesday|wednesday|thursday|friday|saturday|sunday|mon|tue|wed|thu|fri|sat|sun|[1-9]|[12]\d|3[01])(?:\s|$)")
-        .unwrap()
+    const WEEKDAY: &str =
+        "monday|tuesday|wednesday|thursday|friday|saturday|sunday|mon|tue|wed|thu|fri|sat|sun";
+    const MONTH: &str = "january|february|march|april|may|june|july|august|september|october|november|december|jan|feb|mar|apr|jun|jul|aug|sep|oct|nov|dec";
+    const DAY: &str = r"[12]\d|3[01]|[1-9]";
+    const DATE: &str = r"\d{1,4}/\d{1,2}(?:/\d{2,4})?";
+    Regex::new(&format!(
+        r"(?i)@every-((?:[1-9]\d?)-(?:days?|weeks?|months?)|other-(?:day|week|month|{WEEKDAY})|(?:1st|2nd|3rd|4th|5th|last)-(?:{WEEKDAY})|(?:{MONTH})-(?:{DAY})|day|weekday|{WEEKDAY}|{DAY})(?:@from-({DATE}))?(?:@until-({DATE}))?(?:\s|$)"
+    ))
+    .unwrap()
 });
 
+/// Matches @due(<date>) and @due-<date> deadline annotations
+pub static DUE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)@due(?:\(([^()\s]+)\)|-([^()\s]+))").unwrap());
+
 /// Matches <!-- done: ... --> metadata comment at end of content
-static DONE_META_REGEX: LazyLock<Regex> =
+pub(super) static DONE_META_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"\s*<!--\s*done:\s*([^>]*)\s*-->").unwrap());
 
-/// Extracts completion dates from entry content's <!-- done: ... --> comment.
-fn extract_done_dates(content: &str) -> Vec<NaiveDate> {
-    DONE_META_REGEX
+/// Matches <!-- skip: ... --> metadata listing skipped occurrences
+pub(super) static SKIP_META_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\s*<!--\s*skip:\s*([^>]*)\s*-->").unwrap());
+
+/// Matches <!-- moved: FROM>TO, ... --> metadata listing rescheduled occurrences
+pub(super) static MOVED_META_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\s*<!--\s*moved:\s*(.*?)\s*-->").unwrap());
+
+/// Matches <!-- completed: DATE --> metadata recording when a task was done
+static COMPLETED_META_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\s*<!--\s*completed:\s*([^>]*)\s*-->").unwrap());
+
+/// Matches <!-- created: DATE --> metadata recording when an entry was added
+static CREATED_META_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\s*<!--\s*created:\s*([^>]*)\s*-->").unwrap());
+
+/// Matches any metadata comment: occurrence tracking (done, skip, moved) or
+/// entry dates (completed, created)
+static ENTRY_META_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\s*<!--\s*(?:done|skip|moved|completed|created):.*?-->").unwrap()
+});
+
+/// Extracts the comma-separated values of a metadata comment.
+fn extract_meta_values<'a>(content: &'a str, regex: &Regex) -> Vec<&'a str> {
+    regex
         .captures(content)
         .and_then(|caps| caps.get(1))
         .map(|m| {
             m.as_str()
                 .split(',')
-                .filter_map(|s| NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok())
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
                 .collect()
         })
         .unwrap_or_default()
 }
 
+/// Replaces a metadata comment with one holding `values`, leaving other comments
+/// in place. The comment is removed entirely when `values` is empty.
+fn replace_meta(content: &str, regex: &Regex, key: &str, values: &[String]) -> String {
+    let base = regex.replace(content, "").trim().to_string();
+    if values.is_empty() {
+        return base;
+    }
+    format!("{base} <!-- {key}: {} -->", values.join(", "))
+}
+
+fn parse_meta_date(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()
+}
+
+fn format_meta_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+fn extract_meta_dates(content: &str, regex: &Regex) -> Vec<NaiveDate> {
+    extract_meta_values(content, regex)
+        .into_iter()
+        .filter_map(parse_meta_date)
+        .collect()
+}
+
+fn replace_meta_dates(
+    content: &str,
+    regex: &Regex,
+    key: &str,
+    mut dates: Vec<NaiveDate>,
+) -> String {
+    dates.sort();
+    dates.dedup();
+    let values: Vec<String> = dates.into_iter().map(format_meta_date).collect();
+    replace_meta(content, regex, key, &values)
+}
+
 /// Checks if a specific date is marked as done in the entry content.
 #[must_use]
 pub fn is_done_on_date(content: &str, date: NaiveDate) -> bool {
-    extract_done_dates(content).contains(&date)
-}
-
-/// Formats content with done metadata comment. Returns base unchanged if dates is empty.
-fn format_done_meta(base: &str, dates: &[NaiveDate]) -> String {
-    if dates.is_empty() {
-        return base.to_string();
-    }
-    let dates_str = dates
-        .iter()
-        .map(|d| d.format("%Y-%m-%d").to_string())
-        .collect::<Vec<_>>()
-        .join(", ");
-    format!("{base} <!-- done: {dates_str} -->")
+    extract_meta_dates(content, &DONE_META_REGEX).contains(&date)
 }
 
 /// Adds a date to the done list in entry content. Returns the new content.
 #[must_use]
 pub fn add_done_date(content: &str, date: NaiveDate) -> String {
-    let mut dates = extract_done_dates(content);
+    let mut dates = extract_meta_dates(content, &DONE_META_REGEX);
     if dates.contains(&date) {
         return content.to_string();
     }
     dates.push(date);
-    dates.sort();
-    format_done_meta(&strip_done_meta(content), &dates)
+    replace_meta_dates(content, &DONE_META_REGEX, "done", dates)
 }
 
 /// Removes a date from the done list in entry content. Returns the new content.
 #[must_use]
 pub fn remove_done_date(content: &str, date: NaiveDate) -> String {
-    let mut dates = extract_done_dates(content);
+    let mut dates = extract_meta_dates(content, &DONE_META_REGEX);
     dates.retain(|d| d != &date);
-    format_done_meta(&strip_done_meta(content), &dates)
+    replace_meta_dates(content, &DONE_META_REGEX, "done", dates)
+}
+
+/// Extracts (from, to) pairs from entry content's <!-- moved: ... --> comment.
+fn extract_moves(content: &str) -> Vec<(NaiveDate, NaiveDate)> {
+    extract_meta_values(content, &MOVED_META_REGEX)
+        .into_iter()
+        .filter_map(|pair| {
+            let (from, to) = pair.split_once('>')?;
+            Some((parse_meta_date(from.trim())?, parse_meta_date(to.trim())?))
+        })
+        .collect()
+}
+
+fn replace_moves(content: &str, mut moves: Vec<(NaiveDate, NaiveDate)>) -> String {
+    moves.sort();
+    let values: Vec<String> = moves
+        .into_iter()
+        .map(|(from, to)| format!("{}>{}", format_meta_date(from), format_meta_date(to)))
+        .collect();
+    replace_meta(content, &MOVED_META_REGEX, "moved", &values)
+}
+
+/// Returns the date an occurrence shown on `date` was scheduled for before
+/// any move. Occurrences that were never moved are their own origin.
+fn occurrence_origin(moves: &[(NaiveDate, NaiveDate)], date: NaiveDate) -> NaiveDate {
+    moves
+        .iter()
+        .find(|(_, to)| *to == date)
+        .map_or(date, |(from, _)| *from)
+}
+
+/// Checks whether a recurring entry written on `source_date` has an occurrence
+/// on `date`, honoring skipped and moved occurrences.
+#[must_use]
+pub fn recurs_on(
+    content: &str,
+    recurrence: &Recurrence,
+    source_date: NaiveDate,
+    date: NaiveDate,
+) -> bool {
+    if date <= source_date {
+        return false;
+    }
+    let moves = extract_moves(content);
+    if moves.iter().any(|(_, to)| *to == date) {
+        return true;
+    }
+    recurrence.matches(date)
+        && !moves.iter().any(|(from, _)| *from == date)
+        && !is_skipped_on(content, date)
+}
+
+/// Checks if the scheduled occurrence on `date` was skipped.
+#[must_use]
+pub fn is_skipped_on(content: &str, date: NaiveDate) -> bool {
+    extract_meta_dates(content, &SKIP_META_REGEX).contains(&date)
+}
+
+/// Skips the occurrence shown on `date`. A moved occurrence is skipped at its
+/// original date so the move is dropped too. Returns the new content.
+#[must_use]
+pub fn skip_occurrence(content: &str, date: NaiveDate) -> String {
+    let mut moves = extract_moves(content);
+    let origin = occurrence_origin(&moves, date);
+    moves.retain(|(_, to)| *to != date);
+
+    let mut skipped = extract_meta_dates(content, &SKIP_META_REGEX);
+    skipped.push(origin);
+    let content = replace_moves(content, moves);
+    replace_meta_dates(&content, &SKIP_META_REGEX, "skip", skipped)
+}
+
+/// Moves the occurrence shown on `date` to `target`. Moving an occurrence back
+/// to its original date drops the move. Returns the new content.
+#[must_use]
+pub fn move_occurrence(content: &str, date: NaiveDate, target: NaiveDate) -> String {
+    let mut moves = extract_moves(content);
+    let origin = occurrence_origin(&moves, date);
+    moves.retain(|(_, to)| *to != date);
+    if origin != target {
+        moves.push((origin, target));
+    }
+    replace_moves(content, moves)
 }
 
-/// Strips the <!-- done: ... --> metadata from content for display.
+/// Date a task was marked done, from its completed metadata.
 #[must_use]
-pub fn strip_done_meta(content: &str) -> String {
-    DONE_META_REGEX.replace(content, "").trim().to_string()
+pub fn completed_date(content: &str) -> Option<NaiveDate> {
+    extract_meta_dates(content, &COMPLETED_META_REGEX)
+        .into_iter()
+        .next()
 }
 
-/// Transfers done metadata from original content to new content.
-/// Used when editing entries to preserve completion tracking.
+/// Date an entry was added, from its created metadata.
 #[must_use]
-pub fn restore_done_meta(new_content: &str, original: &str) -> String {
-    let done_dates = extract_done_dates(original);
-    format_done_meta(new_content, &done_dates)
+pub fn created_date(content: &str) -> Option<NaiveDate> {
+    extract_meta_dates(content, &CREATED_META_REGEX)
+        .into_iter()
+        .next()
+}
+
+/// Records `date` as the day the entry was added.
+#[must_use]
+pub fn add_created_date(content: &str, date: NaiveDate) -> String {
+    replace_meta_dates(content, &CREATED_META_REGEX, "created", vec![date])
+}
+
+/// Brings completed metadata in line with a task's state after a toggle: a
+/// done task records `date` when given, any other entry loses the record.
+#[must_use]
+pub fn record_completion(content: &str, entry_type: &EntryType, date: Option<NaiveDate>) -> String {
+    match (entry_type.task_state(), date) {
+        (Some(TaskState::Done), Some(date)) => {
+            replace_meta_dates(content, &COMPLETED_META_REGEX, "completed", vec![date])
+        }
+        (Some(TaskState::Done), None) => content.to_string(),
+        _ if COMPLETED_META_REGEX.is_match(content) => {
+            replace_meta(content, &COMPLETED_META_REGEX, "completed", &[])
+        }
+        _ => content.to_string(),
+    }
+}
+
+/// Strips occurrence and entry date metadata from content for display.
+#[must_use]
+pub fn strip_entry_meta(content: &str) -> String {
+    ENTRY_META_REGEX.replace_all(content, "").trim().to_string()
+}
+
+/// Transfers metadata comments from original content to new content.
+/// Used when editing entries to preserve completion, skip, move and
+/// creation tracking.
+#[must_use]
+pub fn restore_entry_meta(new_content: &str, original: &str) -> String {
+    let meta: String = ENTRY_META_REGEX
+        .find_iter(original)
+        .map(|m| format!(" {}", m.as_str().trim()))
+        .collect();
+    format!("{new_content}{meta}")
+}
+
+/// Parses an age in days (`14`, `>14`, `<7`, `>=14`, `<=7`) as the range of
+/// creation dates it covers: (before, after).
+fn parse_age_range(age: &str, today: NaiveDate) -> Option<(Option<NaiveDate>, Option<NaiveDate>)> {
+    let (op, days) = ["<=", ">=", "<", ">"]
+        .iter()
+        .find_map(|op| age.strip_prefix(op).map(|days| (*op, days)))
+        .unwrap_or(("", age));
+    let days: i64 = days.parse().ok()?;
+    let days_ago = |n: i64| today.checked_sub_signed(chrono::Duration::days(n));
+    match op {
+        "" => Some((days_ago(days), days_ago(days))),
+        ">" => Some((days_ago(days + 1), None)),
+        ">=" => Some((days_ago(days), None)),
+        "<" => Some((None, days_ago(days - 1))),
+        "<=" => Some((None, days_ago(days))),
+        _ => None,
+    }
+}
+
+/// Combines two optional bounds, keeping the tighter one.
+fn tighter(
+    a: Option<NaiveDate>,
+    b: Option<NaiveDate>,
+    pick: fn(NaiveDate, NaiveDate) -> NaiveDate,
+) -> Option<NaiveDate> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(pick(a, b)),
+        (a, b) => a.or(b),
+    }
 }
 
 /// Checks if a token looks like spread date syntax (not plain text search).
@@ -221,18 +561,20 @@ pub fn extract_tags(content: &str) -> Vec<String> {
 
 /// Collects all unique tags from the current journal.
 /// Returns tags sorted alphabetically, deduplicated (case-insensitive, first occurrence preserved).
-pub fn collect_journal_tags(path: &Path) -> io::Result<Vec<String>> {
-    let journal = load_journal(path)?;
+pub fn collect_journal_tags(store: &dyn JournalStore, path: &Path) -> io::Result<Vec<String>> {
     let mut seen_lower: HashSet<String> = HashSet::new();
     let mut tags: Vec<String> = Vec::new();
 
-    for cap in TAG_REGEX.captures_iter(&journal) {
-        let tag = cap[1].to_string();
-        let lower = tag.to_lowercase();
-        if seen_lower.insert(lower) {
-            tags.push(tag);
+    store.visit_days(path, &mut |_, lines| {
+        let content = serialize_lines(lines);
+        for cap in TAG_REGEX.captures_iter(&content) {
+            let tag = cap[1].to_string();
+            let lower = tag.to_lowercase();
+            if seen_lower.insert(lower) {
+                tags.push(tag);
+            }
         }
-    }
+    })?;
 
     tags.sort_by_key(|a| a.to_lowercase());
     Ok(tags)
@@ -270,39 +612,60 @@ fn parse_filter_date_with_bias(
     parse_date(input, ctx, today)
 }
 
-/// Normalizes entry structure to: [content] [recurring_dates] [#tags]
+/// Normalizes entry structure to: [content] [recurring_dates] [#tags and key:value properties]
 ///
-/// - Trailing section = contiguous dates/tags at end (only whitespace between them)
-/// - Inline #tags (in content section) have # stripped
+/// - Trailing section = contiguous dates/tags/properties at end (only whitespace between them)
+/// - Inline #tags (in content section) have # stripped; inline properties stay as written
 /// - @every-* patterns are extracted from anywhere and moved to structure
 #[must_use]
 pub fn normalize_entry_structure(content: &str) -> (String, Option<String>) {
-    let recurring_dates: Vec<_> = RECURRING_REGEX.find_iter(content).collect();
-    let tags: Vec<_> = TAG_REGEX.find_iter(content).collect();
+    let recurring_dates: Vec<_> = RECURRING_REGEX
+        .find_iter(content)
+        .map(|m| (m.start(), m.end()))
+        .collect();
+    let tags: Vec<_> = TAG_REGEX
+        .find_iter(content)
+        .map(|m| (m.start(), m.end()))
+        .collect();
+    // A tag inside a property value (`ref:a#b`) belongs to the tag
+    let properties: Vec<_> = find_properties(content)
+        .into_iter()
+        .filter(|&(start, end)| {
+            !tags
+                .iter()
+                .chain(&recurring_dates)
+                .any(|&(s, e)| s < end && start < e)
+        })
+        .collect();
 
-    if recurring_dates.is_empty() && tags.is_empty() {
+    if recurring_dates.is_empty() && tags.is_empty() && properties.is_empty() {
         return (content.to_string(), None);
     }
 
-    let trailing_start = find_trailing_section_start(content, &recurring_dates, &tags);
+    let trailing_start =
+        find_trailing_section_start(content, &[&recurring_dates, &tags, &properties]);
 
-    let (trailing_tags, inline_tags): (Vec<&regex::Match>, Vec<&regex::Match>) =
-        tags.iter().partition(|t| t.start() >= trailing_start);
+    let (trailing_tags, inline_tags): (Vec<_>, Vec<_>) =
+        tags.into_iter().partition(|t| t.0 >= trailing_start);
+    let mut trailing: Vec<(usize, usize)> = properties
+        .iter()
+        .filter(|p| p.0 >= trailing_start)
+        .chain(&trailing_tags)
+        .copied()
+        .collect();
+    trailing.sort_unstable();
 
     // Removals: (start, end, replacement) - replacement None means delete
     let mut removals: Vec<(usize, usize, Option<&str>)> = Vec::new();
-    for m in &recurring_dates {
-        removals.push((m.start(), m.end(), None));
-    }
-    for m in &trailing_tags {
-        removals.push((m.start(), m.end(), None));
+    for &(start, end) in recurring_dates.iter().chain(&trailing) {
+        removals.push((start, end, None));
     }
-    for m in &inline_tags {
-        removals.push((m.start(), m.end(), Some(&m.as_str()[1..])));
+    for &(start, end) in &inline_tags {
+        removals.push((start, end, Some(&content[start + 1..end])));
     }
 
     // Sort descending so we can modify from end to start without invalidating positions
-    removals.sort_by(|a, b| b.0.cmp(&a.0));
+    removals.sort_by_key(|r| std::cmp::Reverse(r.0));
 
     let mut result = content.to_string();
     for (start, end, replacement) in removals {
@@ -311,31 +674,23 @@ pub fn normalize_entry_structure(content: &str) -> (String, Option<String>) {
 
     let result = result.split_whitespace().collect::<Vec<_>>().join(" ");
 
-    // Reconstruct: [content] [recurring] [tags]
+    // Reconstruct: [content] [recurring] [tags and properties]
     let mut final_parts = vec![result];
-    for m in &recurring_dates {
-        final_parts.push(m.as_str().trim().to_string());
+    for &(start, end) in &recurring_dates {
+        final_parts.push(content[start..end].trim().to_string());
     }
-    for m in &trailing_tags {
-        final_parts.push(m.as_str().to_string());
+    for &(start, end) in &trailing {
+        final_parts.push(content[start..end].to_string());
     }
 
     (final_parts.join(" "), None)
 }
 
 /// Find the byte position where the trailing section starts.
-/// Trailing section = contiguous sequence of recurring patterns and tags at the end,
-/// with only whitespace between them.
-fn find_trailing_section_start(
-    content: &str,
-    recurring_dates: &[regex::Match],
-    tags: &[regex::Match],
-) -> usize {
-    let mut patterns: Vec<(usize, usize)> = recurring_dates
-        .iter()
-        .chain(tags.iter())
-        .map(|m| (m.start(), m.end()))
-        .collect();
+/// Trailing section = contiguous sequence of recurring patterns, tags and
+/// properties at the end, with only whitespace between them.
+fn find_trailing_section_start(content: &str, spans: &[&[(usize, usize)]]) -> usize {
+    let mut patterns: Vec<(usize, usize)> = spans.iter().flat_map(|s| s.iter().copied()).collect();
 
     if patterns.is_empty() {
         return content.len();
@@ -379,53 +734,88 @@ pub fn expand_favorite_tags(content: &str, favorite_tags: &HashMap<String, Strin
         .into_owned()
 }
 
-/// Expands saved filter shortcuts ($name) with their definitions from config.
-/// Returns the expanded query and a list of unknown filter names.
-#[must_use]
-pub fn expand_saved_filters(
-    query: &str,
-    filters: &HashMap<String, String>,
-) -> (String, Vec<String>) {
-    use std::cell::RefCell;
-
-    let unknown = RefCell::new(Vec::new());
-    let result = SAVED_FILTER_REGEX
-        .replace_all(query, |caps: &regex::Captures| {
-            let name = &caps[1];
-            filters.get(name).map_or_else(
-                || {
-                    unknown.borrow_mut().push(caps[0].to_string());
-                    caps[0].to_string()
-                },
-                |expansion| expansion.clone(),
-            )
-        })
-        .into_owned();
-
-    (result, unknown.into_inner())
-}
-
-/// Parses an @every-* pattern string (without the @every- prefix) into a RecurringPattern.
-/// Reuses `parse_weekday()` for weekday names to avoid duplication.
+/// Parses an @every-* pattern string (without the @every- prefix or bounds)
+/// into a RecurringPattern. Reuses `parse_weekday()` for weekday names.
 #[must_use]
 pub fn parse_recurring_pattern(pattern_str: &str) -> Option<RecurringPattern> {
     let lower = pattern_str.to_lowercase();
     match lower.as_str() {
-        "day" => Some(RecurringPattern::Daily),
-        "weekday" => Some(RecurringPattern::Weekday),
-        _ => {
-            // Try parsing as weekday (reuse existing parse_weekday)
-            if let Some(weekday) = parse_weekday(&lower) {
-                return Some(RecurringPattern::Weekly(weekday));
-            }
-            // Then try as day of month (1-31)
-            lower
-                .parse::<u8>()
-                .ok()
-                .filter(|&d| (1..=31).contains(&d))
-                .map(RecurringPattern::Monthly)
-        }
+        "day" => return Some(RecurringPattern::Daily),
+        "weekday" => return Some(RecurringPattern::Weekday),
+        "other-day" => return Some(RecurringPattern::EveryDays(2)),
+        "other-week" => return Some(RecurringPattern::EveryWeeks(2, None)),
+        "other-month" => return Some(RecurringPattern::EveryMonths(2)),
+        _ => {}
+    }
+
+    if let Some(weekday) = parse_weekday(&lower) {
+        return Some(RecurringPattern::Weekly(weekday));
+    }
+    if let Some(day) = parse_day_of_month(&lower) {
+        return Some(RecurringPattern::Monthly(day));
     }
+
+    let (head, tail) = lower.split_once('-')?;
+    if head == "other" {
+        return parse_weekday(tail).map(|wd| RecurringPattern::EveryWeeks(2, Some(wd)));
+    }
+    if let Ok(n) = head.parse::<u32>()
+        && n > 0
+    {
+        return match tail {
+            "day" | "days" => Some(RecurringPattern::EveryDays(n)),
+            "week" | "weeks" => Some(RecurringPattern::EveryWeeks(n, None)),
+            "month" | "months" => Some(RecurringPattern::EveryMonths(n)),
+            _ => None,
+        };
+    }
+    let nth = match head {
+        "1st" => Some(1),
+        "2nd" => Some(2),
+        "3rd" => Some(3),
+        "4th" => Some(4),
+        "5th" => Some(5),
+        _ => None,
+    };
+    if let Some(nth) = nth {
+        return parse_weekday(tail).map(|wd| RecurringPattern::NthWeekday(nth, wd));
+    }
+    if head == "last" {
+        return parse_weekday(tail).map(RecurringPattern::LastWeekday);
+    }
+    let month = parse_month(head)?;
+    let day = parse_day_of_month(tail)?;
+    NaiveDate::from_ymd_opt(2000, month, u32::from(day))?;
+    Some(RecurringPattern::Yearly(month, day))
+}
+
+fn parse_day_of_month(s: &str) -> Option<u8> {
+    s.parse::<u8>().ok().filter(|d| (1..=31).contains(d))
+}
+
+fn parse_month(s: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ];
+    const FULL: [&str; 12] = [
+        "january",
+        "february",
+        "march",
+        "april",
+        "may",
+        "june",
+        "july",
+        "august",
+        "september",
+        "october",
+        "november",
+        "december",
+    ];
+    MONTHS
+        .iter()
+        .zip(FULL)
+        .position(|(short, full)| s == *short || s == full)
+        .map(|i| i as u32 + 1)
 }
 
 /// Strips @every-* tags from content (e.g., for matching done-today entries).
@@ -434,63 +824,87 @@ pub fn strip_recurring_tags(content: &str) -> String {
     RECURRING_REGEX.replace_all(content, "").trim().to_string()
 }
 
-/// Extracts the recurring pattern from entry content if it contains an @every-* pattern.
+/// Extracts the recurrence from entry content if it contains an @every-* pattern.
+/// Intervals and bounds are anchored to `source_date`, the day the entry was written.
 #[must_use]
-pub fn extract_recurring_pattern(content: &str) -> Option<RecurringPattern> {
-    RECURRING_REGEX
-        .captures(content)
-        .and_then(|cap| cap.get(1))
-        .and_then(|m| parse_recurring_pattern(m.as_str()))
+pub fn extract_recurring_pattern(content: &str, source_date: NaiveDate) -> Option<Recurrence> {
+    let caps = RECURRING_REGEX.captures(content)?;
+    let pattern = parse_recurring_pattern(caps.get(1)?.as_str())?;
+    let bound = |i: usize| match caps.get(i) {
+        Some(m) => parse_date(m.as_str(), ParseContext::Entry, source_date).map(Some),
+        None => Some(None),
+    };
+    Some(Recurrence {
+        pattern,
+        anchor: source_date,
+        from: bound(2)?,
+        until: bound(3)?,
+    })
+}
+
+/// Extracts the due date of an entry written on `written`. Relative expressions
+/// (fri, d3, tomorrow) count forward from the day the entry was written.
+#[must_use]
+pub fn extract_due_date(content: &str, written: NaiveDate) -> Option<NaiveDate> {
+    let caps = DUE_REGEX.captures(content)?;
+    let expr = caps.get(1).or_else(|| caps.get(2))?.as_str();
+    parse_date(expr, ParseContext::Entry, written)
 }
 
-/// Collects all recurring projected entries for the target date.
+/// Collects all projected entries for the target date: recurring entries with an
+/// occurrence on it (after skips and moves) and entries whose @due date falls on it.
 /// Entries from the target date itself are excluded (they're regular entries).
 pub fn collect_projected_entries_for_date(
+    store: &dyn JournalStore,
     target_date: NaiveDate,
     path: &Path,
 ) -> io::Result<Vec<Entry>> {
-    let journal = load_journal(path)?;
     let mut entries = Vec::new();
-    let mut current_date: Option<NaiveDate> = None;
-    let mut line_index_in_day: usize = 0;
-
-    for line in journal.lines() {
-        if let Some(date) = parse_day_header(line) {
-            current_date = Some(date);
-            line_index_in_day = 0;
-            continue;
-        }
 
-        if let Some(source_date) = current_date {
-            if source_date == target_date {
-                line_index_in_day += 1;
-                continue;
-            }
+    store.visit_days(path, &mut |source_date, lines| {
+        if source_date == target_date {
+            return;
+        }
 
-            let parsed = parse_lines(line);
-            if let Some(Line::Entry(raw_entry)) = parsed.first()
-                && let Some(pattern) = extract_recurring_pattern(&raw_entry.content)
-                && target_date >= source_date
-                && pattern.matches(target_date)
+        for (line_index, line) in lines.iter().enumerate() {
+            if let Line::Entry(raw_entry) = line
+                && let Some(recurrence) = extract_recurring_pattern(&raw_entry.content, source_date)
+                && recurs_on(&raw_entry.content, &recurrence, source_date, target_date)
             {
                 let is_done = is_done_on_date(&raw_entry.content, target_date);
                 let entry_type = if is_done {
-                    EntryType::Task { completed: true }
+                    EntryType::Task {
+                        state: TaskState::Done,
+                    }
                 } else {
                     raw_entry.entry_type.clone()
                 };
 
                 entries.push(Entry {
                     entry_type,
-                    content: strip_done_meta(&raw_entry.content),
+                    content: strip_entry_meta(&raw_entry.content),
                     source_date,
-                    line_index: line_index_in_day,
+                    line_index,
                     source_type: SourceType::Recurring,
+                    depth: 0,
+                    journal: None,
+                });
+            } else if let Line::Entry(raw_entry) = line
+                && !RECURRING_REGEX.is_match(&raw_entry.content)
+                && extract_due_date(&raw_entry.content, source_date) == Some(target_date)
+            {
+                entries.push(Entry {
+                    entry_type: raw_entry.entry_type.clone(),
+                    content: raw_entry.content.clone(),
+                    source_date,
+                    line_index,
+                    source_type: SourceType::Due,
+                    depth: 0,
+                    journal: None,
                 });
             }
-            line_index_in_day += 1;
         }
-    }
+    })?;
 
     entries.sort_by_key(|e| e.source_date);
     Ok(entries)
@@ -499,34 +913,27 @@ pub fn collect_projected_entries_for_date(
 /// Scans the journal for recurring entries and returns which dates in the range have them.
 /// More efficient than calling collect_projected_entries_for_date for each date.
 pub fn scan_recurring_in_range(
+    store: &dyn JournalStore,
     start: NaiveDate,
     end: NaiveDate,
     path: &Path,
 ) -> io::Result<HashSet<NaiveDate>> {
-    let journal = load_journal(path)?;
     let mut result = HashSet::new();
-    let mut current_date: Option<NaiveDate> = None;
-
-    for line in journal.lines() {
-        if let Some(date) = parse_day_header(line) {
-            current_date = Some(date);
-            continue;
-        }
 
-        if let Some(source_date) = current_date {
-            let parsed = parse_lines(line);
-            if let Some(Line::Entry(raw_entry)) = parsed.first()
-                && let Some(pattern) = extract_recurring_pattern(&raw_entry.content)
+    store.visit_days(path, &mut |source_date, lines| {
+        for line in lines {
+            if let Line::Entry(raw_entry) = line
+                && let Some(recurrence) = extract_recurring_pattern(&raw_entry.content, source_date)
             {
                 // Check each date in range to see if this pattern matches
                 for date in start.iter_days().take_while(|d| *d <= end) {
-                    if date >= source_date && date != source_date && pattern.matches(date) {
+                    if recurs_on(&raw_entry.content, &recurrence, source_date, date) {
                         result.insert(date);
                     }
                 }
             }
         }
-    }
+    })?;
 
     Ok(result)
 }
@@ -540,148 +947,305 @@ fn parse_type_keyword(s: &str) -> Option<FilterType> {
     }
 }
 
-#[must_use]
-pub fn parse_filter_query(query: &str) -> Filter {
-    let mut filter = Filter::default();
-    let today = chrono::Local::now().date_naive();
-
-    for token in query.split_whitespace() {
-        // Spread date syntax: DATE, DATE.., ..DATE, DATE..DATE
-        // Dates default to past (d7 = 7 days ago, mon = last Monday)
-        // Append + for explicit future (d7+ = 7 days from now, mon+ = next Monday)
-        if is_spread_syntax(token) {
-            if filter.before_date.is_some() || filter.after_date.is_some() {
-                filter
-                    .invalid_tokens
-                    .push("Multiple date ranges".to_string());
-            } else if let Some((before, after)) = parse_spread_date(token, today) {
-                filter.before_date = before;
-                filter.after_date = after;
-            } else {
-                filter.invalid_tokens.push(token.to_string());
-            }
-            continue;
+/// Parses a text term: `re:/pattern/`, `~fuzzy`, a `"quoted phrase"` or a
+/// plain word. Returns None for an invalid pattern or an unclosed quote.
+fn parse_search_term(token: &str) -> Option<SearchTerm> {
+    if let Some(pattern) = token.strip_prefix("re:/") {
+        let pattern = pattern.strip_suffix('/')?;
+        return SearchTerm::regex(pattern).ok();
+    }
+    if let Some(fuzzy) = token.strip_prefix('~') {
+        let fuzzy = unquote(fuzzy)?;
+        return (!fuzzy.is_empty()).then(|| SearchTerm::Fuzzy(fuzzy.to_string()));
+    }
+    let text = unquote(token)?;
+    (!text.is_empty()).then(|| SearchTerm::literal(text))
+}
+
+/// Strips the quotes around a phrase; None when the closing quote is missing.
+fn unquote(text: &str) -> Option<&str> {
+    match text.strip_prefix('"') {
+        Some(phrase) => phrase.strip_suffix('"'),
+        None => Some(text),
+    }
+}
+
+/// Applies one filter term to a clause. Returns the text to report if the
+/// term is invalid.
+pub(super) fn apply_term(
+    clause: &mut FilterClause,
+    modifiers: &mut QueryModifiers,
+    token: &str,
+    today: NaiveDate,
+) -> Result<(), String> {
+    // Due date range: due:DATE, due:DATE.., due:..DATE, due:DATE..DATE
+    if let Some(range) = token.strip_prefix("due:") {
+        if clause.due_range.is_some() {
+            return Err("Multiple due ranges".to_string());
         }
+        clause.due_range = Some(parse_spread_date(range, today).ok_or_else(|| token.to_string())?);
+        return Ok(());
+    }
 
-        // Content-based filters: @recurring
-        if token == "@recurring" {
-            filter.recurring = true;
-            continue;
+    // Completion date range: done:DATE, done:DATE.., done:..DATE, done:DATE..DATE
+    if let Some(range) = token.strip_prefix("done:") {
+        if clause.done_range.is_some() {
+            return Err("Multiple done ranges".to_string());
         }
-        if token.starts_with('@') {
-            filter.invalid_tokens.push(token.to_string());
-            continue;
+        clause.done_range = Some(parse_spread_date(range, today).ok_or_else(|| token.to_string())?);
+        return Ok(());
+    }
+
+    // Creation date range, or the same range written as an age in days
+    let created = if let Some(range) = token.strip_prefix("created:") {
+        Some(parse_spread_date(range, today))
+    } else {
+        token
+            .strip_prefix("age:")
+            .map(|age| parse_age_range(age, today))
+    };
+    if let Some(range) = created {
+        let (before, after) = range.ok_or_else(|| token.to_string())?;
+        let (current_before, current_after) = clause.created_range.unwrap_or_default();
+        clause.created_range = Some((
+            tighter(current_before, before, NaiveDate::min),
+            tighter(current_after, after, NaiveDate::max),
+        ));
+        return Ok(());
+    }
+
+    // Spread date syntax: DATE, DATE.., ..DATE, DATE..DATE
+    // Dates default to past (d7 = 7 days ago, mon = last Monday)
+    // Append + for explicit future (d7+ = 7 days from now, mon+ = next Monday)
+    if is_spread_syntax(token) {
+        if clause.before_date.is_some() || clause.after_date.is_some() {
+            return Err("Multiple date ranges".to_string());
         }
+        let (before, after) = parse_spread_date(token, today).ok_or_else(|| token.to_string())?;
+        clause.before_date = before;
+        clause.after_date = after;
+        return Ok(());
+    }
 
-        if let Some(negated) = token.strip_prefix('-') {
-            if let Some(tag) = negated.strip_prefix('#') {
-                filter.exclude_tags.push(tag.to_string());
-            } else if let Some(type_str) = negated.strip_prefix('!') {
-                if let Some(filter_type) = parse_type_keyword(type_str) {
-                    filter.exclude_types.push(filter_type);
-                } else {
-                    filter.invalid_tokens.push(token.to_string());
-                }
-            } else if !negated.is_empty() {
-                filter.exclude_terms.push(negated.to_string());
-            }
-        } else if let Some(type_str) = token.strip_prefix('!') {
-            let base_type = if let Some(idx) = type_str.find('/') {
-                &type_str[..idx]
-            } else {
-                type_str
-            };
+    // Result order: sort:<key> and group:<key>, the last one wins
+    if let Some(key) = token.strip_prefix("sort:") {
+        modifiers.sort = Some(FilterSort::parse(key).ok_or_else(|| token.to_string())?);
+        return Ok(());
+    }
+    if let Some(key) = token.strip_prefix("group:") {
+        modifiers.group = FilterGroup::parse(key).ok_or_else(|| token.to_string())?;
+        return Ok(());
+    }
 
-            let (new_type, completed_override) = match base_type {
-                "tasks" | "task" | "t" => (Some(FilterType::Task), Some(false)),
-                "completed" | "c" => (Some(FilterType::Task), Some(true)),
-                "notes" | "note" | "n" => (Some(FilterType::Note), None),
-                "events" | "event" | "e" => (Some(FilterType::Event), None),
-                _ => (None, None),
-            };
+    // Content-based filters: @recurring
+    if token == "@recurring" {
+        modifiers.recurring = true;
+        return Ok(());
+    }
 
-            if let Some(new_type) = new_type {
-                if !filter.entry_types.contains(&new_type) {
-                    filter.entry_types.push(new_type);
-                }
-                // Handle completed filter: if conflicting values, show all (None)
-                if let Some(new_completed) = completed_override {
-                    filter.completed = match filter.completed {
-                        None => Some(new_completed),
-                        Some(existing) if existing != new_completed => None,
-                        other => other,
-                    };
-                }
-            } else {
-                filter.invalid_tokens.push(token.to_string());
+    // Journal scope: @all, @hub, @project:<id>
+    let scope = match token {
+        "@all" => Some(FilterScope::All),
+        "@hub" => Some(FilterScope::Hub),
+        _ => token
+            .strip_prefix("@project:")
+            .filter(|id| !id.is_empty())
+            .map(|id| FilterScope::Project(id.to_string())),
+    };
+    if let Some(scope) = scope {
+        if !modifiers.scopes.contains(&scope) {
+            modifiers.scopes.push(scope);
+        }
+        return Ok(());
+    }
+    if token.starts_with('@') {
+        return Err(token.to_string());
+    }
+
+    if let Some(negated) = token.strip_prefix('-') {
+        if let Some(tag) = negated.strip_prefix('#') {
+            clause.exclude_tags.push(tag.to_string());
+        } else if let Some(type_str) = negated.strip_prefix('!') {
+            let filter_type = parse_type_keyword(type_str).ok_or_else(|| token.to_string())?;
+            clause.exclude_types.push(filter_type);
+        } else if let Some(property) = PropertyFilter::parse(negated) {
+            clause.exclude_properties.push(property);
+        } else if !negated.is_empty() {
+            let term = parse_search_term(negated).ok_or_else(|| token.to_string())?;
+            clause.exclude_terms.push(term);
+        }
+    } else if token == "!overdue" {
+        // Open tasks whose due date has passed
+        if clause.due_range.is_some() {
+            return Err("Multiple due ranges".to_string());
+        }
+        clause.due_range = Some((today.pred_opt(), None));
+        if !clause.entry_types.contains(&FilterType::Task) {
+            clause.entry_types.push(FilterType::Task);
+        }
+        for state in [TaskState::Open, TaskState::InProgress] {
+            if !clause.task_states.contains(&state) {
+                clause.task_states.push(state);
             }
-        } else if let Some(tag) = token.strip_prefix('#') {
-            filter.tags.push(tag.to_string());
-        } else if !token.is_empty() {
-            filter.search_terms.push(token.to_string());
         }
+    } else if let Some(type_str) = token.strip_prefix('!') {
+        let base_type = if let Some(idx) = type_str.find('/') {
+            &type_str[..idx]
+        } else {
+            type_str
+        };
+
+        let (new_type, states): (_, &[TaskState]) = match base_type {
+            "tasks" | "task" | "t" => (
+                Some(FilterType::Task),
+                &[TaskState::Open, TaskState::InProgress],
+            ),
+            "completed" | "c" => (Some(FilterType::Task), &[TaskState::Done]),
+            "inprogress" | "in_progress" | "i" => {
+                (Some(FilterType::Task), &[TaskState::InProgress])
+            }
+            "cancelled" | "canceled" => (Some(FilterType::Task), &[TaskState::Cancelled]),
+            "migrated" | "m" => (Some(FilterType::Task), &[TaskState::Migrated]),
+            "notes" | "note" | "n" => (Some(FilterType::Note), &[]),
+            "events" | "event" | "e" => (Some(FilterType::Event), &[]),
+            _ => (None, &[]),
+        };
+
+        let new_type = new_type.ok_or_else(|| token.to_string())?;
+        if !clause.entry_types.contains(&new_type) {
+            clause.entry_types.push(new_type);
+        }
+        // Several state keywords widen the filter to any of those states
+        for state in states {
+            if !clause.task_states.contains(state) {
+                clause.task_states.push(*state);
+            }
+        }
+    } else if let Some(tag) = token.strip_prefix('#') {
+        clause.tags.push(tag.to_string());
+    } else if let Some(property) = PropertyFilter::parse(token) {
+        clause.properties.push(property);
+    } else if !token.is_empty() {
+        let term = parse_search_term(token).ok_or_else(|| token.to_string())?;
+        clause.search_terms.push(term);
     }
 
-    filter
+    Ok(())
 }
 
 /// Collects entries matching the filter criteria.
 /// Returns entries with SourceType::Local (filter results are from their source day).
-pub fn collect_filtered_entries(filter: &Filter, path: &Path) -> io::Result<Vec<Entry>> {
+pub fn collect_filtered_entries(
+    store: &dyn JournalStore,
+    filter: &Filter,
+    path: &Path,
+) -> io::Result<Vec<Entry>> {
     if !filter.invalid_tokens.is_empty() {
         return Ok(Vec::new());
     }
 
-    let journal = load_journal(path)?;
     let mut entries = Vec::new();
-    let mut current_date: Option<NaiveDate> = None;
-    let mut line_index_in_day: usize = 0;
-
-    for line in journal.lines() {
-        if let Some(date) = parse_day_header(line) {
-            current_date = Some(date);
-            line_index_in_day = 0;
-            continue;
-        }
 
-        if let Some(source_date) = current_date {
-            // Date filters on day header
-            if let Some(before) = filter.before_date
-                && source_date > before
-            {
-                line_index_in_day += 1;
+    store.visit_days(path, &mut |source_date, lines| {
+        for (line_index, line) in lines.iter().enumerate() {
+            let Line::Entry(raw_entry) = line else {
                 continue;
-            }
-            if let Some(after) = filter.after_date
-                && source_date < after
-            {
-                line_index_in_day += 1;
+            };
+
+            // @recurring shows only recurring; otherwise recurring entries are excluded
+            let is_recurring = RECURRING_REGEX.is_match(&raw_entry.content);
+            if filter.recurring != is_recurring {
                 continue;
             }
 
-            let parsed = parse_lines(line);
-            if let Some(Line::Entry(raw_entry)) = parsed.first() {
-                // @recurring shows only recurring; otherwise recurring entries are excluded
-                let is_recurring = RECURRING_REGEX.is_match(&raw_entry.content);
-                if filter.recurring != is_recurring {
-                    line_index_in_day += 1;
-                    continue;
-                }
+            if filter.expr.matches(raw_entry, source_date) {
+                entries.push(Entry::from_raw(
+                    raw_entry,
+                    source_date,
+                    line_index,
+                    SourceType::Local,
+                ));
+            }
+        }
+    })?;
+
+    entries.sort_by_key(|entry| entry.source_date);
+    order_filter_results(&mut entries, filter);
+    Ok(entries)
+}
 
-                if entry_matches_filter(raw_entry, filter) {
-                    entries.push(Entry::from_raw(
-                        raw_entry,
-                        source_date,
-                        line_index_in_day,
-                        SourceType::Local,
+/// Resolves scope modifiers to the journals they cover, in the order named.
+/// Returns an error naming a project that isn't registered or available.
+pub fn resolve_filter_scope(
+    scopes: &[FilterScope],
+    active_path: &Path,
+    hub_path: &Path,
+    registry: &ProjectRegistry,
+) -> Result<Vec<SourceJournal>, String> {
+    let project_journal = |project: &ProjectInfo| SourceJournal {
+        name: project.id.clone(),
+        path: project.journal_path(),
+    };
+    let hub_journal = || SourceJournal {
+        name: "hub".to_string(),
+        path: hub_path.to_path_buf(),
+    };
+
+    let mut journals: Vec<SourceJournal> = Vec::new();
+    let mut add = |journal: SourceJournal| {
+        if !journals.iter().any(|j| j.path == journal.path) {
+            journals.push(journal);
+        }
+    };
+
+    for scope in scopes {
+        match scope {
+            FilterScope::Hub => add(hub_journal()),
+            FilterScope::Project(id) => match registry.find_by_id(id) {
+                Some(project) if project.available => add(project_journal(project)),
+                Some(_) => return Err(format!("Project unavailable: {id}")),
+                None => return Err(format!("Unknown project: {id}")),
+            },
+            FilterScope::All => {
+                add(hub_journal());
+                if active_path != hub_path {
+                    add(registry.find_by_path(active_path).map_or_else(
+                        || SourceJournal {
+                            name: "project".to_string(),
+                            path: active_path.to_path_buf(),
+                        },
+                        project_journal,
                     ));
                 }
+                for project in registry.projects.iter().filter(|p| p.available) {
+                    add(project_journal(project));
+                }
             }
-            line_index_in_day += 1;
         }
     }
 
+    Ok(journals)
+}
+
+/// Collects matching entries from each journal, tagging every result with
+/// the journal it came from. Results are ordered by date, then by journal,
+/// before the filter's own sort and grouping apply.
+pub fn collect_filtered_entries_across(
+    store: &dyn JournalStore,
+    filter: &Filter,
+    journals: &[SourceJournal],
+) -> io::Result<Vec<Entry>> {
+    let mut entries = Vec::new();
+    for journal in journals {
+        let found = collect_filtered_entries(store, filter, &journal.path)?;
+        entries.extend(found.into_iter().map(|entry| Entry {
+            journal: Some(journal.clone()),
+            ..entry
+        }));
+    }
+
     entries.sort_by_key(|entry| entry.source_date);
+    order_filter_results(&mut entries, filter);
     Ok(entries)
 }
 
@@ -693,59 +1257,107 @@ fn entry_type_to_filter_type(entry_type: &EntryType) -> FilterType {
     }
 }
 
-fn entry_matches_filter(entry: &RawEntry, filter: &Filter) -> bool {
+/// Checks one clause against an entry written on `source_date`.
+pub(super) fn clause_matches(
+    clause: &FilterClause,
+    entry: &RawEntry,
+    source_date: NaiveDate,
+) -> bool {
+    // Date ranges apply to the day the entry was written
+    if clause
+        .before_date
+        .is_some_and(|before| source_date > before)
+        || clause.after_date.is_some_and(|after| source_date < after)
+    {
+        return false;
+    }
+
     let entry_filter_type = entry_type_to_filter_type(&entry.entry_type);
 
-    if !filter.entry_types.is_empty() && !filter.entry_types.contains(&entry_filter_type) {
+    if !clause.entry_types.is_empty() && !clause.entry_types.contains(&entry_filter_type) {
         return false;
     }
 
-    for excluded_type in &filter.exclude_types {
+    for excluded_type in &clause.exclude_types {
         if &entry_filter_type == excluded_type {
             return false;
         }
     }
 
-    if let Some(want_completed) = filter.completed
-        && let EntryType::Task { completed } = entry.entry_type
-        && completed != want_completed
+    if !clause.task_states.is_empty()
+        && let EntryType::Task { state } = entry.entry_type
+        && !clause.task_states.contains(&state)
     {
         return false;
     }
 
-    let entry_tags = extract_tags(&entry.content);
+    if let Some((before, after)) = clause.due_range {
+        let Some(due) = extract_due_date(&entry.content, source_date) else {
+            return false;
+        };
+        if before.is_some_and(|b| due > b) || after.is_some_and(|a| due < a) {
+            return false;
+        }
+    }
 
-    for required_tag in &filter.tags {
-        if !entry_tags
-            .iter()
-            .any(|t| t.eq_ignore_ascii_case(required_tag))
-        {
+    if let Some((before, after)) = clause.done_range {
+        let Some(done) = completed_date(&entry.content) else {
+            return false;
+        };
+        if before.is_some_and(|b| done > b) || after.is_some_and(|a| done < a) {
             return false;
         }
     }
 
-    for excluded_tag in &filter.exclude_tags {
-        if entry_tags
-            .iter()
-            .any(|t| t.eq_ignore_ascii_case(excluded_tag))
-        {
+    if let Some((before, after)) = clause.created_range {
+        let created = created_date(&entry.content).unwrap_or(source_date);
+        if before.is_some_and(|b| created > b) || after.is_some_and(|a| created < a) {
             return false;
         }
     }
 
-    let content_lower = entry.content.to_lowercase();
+    let entry_tags = extract_tags(&entry.content);
 
-    for term in &filter.search_terms {
-        if !content_lower.contains(&term.to_lowercase()) {
+    for required_tag in &clause.tags {
+        if !entry_tags.iter().any(|t| tag_matches(t, required_tag)) {
             return false;
         }
     }
 
-    for term in &filter.exclude_terms {
-        if content_lower.contains(&term.to_lowercase()) {
+    for excluded_tag in &clause.exclude_tags {
+        if entry_tags.iter().any(|t| tag_matches(t, excluded_tag)) {
             return false;
         }
     }
 
+    // Text search skips metadata comments
+    let text = strip_entry_meta(&entry.content);
+
+    if !clause.properties.is_empty() || !clause.exclude_properties.is_empty() {
+        let properties = extract_properties(&text);
+        if !clause
+            .properties
+            .iter()
+            .all(|p| p.matches(&text, &properties))
+        {
+            return false;
+        }
+        if clause
+            .exclude_properties
+            .iter()
+            .any(|p| p.matches(&text, &properties))
+        {
+            return false;
+        }
+    }
+
+    if !clause.search_terms.iter().all(|term| term.is_match(&text)) {
+        return false;
+    }
+
+    if clause.exclude_terms.iter().any(|term| term.is_match(&text)) {
+        return false;
+    }
+
     true
 }
@@ -0,0 +1,367 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+
+use chrono::NaiveDate;
+
+use super::entries::RawEntry;
+use super::filter::{
+    Filter, FilterClause, QueryModifiers, SAVED_FILTER_REGEX, apply_term, clause_matches,
+};
+use super::filter_order::FilterSort;
+use super::search::SearchTerm;
+
+/// Saved filters may reference each other; deeper nesting is reported as a cycle
+const MAX_SAVED_FILTER_DEPTH: usize = 8;
+
+/// Boolean filter expression. Juxtaposed terms form a clause, `|` (or `or`)
+/// binds looser than juxtaposition, and parentheses group.
+#[derive(Debug, Clone)]
+pub enum FilterExpr {
+    Clause(Box<FilterClause>),
+    And(Vec<FilterExpr>),
+    Or(Vec<FilterExpr>),
+    Not(Box<FilterExpr>),
+}
+
+impl Default for FilterExpr {
+    fn default() -> Self {
+        Self::Clause(Box::default())
+    }
+}
+
+impl FilterExpr {
+    /// Checks the expression against an entry written on `source_date`.
+    #[must_use]
+    pub fn matches(&self, entry: &RawEntry, source_date: NaiveDate) -> bool {
+        match self {
+            Self::Clause(clause) => clause_matches(clause, entry, source_date),
+            Self::And(operands) => operands.iter().all(|e| e.matches(entry, source_date)),
+            Self::Or(operands) => operands.iter().any(|e| e.matches(entry, source_date)),
+            Self::Not(inner) => !inner.matches(entry, source_date),
+        }
+    }
+
+    /// Appends the positive text terms of every clause not under a negation.
+    pub fn collect_search_terms(&self, terms: &mut Vec<SearchTerm>) {
+        match self {
+            Self::Clause(clause) => terms.extend(clause.search_terms.iter().cloned()),
+            Self::And(operands) | Self::Or(operands) => {
+                for operand in operands {
+                    operand.collect_search_terms(terms);
+                }
+            }
+            Self::Not(_) => {}
+        }
+    }
+}
+
+/// A part of the query that failed to parse, with its byte span in the query.
+/// Tokens coming from a saved filter point at the `$name` that pulled them in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidToken {
+    pub text: String,
+    pub span: Range<usize>,
+}
+
+impl fmt::Display for InvalidToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Term(String),
+    Open,
+    /// `-(`, negating the group it opens
+    NotOpen,
+    Close,
+    Or,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    span: Range<usize>,
+}
+
+fn split_tokens(query: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = query.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        let kind = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '(' => TokenKind::Open,
+            ')' => TokenKind::Close,
+            '|' => TokenKind::Or,
+            '-' if query[start + 1..].starts_with('(') => {
+                chars.next();
+                TokenKind::NotOpen
+            }
+            _ => {
+                // Quoted phrases and re:/.../ patterns may hold spaces,
+                // parentheses and bars
+                let mut end = start;
+                let mut quoted = false;
+                let mut in_pattern = false;
+                let mut escaped = false;
+                while let Some(&(i, c)) = chars.peek() {
+                    if in_pattern {
+                        in_pattern = escaped || c != '/';
+                        escaped = !escaped && c == '\\';
+                    } else if quoted {
+                        quoted = c != '"';
+                    } else if c.is_whitespace() || matches!(c, '(' | ')' | '|') {
+                        break;
+                    } else if c == '"' {
+                        quoted = true;
+                    } else if c == '/' && matches!(&query[start..i], "re:" | "-re:") {
+                        in_pattern = true;
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                let text = &query[start..end];
+                let kind = if text.eq_ignore_ascii_case("or") {
+                    TokenKind::Or
+                } else {
+                    TokenKind::Term(text.to_string())
+                };
+                tokens.push(Token {
+                    kind,
+                    span: start..end,
+                });
+                continue;
+            }
+        };
+        chars.next();
+        let end = chars.peek().map_or(query.len(), |&(i, _)| i);
+        tokens.push(Token {
+            kind,
+            span: start..end,
+        });
+    }
+
+    tokens
+}
+
+/// Tokenizes a query, replacing `$name` with the saved filter's tokens. A
+/// definition using `|` or parentheses is wrapped in a group so it composes
+/// as a unit; a plain one joins the surrounding clause.
+fn tokenize(
+    query: &str,
+    saved_filters: &HashMap<String, String>,
+    expanding: &mut Vec<String>,
+    invalid: &mut Vec<InvalidToken>,
+) -> Vec<Token> {
+    let mut tokens = Vec::new();
+
+    for token in split_tokens(query) {
+        let TokenKind::Term(text) = &token.kind else {
+            tokens.push(token);
+            continue;
+        };
+        let Some(name) = SAVED_FILTER_REGEX
+            .captures(text)
+            .filter(|caps| caps[0].len() == text.len())
+            .map(|caps| caps[1].to_string())
+        else {
+            tokens.push(token);
+            continue;
+        };
+        let Some(definition) = saved_filters.get(&name) else {
+            invalid.push(InvalidToken {
+                text: text.clone(),
+                span: token.span,
+            });
+            continue;
+        };
+        if expanding.contains(&name) || expanding.len() >= MAX_SAVED_FILTER_DEPTH {
+            invalid.push(InvalidToken {
+                text: format!("{text} (recursive)"),
+                span: token.span,
+            });
+            continue;
+        }
+
+        expanding.push(name);
+        let mut inner_invalid = Vec::new();
+        let inner = tokenize(definition, saved_filters, expanding, &mut inner_invalid);
+        expanding.pop();
+
+        // Errors inside a definition point at the reference
+        invalid.extend(inner_invalid.into_iter().map(|error| InvalidToken {
+            text: error.text,
+            span: token.span.clone(),
+        }));
+        let grouped = inner.iter().any(|t| !matches!(t.kind, TokenKind::Term(_)));
+        let at = |kind| Token {
+            kind,
+            span: token.span.clone(),
+        };
+        if grouped {
+            tokens.push(at(TokenKind::Open));
+        }
+        tokens.extend(inner.into_iter().map(|t| at(t.kind)));
+        if grouped {
+            tokens.push(at(TokenKind::Close));
+        }
+    }
+
+    tokens
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    today: NaiveDate,
+    modifiers: QueryModifiers,
+    invalid: Vec<InvalidToken>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&TokenKind> {
+        self.tokens.get(self.pos).map(|t| &t.kind)
+    }
+
+    fn error(&mut self, text: impl Into<String>, span: Range<usize>) {
+        self.invalid.push(InvalidToken {
+            text: text.into(),
+            span,
+        });
+    }
+
+    /// or := and (("|" | "or") and)*
+    fn parse_or(&mut self) -> FilterExpr {
+        let mut operands = vec![self.parse_and()];
+        while let Some(TokenKind::Or) = self.peek() {
+            let span = self.tokens[self.pos].span.clone();
+            self.pos += 1;
+            if matches!(self.peek(), None | Some(TokenKind::Or | TokenKind::Close)) {
+                self.error("|", span);
+                continue;
+            }
+            operands.push(self.parse_and());
+        }
+        if operands.len() == 1 {
+            operands.remove(0)
+        } else {
+            FilterExpr::Or(operands)
+        }
+    }
+
+    /// and := (term | group)*, with the terms collected into one clause
+    fn parse_and(&mut self) -> FilterExpr {
+        let mut clause = FilterClause::default();
+        let mut has_terms = false;
+        let mut groups = Vec::new();
+
+        while let Some(kind) = self.peek() {
+            let span = self.tokens[self.pos].span.clone();
+            match kind.clone() {
+                TokenKind::Term(text) => {
+                    self.pos += 1;
+                    has_terms = true;
+                    if let Err(invalid) =
+                        apply_term(&mut clause, &mut self.modifiers, &text, self.today)
+                    {
+                        self.error(invalid, span);
+                    }
+                }
+                kind @ (TokenKind::Open | TokenKind::NotOpen) => {
+                    self.pos += 1;
+                    let group = self.parse_group(span);
+                    groups.push(if kind == TokenKind::NotOpen {
+                        FilterExpr::Not(Box::new(group))
+                    } else {
+                        group
+                    });
+                }
+                TokenKind::Close | TokenKind::Or => break,
+            }
+        }
+
+        if groups.is_empty() {
+            return FilterExpr::Clause(Box::new(clause));
+        }
+        if has_terms {
+            groups.insert(0, FilterExpr::Clause(Box::new(clause)));
+        }
+        if groups.len() == 1 {
+            groups.remove(0)
+        } else {
+            FilterExpr::And(groups)
+        }
+    }
+
+    /// group := "(" or ")", with the opening token already consumed
+    fn parse_group(&mut self, open_span: Range<usize>) -> FilterExpr {
+        if let Some(TokenKind::Close) = self.peek() {
+            let close_span = self.tokens[self.pos].span.clone();
+            self.pos += 1;
+            self.error("()", open_span.start..close_span.end);
+            return FilterExpr::default();
+        }
+
+        let inner = self.parse_or();
+        if let Some(TokenKind::Close) = self.peek() {
+            self.pos += 1;
+        } else {
+            self.error("(", open_span);
+        }
+        inner
+    }
+}
+
+/// Parses a filter query into an expression, resolving `$name` references
+/// against `saved_filters`.
+#[must_use]
+pub fn parse_filter_query(query: &str, saved_filters: &HashMap<String, String>) -> Filter {
+    let mut invalid = Vec::new();
+    let tokens = tokenize(query, saved_filters, &mut Vec::new(), &mut invalid);
+
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        today: chrono::Local::now().date_naive(),
+        modifiers: QueryModifiers::default(),
+        invalid,
+    };
+    let mut expr = parser.parse_or();
+
+    // Anything left over starts with an unmatched ")"
+    while parser.pos < parser.tokens.len() {
+        let span = parser.tokens[parser.pos].span.clone();
+        parser.pos += 1;
+        parser.error(")", span);
+        expr = FilterExpr::And(vec![expr, parser.parse_or()]);
+    }
+
+    parser.invalid.sort_by_key(|token| token.span.start);
+    let mut filter = Filter {
+        expr,
+        recurring: parser.modifiers.recurring,
+        scopes: parser.modifiers.scopes,
+        group: parser.modifiers.group,
+        invalid_tokens: parser.invalid,
+        sort: FilterSort::Date,
+    };
+    // Fuzzy terms rank results unless the query picks an order
+    filter.sort = parser.modifiers.sort.unwrap_or_else(|| {
+        if filter
+            .highlight_terms()
+            .iter()
+            .any(|term| matches!(term, SearchTerm::Fuzzy(_)))
+        {
+            FilterSort::Match
+        } else {
+            FilterSort::Date
+        }
+    });
+    filter
+}
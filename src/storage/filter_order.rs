@@ -0,0 +1,249 @@
+use std::borrow::Cow;
+use std::cmp::Reverse;
+
+use super::entries::{Entry, EntryType, TaskState};
+use super::filter::{Filter, created_date, extract_tags, strip_entry_meta};
+use super::properties::{is_property_key, property_value};
+
+/// Order of filter results, chosen with `sort:<key>`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum FilterSort {
+    /// Oldest day first
+    #[default]
+    Date,
+    /// Newest day first
+    DateDesc,
+    /// Tasks, then notes, then events
+    Type,
+    /// Open tasks first, then by task state; notes and events last
+    Completion,
+    /// Alphabetically by first tag; untagged entries last
+    Tag,
+    /// Entries that have been around longest first, by their recorded
+    /// creation date or else the day they're on
+    Age,
+    /// Best fuzzy match first; the default for queries with `~` terms
+    Match,
+    /// By the value of a `key:value` property, smallest first unless
+    /// `descending` (`sort:est-desc`); entries without it last
+    Property { key: String, descending: bool },
+}
+
+impl FilterSort {
+    pub const ALL: [Self; 7] = [
+        Self::Date,
+        Self::DateDesc,
+        Self::Type,
+        Self::Completion,
+        Self::Tag,
+        Self::Age,
+        Self::Match,
+    ];
+
+    #[must_use]
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "date" | "date-asc" => Some(Self::Date),
+            "date-desc" => Some(Self::DateDesc),
+            "type" => Some(Self::Type),
+            "completion" | "state" => Some(Self::Completion),
+            "tag" => Some(Self::Tag),
+            "age" => Some(Self::Age),
+            "match" => Some(Self::Match),
+            _ => {
+                let (key, descending) = match s.strip_suffix("-desc") {
+                    Some(key) => (key, true),
+                    None => (s, false),
+                };
+                is_property_key(key).then(|| Self::Property {
+                    key: key.to_lowercase(),
+                    descending,
+                })
+            }
+        }
+    }
+
+    /// Name written after `sort:` in a query.
+    #[must_use]
+    pub fn name(&self) -> Cow<'static, str> {
+        match self {
+            Self::Date => "date".into(),
+            Self::DateDesc => "date-desc".into(),
+            Self::Type => "type".into(),
+            Self::Completion => "completion".into(),
+            Self::Tag => "tag".into(),
+            Self::Age => "age".into(),
+            Self::Match => "match".into(),
+            Self::Property { key, descending } => {
+                let suffix = if *descending { "-desc" } else { "" };
+                format!("{key}{suffix}").into()
+            }
+        }
+    }
+
+    /// Next built-in order in the cycle; property sorts go back to the start.
+    #[must_use]
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|sort| sort == self);
+        index.map_or(Self::ALL[0].clone(), |index| {
+            Self::ALL[(index + 1) % Self::ALL.len()].clone()
+        })
+    }
+}
+
+/// Grouping of filter results under headers, chosen with `group:<key>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterGroup {
+    Day,
+    /// By first tag
+    Tag,
+    /// By the journal an entry came from
+    Project,
+    Type,
+}
+
+impl FilterGroup {
+    pub const ALL: [Self; 4] = [Self::Day, Self::Tag, Self::Project, Self::Type];
+
+    /// Parses a `group:` value. `none` is valid and clears grouping, so a
+    /// query can drop the grouping of a saved filter it uses.
+    #[must_use]
+    pub fn parse(s: &str) -> Option<Option<Self>> {
+        match s {
+            "none" => Some(None),
+            "day" | "date" => Some(Some(Self::Day)),
+            "tag" => Some(Some(Self::Tag)),
+            "project" | "journal" => Some(Some(Self::Project)),
+            "type" => Some(Some(Self::Type)),
+            _ => None,
+        }
+    }
+
+    /// Name written after `group:` in a query.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Day => "day",
+            Self::Tag => "tag",
+            Self::Project => "project",
+            Self::Type => "type",
+        }
+    }
+
+    /// Next grouping in the cycle, passing through no grouping.
+    #[must_use]
+    pub fn next(group: Option<Self>) -> Option<Self> {
+        match group {
+            None => Some(Self::ALL[0]),
+            Some(group) => {
+                let index = Self::ALL.iter().position(|&g| g == group).unwrap_or(0);
+                Self::ALL.get(index + 1).copied()
+            }
+        }
+    }
+
+    /// Header an entry is listed under.
+    #[must_use]
+    pub fn label(self, entry: &Entry) -> String {
+        match self {
+            Self::Day => entry.source_date.format("%a %Y/%m/%d").to_string(),
+            Self::Tag => extract_tags(&entry.content)
+                .into_iter()
+                .next()
+                .map_or_else(|| "untagged".to_string(), |tag| format!("#{tag}")),
+            Self::Project => entry.journal.as_ref().map_or_else(
+                || "this journal".to_string(),
+                |journal| journal.name.clone(),
+            ),
+            Self::Type => match entry.entry_type {
+                EntryType::Task { .. } => "tasks",
+                EntryType::Note => "notes",
+                EntryType::Event => "events",
+            }
+            .to_string(),
+        }
+    }
+}
+
+fn type_rank(entry_type: &EntryType) -> usize {
+    match entry_type {
+        EntryType::Task { .. } => 0,
+        EntryType::Note => 1,
+        EntryType::Event => 2,
+    }
+}
+
+fn completion_rank(entry_type: &EntryType) -> usize {
+    entry_type
+        .task_state()
+        .map_or(TaskState::ALL.len(), |state| {
+            TaskState::ALL
+                .iter()
+                .position(|&s| s == state)
+                .unwrap_or_default()
+        })
+}
+
+/// Orders date-sorted results by `sort`, then gathers them under their group
+/// headers. Groups appear in the order their first entry sorted to. Both
+/// steps are stable, so entries that tie keep their file order. Each entry is
+/// sorted and grouped by its own content, so a sub-entry stays below its
+/// parent under the date sorts but can move away from it under the others.
+pub fn order_filter_results(entries: &mut [Entry], filter: &Filter) {
+    match &filter.sort {
+        FilterSort::Date => entries.sort_by_key(|entry| entry.source_date),
+        FilterSort::Age => entries
+            .sort_by_cached_key(|entry| created_date(&entry.content).unwrap_or(entry.source_date)),
+        FilterSort::DateDesc => entries.sort_by_key(|entry| Reverse(entry.source_date)),
+        FilterSort::Type => entries.sort_by_key(|entry| type_rank(&entry.entry_type)),
+        FilterSort::Completion => entries.sort_by_key(|entry| completion_rank(&entry.entry_type)),
+        FilterSort::Tag => entries.sort_by_cached_key(|entry| {
+            let tag = extract_tags(&entry.content)
+                .into_iter()
+                .next()
+                .map(|tag| tag.to_lowercase());
+            (tag.is_none(), tag)
+        }),
+        FilterSort::Match => {
+            let terms = filter.highlight_terms();
+            entries.sort_by_cached_key(|entry| {
+                Reverse(
+                    terms
+                        .iter()
+                        .map(|term| term.score(&entry.content))
+                        .sum::<u32>(),
+                )
+            });
+        }
+        // Entries without the property go last either way
+        FilterSort::Property {
+            key,
+            descending: false,
+        } => entries.sort_by_cached_key(|entry| {
+            let value = property_value(&strip_entry_meta(&entry.content), key);
+            (value.is_none(), value)
+        }),
+        FilterSort::Property {
+            key,
+            descending: true,
+        } => entries.sort_by_cached_key(|entry| {
+            let value = property_value(&strip_entry_meta(&entry.content), key);
+            (value.is_none(), value.map(Reverse))
+        }),
+    }
+
+    let Some(group) = filter.group else {
+        return;
+    };
+    let mut labels: Vec<String> = Vec::new();
+    entries.sort_by_cached_key(|entry| {
+        let label = group.label(entry);
+        match labels.iter().position(|existing| *existing == label) {
+            Some(index) => index,
+            None => {
+                labels.push(label);
+                labels.len() - 1
+            }
+        }
+    });
+}
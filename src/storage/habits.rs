@@ -0,0 +1,135 @@
+use std::io;
+use std::path::Path;
+
+use chrono::NaiveDate;
+
+use super::entries::{EntryType, Line};
+use super::filter::{
+    extract_recurring_pattern, is_done_on_date, is_skipped_on, recurs_on, strip_entry_meta,
+    strip_recurring_tags,
+};
+use super::store::JournalStore;
+
+/// Outcome of one scheduled occurrence of a recurring task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OccurrenceMark {
+    Done,
+    Missed,
+    Skipped,
+    /// Today's occurrence, not done yet
+    Pending,
+}
+
+/// Completion history of a recurring task, from the day after it was written
+/// through today.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Habit {
+    /// Display content, without the @every-* pattern and occurrence metadata
+    pub content: String,
+    pub source_date: NaiveDate,
+    pub line_index: usize,
+    /// Every scheduled occurrence so far, oldest first
+    pub occurrences: Vec<(NaiveDate, OccurrenceMark)>,
+}
+
+impl Habit {
+    /// Outcomes that count toward streaks and completion: skipped and
+    /// pending occurrences neither extend nor break a streak.
+    fn counted(&self) -> impl DoubleEndedIterator<Item = bool> + '_ {
+        self.occurrences.iter().filter_map(|(_, mark)| match mark {
+            OccurrenceMark::Done => Some(true),
+            OccurrenceMark::Missed => Some(false),
+            OccurrenceMark::Skipped | OccurrenceMark::Pending => None,
+        })
+    }
+
+    /// Consecutive completed occurrences ending with the most recent one.
+    #[must_use]
+    pub fn current_streak(&self) -> usize {
+        self.counted().rev().take_while(|done| *done).count()
+    }
+
+    #[must_use]
+    pub fn best_streak(&self) -> usize {
+        let mut best = 0;
+        let mut run = 0;
+        for done in self.counted() {
+            run = if done { run + 1 } else { 0 };
+            best = best.max(run);
+        }
+        best
+    }
+
+    /// Percentage of done occurrences among done and missed ones, or None
+    /// before the first occurrence is due.
+    #[must_use]
+    pub fn completion_percent(&self) -> Option<usize> {
+        let (done, total) = self.counted().fold((0, 0), |(done, total), d| {
+            (done + usize::from(d), total + 1)
+        });
+        (total > 0).then(|| done * 100 / total)
+    }
+
+    /// The last `count` scheduled occurrences, oldest first.
+    #[must_use]
+    pub fn recent(&self, count: usize) -> &[(NaiveDate, OccurrenceMark)] {
+        &self.occurrences[self.occurrences.len().saturating_sub(count)..]
+    }
+}
+
+/// Collects every recurring task in the journal with its occurrence history
+/// up to `today`, ordered by when each was written.
+pub fn collect_habits(
+    store: &dyn JournalStore,
+    path: &Path,
+    today: NaiveDate,
+) -> io::Result<Vec<Habit>> {
+    let mut habits = Vec::new();
+
+    store.visit_days(path, &mut |source_date, lines| {
+        for (line_index, line) in lines.iter().enumerate() {
+            let Line::Entry(raw_entry) = line else {
+                continue;
+            };
+            if !matches!(raw_entry.entry_type, EntryType::Task { .. }) {
+                continue;
+            }
+            let content = &raw_entry.content;
+            let Some(recurrence) = extract_recurring_pattern(content, source_date) else {
+                continue;
+            };
+
+            let occurrences = source_date
+                .iter_days()
+                .skip(1)
+                .take_while(|date| *date <= today)
+                .filter_map(|date| {
+                    let mark = if recurs_on(content, &recurrence, source_date, date) {
+                        if is_done_on_date(content, date) {
+                            OccurrenceMark::Done
+                        } else if date == today {
+                            OccurrenceMark::Pending
+                        } else {
+                            OccurrenceMark::Missed
+                        }
+                    } else if recurrence.matches(date) && is_skipped_on(content, date) {
+                        OccurrenceMark::Skipped
+                    } else {
+                        return None;
+                    };
+                    Some((date, mark))
+                })
+                .collect();
+
+            habits.push(Habit {
+                content: strip_recurring_tags(&strip_entry_meta(content)),
+                source_date,
+                line_index,
+                occurrences,
+            });
+        }
+    })?;
+
+    habits.sort_by_key(|habit| habit.source_date);
+    Ok(habits)
+}
@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::time::SystemTime;
+
+use chrono::NaiveDate;
+
+use super::entries::{Line, parse_lines, serialize_lines};
+use super::lock::lock_journal;
+use super::persistence::{parse_day_header, update_day_content, write_atomic};
+
+/// Parsed journals shared by every `MarkdownStore` in the process, keyed by path.
+static CACHE: LazyLock<Mutex<HashMap<PathBuf, CachedJournal>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Identifies a version of the file on disk. A cached journal is reused only
+/// while the file's modification time and size are unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+impl FileStamp {
+    /// None if the file doesn't exist.
+    fn read(path: &Path) -> io::Result<Option<Self>> {
+        match fs::metadata(path) {
+            Ok(metadata) => Ok(Some(Self {
+                modified: metadata.modified().ok(),
+                len: metadata.len(),
+            })),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// One day section of the journal.
+#[derive(Debug, Clone)]
+pub(super) struct CachedDay {
+    pub date: NaiveDate,
+    /// Byte range of the day's content (after its header line) in the journal text
+    pub content: Range<usize>,
+    pub lines: Vec<Line>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(super) struct CachedJournal {
+    stamp: Option<FileStamp>,
+    text: String,
+    /// Day sections in file order
+    pub days: Vec<CachedDay>,
+    /// First section for each date
+    index: HashMap<NaiveDate, usize>,
+}
+
+/// Finds day sections by their header lines, returning each date with the byte
+/// range of its content. Text before the first header belongs to no day.
+pub(super) fn index_days(journal: &str) -> Vec<(NaiveDate, Range<usize>)> {
+    let mut sections = Vec::new();
+    let mut current: Option<(NaiveDate, usize)> = None;
+    let mut pos = 0;
+
+    for line in journal.split_inclusive('\n') {
+        let start = pos;
+        pos += line.len();
+        let text = line.trim_end_matches(['\n', '\r']);
+        if let Some(date) = parse_day_header(text) {
+            if let Some((date, content_start)) = current.take() {
+                sections.push((date, content_start..start));
+            }
+            current = Some((date, pos));
+        }
+    }
+    if let Some((date, content_start)) = current {
+        sections.push((date, content_start..journal.len()));
+    }
+    sections
+}
+
+pub(super) fn parse_section(content: &str) -> Vec<Line> {
+    parse_lines(content.trim_end())
+}
+
+impl CachedJournal {
+    fn parse(text: String, stamp: Option<FileStamp>) -> Self {
+        Self::reindex(text, stamp, &Self::default())
+    }
+
+    /// Indexes `text`, reusing parsed lines from `previous` for every section whose
+    /// text is unchanged, so an edit only re-parses the days it touched.
+    fn reindex(text: String, stamp: Option<FileStamp>, previous: &Self) -> Self {
+        let mut unchanged: HashMap<(NaiveDate, &str), &Vec<Line>> = HashMap::new();
+        for day in &previous.days {
+            unchanged
+                .entry((day.date, &previous.text[day.content.clone()]))
+                .or_insert(&day.lines);
+        }
+
+        let mut days = Vec::new();
+        let mut index = HashMap::new();
+        for (date, content) in index_days(&text) {
+            let section = &text[content.clone()];
+            let lines = match unchanged.get(&(date, section)) {
+                Some(lines) => (*lines).clone(),
+                None => parse_section(section),
+            };
+            index.entry(date).or_insert(days.len());
+            days.push(CachedDay {
+                date,
+                content,
+                lines,
+            });
+        }
+
+        Self {
+            stamp,
+            text,
+            days,
+            index,
+        }
+    }
+
+    #[must_use]
+    pub fn day(&self, date: NaiveDate) -> Option<&CachedDay> {
+        self.index.get(&date).map(|&i| &self.days[i])
+    }
+}
+
+fn cache() -> std::sync::MutexGuard<'static, HashMap<PathBuf, CachedJournal>> {
+    CACHE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns the cached journal for `path`, re-reading it only if the file changed.
+fn refresh<'a>(
+    journals: &'a mut HashMap<PathBuf, CachedJournal>,
+    path: &Path,
+) -> io::Result<&'a mut CachedJournal> {
+    // Stat before reading so a write racing with the read leaves a stale stamp
+    // and is picked up next time.
+    let stamp = FileStamp::read(path)?;
+    let cached = journals.entry(path.to_path_buf()).or_default();
+    if cached.stamp != stamp {
+        let text = match stamp {
+            Some(_) => fs::read_to_string(path)?,
+            None => String::new(),
+        };
+        *cached = CachedJournal::parse(text, stamp);
+    }
+    Ok(cached)
+}
+
+/// Runs `f` against the parsed journal.
+pub(super) fn with_journal<R>(path: &Path, f: impl FnOnce(&CachedJournal) -> R) -> io::Result<R> {
+    let mut journals = cache();
+    let journal = refresh(&mut journals, path)?;
+    Ok(f(journal))
+}
+
+/// Applies `f` to one day (or every day section, repeated dates included, when
+/// `only` is None) while holding the journal lock, then writes the journal if
+/// any lines changed. Only the days `f` changed are re-parsed.
+///
+/// `f` runs on a copy of the days without the cache locked, so it may read
+/// through a store itself; the journal lock keeps other writers out.
+pub(super) fn modify_days(
+    path: &Path,
+    only: Option<NaiveDate>,
+    f: &mut dyn FnMut(NaiveDate, &mut Vec<Line>),
+) -> io::Result<()> {
+    let _lock = lock_journal(path)?;
+    let (text, days) = {
+        let mut journals = cache();
+        let journal = refresh(&mut journals, path)?;
+        let days: Vec<CachedDay> = match only {
+            Some(date) => journal.day(date).cloned().into_iter().collect(),
+            None => journal.days.clone(),
+        };
+        (journal.text.clone(), days)
+    };
+
+    let updated = match only {
+        Some(date) => {
+            let original = days.first().map(|d| d.lines.clone()).unwrap_or_default();
+            let mut lines = original.clone();
+            f(date, &mut lines);
+            if lines == original {
+                return Ok(());
+            }
+            update_day_content(&text, date, &serialize_lines(&lines))
+        }
+        None => {
+            let mut changed = HashMap::new();
+            for (i, day) in days.iter().enumerate() {
+                let mut lines = day.lines.clone();
+                f(day.date, &mut lines);
+                if lines != day.lines {
+                    changed.insert(i, lines);
+                }
+            }
+            if changed.is_empty() {
+                return Ok(());
+            }
+            rewrite_sections(&text, &days, &changed)
+        }
+    };
+    if updated == text {
+        return Ok(());
+    }
+
+    let written = write_atomic(path, &updated);
+    let mut journals = cache();
+    let journal = journals.entry(path.to_path_buf()).or_default();
+    if let Err(e) = written {
+        // The file may or may not have been replaced; re-read it next time
+        *journal = CachedJournal::default();
+        return Err(e);
+    }
+    let reindexed = CachedJournal::reindex(updated, FileStamp::read(path)?, journal);
+    *journal = reindexed;
+    Ok(())
+}
+
+/// Rebuilds the journal text with the sections in `changed` (by index into
+/// `days`, the sections of `text`) replaced in place, so repeated headers for
+/// one date each keep their own content. Unchanged sections are copied
+/// verbatim and sections left empty are dropped.
+fn rewrite_sections(text: &str, days: &[CachedDay], changed: &HashMap<usize, Vec<Line>>) -> String {
+    let header_start = |day: &CachedDay| {
+        text[..day.content.start]
+            .trim_end_matches(['\n', '\r'])
+            .rfind('\n')
+            .map_or(0, |i| i + 1)
+    };
+
+    let mut result = String::with_capacity(text.len());
+    let mut pos = 0;
+    for (i, day) in days.iter().enumerate() {
+        let Some(lines) = changed.get(&i) else {
+            continue;
+        };
+        let start = header_start(day);
+        result.push_str(&text[pos..start]);
+        let content = serialize_lines(lines);
+        if !content.trim().is_empty() {
+            let header = text[start..day.content.start].trim_end();
+            result.push_str(header);
+            result.push('\n');
+            result.push_str(content.trim_end());
+            result.push_str("\n\n");
+        }
+        pos = day.content.end;
+    }
+    result.push_str(&text[pos..]);
+
+    let trimmed = result.trim_end();
+    if trimmed.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n", trimmed)
+    }
+}
+
+/// Drops the cached copy of a journal written through the text-level API.
+pub(super) fn invalidate(path: &Path) {
+    cache().remove(path);
+}
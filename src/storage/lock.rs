@@ -0,0 +1,62 @@
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::persistence::resolve_journal_path;
+
+/// How long to wait for another process to finish its write before giving up.
+const LOCK_TIMEOUT: Duration = Duration::from_millis(1000);
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Advisory lock held for the duration of a journal read-modify-write cycle.
+/// Released when dropped.
+#[derive(Debug)]
+pub struct JournalLock {
+    _file: File,
+}
+
+/// Path of the lock file guarding a journal: a hidden sibling of the resolved
+/// file, so the lock survives the journal being replaced by an atomic rename
+/// and symlinked paths to one journal share a lock.
+#[must_use]
+pub fn lock_path(journal_path: &Path) -> PathBuf {
+    let target = resolve_journal_path(journal_path);
+    let name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "journal".to_string());
+    target.with_file_name(format!(".{name}.lock"))
+}
+
+/// Acquires the journal lock, retrying briefly if another process holds it.
+/// Returns a `WouldBlock` error if the lock is still contended after the timeout.
+pub fn lock_journal(journal_path: &Path) -> io::Result<JournalLock> {
+    if let Some(parent) = journal_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let path = lock_path(journal_path);
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)?;
+
+    let start = Instant::now();
+    loop {
+        match file.try_lock() {
+            Ok(()) => return Ok(JournalLock { _file: file }),
+            Err(TryLockError::WouldBlock) if start.elapsed() < LOCK_TIMEOUT => {
+                thread::sleep(LOCK_RETRY_INTERVAL);
+            }
+            Err(TryLockError::WouldBlock) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::WouldBlock,
+                    "Journal is locked by another caliber process, try again",
+                ));
+            }
+            Err(TryLockError::Error(e)) => return Err(e),
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use std::io;
+use std::path::Path;
+
+use chrono::NaiveDate;
+
+use super::entries::Line;
+use super::store::{JournalStore, modify_day_lines, normalize_day_lines};
+
+/// A contiguous change against the base: `base[start..end]` replaced by `lines`.
+#[derive(Debug, Clone)]
+struct Hunk<T> {
+    start: usize,
+    end: usize,
+    lines: Vec<T>,
+}
+
+/// A region of a three-way merge result.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MergeChunk<T> {
+    /// Lines both sides agree on (unchanged, changed by one side, or changed identically)
+    Resolved(Vec<T>),
+    /// Overlapping changes that need a decision
+    Conflict {
+        base: Vec<T>,
+        mine: Vec<T>,
+        theirs: Vec<T>,
+    },
+}
+
+/// How to resolve conflicting chunks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictResolution {
+    Mine,
+    Theirs,
+    /// Mine followed by theirs
+    Both,
+}
+
+/// Longest-common-subsequence alignment, returned as matching (base, other) index pairs.
+fn matching_pairs<T: PartialEq>(base: &[T], other: &[T]) -> Vec<(usize, usize)> {
+    let (n, m) = (base.len(), other.len());
+    let mut table = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[i][j] = if base[i] == other[j] {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if base[i] == other[j] {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if table[i + 1][j] >= table[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs
+}
+
+fn diff_hunks<T: PartialEq + Clone>(base: &[T], other: &[T]) -> Vec<Hunk<T>> {
+    let mut hunks = Vec::new();
+    let (mut bi, mut oi) = (0, 0);
+    let sentinel = (base.len(), other.len());
+    for (bm, om) in matching_pairs(base, other).into_iter().chain([sentinel]) {
+        if bm > bi || om > oi {
+            hunks.push(Hunk {
+                start: bi,
+                end: bm,
+                lines: other[oi..om].to_vec(),
+            });
+        }
+        bi = bm + 1;
+        oi = om + 1;
+    }
+    hunks
+}
+
+/// Two changes overlap if their base ranges intersect. Pure insertions only
+/// collide with insertions at the same point or changes that surround them.
+fn overlaps(a: (usize, usize), b: (usize, usize)) -> bool {
+    match (a.0 == a.1, b.0 == b.1) {
+        (true, true) => a.0 == b.0,
+        (true, false) => b.0 < a.0 && a.0 < b.1,
+        (false, true) => a.0 < b.0 && b.0 < a.1,
+        (false, false) => a.0 < b.1 && b.0 < a.1,
+    }
+}
+
+/// Applies one side's hunks within `base[start..end]`.
+fn apply_hunks<T: Clone>(base: &[T], start: usize, end: usize, hunks: &[&Hunk<T>]) -> Vec<T> {
+    let mut result = Vec::new();
+    let mut pos = start;
+    for hunk in hunks {
+        result.extend_from_slice(&base[pos..hunk.start]);
+        result.extend(hunk.lines.iter().cloned());
+        pos = hunk.end;
+    }
+    result.extend_from_slice(&base[pos..end]);
+    result
+}
+
+/// Line-based three-way merge. Changes that touch different parts of the base
+/// merge cleanly; overlapping changes that differ become conflict chunks.
+#[must_use]
+pub fn merge3<T: PartialEq + Clone>(base: &[T], mine: &[T], theirs: &[T]) -> Vec<MergeChunk<T>> {
+    let mut hunks: Vec<(bool, Hunk<T>)> = diff_hunks(base, mine)
+        .into_iter()
+        .map(|h| (true, h))
+        .chain(diff_hunks(base, theirs).into_iter().map(|h| (false, h)))
+        .collect();
+    hunks.sort_by_key(|(_, h)| (h.start, h.end));
+
+    let mut chunks = Vec::new();
+    let mut resolved: Vec<T> = Vec::new();
+    let mut pos = 0;
+    let mut idx = 0;
+
+    while idx < hunks.len() {
+        let (mut start, mut end) = (hunks[idx].1.start, hunks[idx].1.end);
+        let mut group_end = idx + 1;
+        while group_end < hunks.len()
+            && overlaps((start, end), {
+                let h = &hunks[group_end].1;
+                (h.start, h.end)
+            })
+        {
+            start = start.min(hunks[group_end].1.start);
+            end = end.max(hunks[group_end].1.end);
+            group_end += 1;
+        }
+        let group = &hunks[idx..group_end];
+        idx = group_end;
+
+        resolved.extend_from_slice(&base[pos..start]);
+        pos = end;
+
+        let side = |is_mine: bool| -> Vec<&Hunk<T>> {
+            group
+                .iter()
+                .filter(|(m, _)| *m == is_mine)
+                .map(|(_, h)| h)
+                .collect()
+        };
+        let (mine_hunks, their_hunks) = (side(true), side(false));
+        let mine_lines = apply_hunks(base, start, end, &mine_hunks);
+        let their_lines = apply_hunks(base, start, end, &their_hunks);
+
+        if their_hunks.is_empty() || mine_lines == their_lines {
+            resolved.extend(mine_lines);
+        } else if mine_hunks.is_empty() {
+            resolved.extend(their_lines);
+        } else {
+            if !resolved.is_empty() {
+                chunks.push(MergeChunk::Resolved(std::mem::take(&mut resolved)));
+            }
+            chunks.push(MergeChunk::Conflict {
+                base: base[start..end].to_vec(),
+                mine: mine_lines,
+                theirs: their_lines,
+            });
+        }
+    }
+
+    resolved.extend_from_slice(&base[pos..]);
+    if !resolved.is_empty() {
+        chunks.push(MergeChunk::Resolved(resolved));
+    }
+    chunks
+}
+
+/// Flattens merge chunks, resolving any conflicts with `resolution`.
+#[must_use]
+pub fn resolve_chunks<T: Clone>(
+    chunks: &[MergeChunk<T>],
+    resolution: ConflictResolution,
+) -> Vec<T> {
+    let mut result = Vec::new();
+    for chunk in chunks {
+        match chunk {
+            MergeChunk::Resolved(lines) => result.extend(lines.iter().cloned()),
+            MergeChunk::Conflict { mine, theirs, .. } => match resolution {
+                ConflictResolution::Mine => result.extend(mine.iter().cloned()),
+                ConflictResolution::Theirs => result.extend(theirs.iter().cloned()),
+                ConflictResolution::Both => {
+                    result.extend(mine.iter().cloned());
+                    result.extend(theirs.iter().cloned());
+                }
+            },
+        }
+    }
+    result
+}
+
+/// Overlapping edits to a day made both in memory and on disk.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DayConflict {
+    pub date: NaiveDate,
+    /// The day as found on disk; the new base once the conflict is resolved
+    pub theirs: Vec<Line>,
+    pub chunks: Vec<MergeChunk<Line>>,
+}
+
+impl DayConflict {
+    #[must_use]
+    pub fn resolve(&self, resolution: ConflictResolution) -> Vec<Line> {
+        resolve_chunks(&self.chunks, resolution)
+    }
+}
+
+/// Result of saving a day against the content it was loaded from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SaveOutcome {
+    /// The day on disk still matched the base and was overwritten
+    Saved(Vec<Line>),
+    /// The day changed on disk, but the edits didn't overlap and were merged
+    Merged(Vec<Line>),
+    /// Overlapping edits; nothing was written
+    Conflict(DayConflict),
+}
+
+/// Saves a day's lines, merging with any changes made in the store since `base` was loaded.
+/// Returned lines are the day as now stored.
+pub fn save_day_lines_merged(
+    store: &dyn JournalStore,
+    date: NaiveDate,
+    path: &Path,
+    base: &[Line],
+    mine: &[Line],
+) -> io::Result<SaveOutcome> {
+    modify_day_lines(store, date, path, |day| {
+        let theirs = day.clone();
+        let (lines, merged) = if theirs == base || theirs == mine {
+            (mine.to_vec(), false)
+        } else {
+            let chunks = merge3(base, mine, &theirs);
+            if chunks
+                .iter()
+                .any(|c| matches!(c, MergeChunk::Conflict { .. }))
+            {
+                return SaveOutcome::Conflict(DayConflict {
+                    date,
+                    theirs,
+                    chunks,
+                });
+            }
+            (resolve_chunks(&chunks, ConflictResolution::Mine), true)
+        };
+
+        let stored = normalize_day_lines(&lines);
+        *day = lines;
+        if merged {
+            SaveOutcome::Merged(stored)
+        } else {
+            SaveOutcome::Saved(stored)
+        }
+    })
+}
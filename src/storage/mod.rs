@@ -1,41 +1,87 @@
 mod context;
+mod daily_files;
 mod date_parsing;
+mod doctor;
 mod entries;
 mod filter;
+mod filter_expr;
+mod filter_order;
+mod habits;
+mod journal_cache;
+mod lock;
+mod merge;
 mod persistence;
 mod project_registry;
+mod properties;
+mod search;
+mod store;
 
 // Re-export context types and functions
-pub use context::{JournalContext, JournalSlot, detect_project_journal, find_git_root};
+pub use context::{
+    JournalContext, JournalSlot, detect_project_journal, detect_project_with_profile, find_git_root,
+};
 
 // Re-export entry types
 pub use entries::{
-    Entry, EntryType, Line, RawEntry, RecurringPattern, SourceType, parse_lines,
-    parse_to_raw_entry, serialize_lines,
+    Entry, EntryType, INDENT, Line, RawEntry, Recurrence, RecurringPattern, SourceJournal,
+    SourceType, TaskState, parse_lines, parse_to_raw_entry, serialize_lines, subtask_progress,
+    subtree_end,
 };
 
 // Re-export persistence functions and types
 pub use persistence::{
     DayInfo, cycle_entry_type, delete_entry, extract_day_content, get_entry_content,
-    get_entry_type, load_day, load_day_lines, load_journal, mutate_entry, parse_day_header,
-    save_day, save_day_lines, save_journal, scan_days_in_range, toggle_entry_complete,
-    update_day_content, update_entry_content,
+    get_entry_type, load_day, load_journal, modify_journal, mutate_entry, parse_day_header,
+    save_journal, toggle_entry_complete, update_day_content, update_entry_content,
+    write_private_file,
+};
+
+// Re-export journal storage backends
+pub use daily_files::DailyFilesStore;
+pub use store::{
+    JournalDay, JournalStore, MarkdownStore, MemoryStore, copy_journal, modify_day_lines,
+    normalize_day_lines, parse_journal_days,
+};
+
+// Re-export journal locking
+pub use lock::{JournalLock, lock_journal, lock_path};
+
+// Re-export three-way merge types and functions
+pub use merge::{
+    ConflictResolution, DayConflict, MergeChunk, SaveOutcome, merge3, resolve_chunks,
+    save_day_lines_merged,
 };
 
 // Re-export date parsing types and functions
 pub use date_parsing::{ParseContext, parse_date, parse_weekday};
 
+// Re-export journal diagnostics
+pub use doctor::{IssueKind, JournalIssue, diagnose_journal, repair_journal};
+
 // Re-export filter types and functions
 pub use filter::{
-    FAVORITE_TAG_REGEX, Filter, FilterType, LAST_TRAILING_TAG_REGEX, RECURRING_REGEX,
-    SAVED_FILTER_REGEX, TAG_CHAR_CLASS, TAG_REGEX, TRAILING_TAGS_REGEX, add_done_date,
-    collect_filtered_entries, collect_journal_tags, collect_projected_entries_for_date,
-    create_tag_delete_regex, create_tag_match_regex, expand_favorite_tags, expand_saved_filters,
-    extract_recurring_pattern, extract_tags, is_done_on_date, normalize_entry_structure,
-    parse_filter_date, parse_filter_query, parse_natural_date, parse_recurring_pattern,
-    remove_done_date, restore_done_meta, scan_recurring_in_range, strip_done_meta,
-    strip_recurring_tags,
+    DUE_REGEX, FAVORITE_TAG_REGEX, Filter, FilterClause, FilterScope, FilterType, RECURRING_REGEX,
+    SAVED_FILTER_REGEX, TAG_CHAR_CLASS, TAG_REGEX, TRAILING_SECTION_REGEX, add_created_date,
+    add_done_date, collect_filtered_entries, collect_filtered_entries_across, collect_journal_tags,
+    collect_projected_entries_for_date, completed_date, create_tag_delete_regex,
+    create_tag_match_regex, created_date, expand_favorite_tags, extract_due_date,
+    extract_recurring_pattern, extract_tags, is_done_on_date, is_skipped_on, move_occurrence,
+    normalize_entry_structure, parse_filter_date, parse_natural_date, parse_recurring_pattern,
+    record_completion, recurs_on, remove_done_date, resolve_filter_scope, restore_entry_meta,
+    scan_recurring_in_range, skip_occurrence, strip_entry_meta, strip_recurring_tags, tag_matches,
 };
+pub use filter_expr::{FilterExpr, InvalidToken, parse_filter_query};
+pub use filter_order::{FilterGroup, FilterSort, order_filter_results};
+
+pub use search::SearchTerm;
+
+// Re-export property types and functions
+pub use properties::{
+    PROPERTY_REGEX, PropertyFilter, PropertyValue, extract_properties, property_value,
+};
+
+// Re-export habit tracking types and functions
+pub use habits::{Habit, OccurrenceMark, collect_habits};
 
 // Re-export project registry types
 pub use project_registry::{
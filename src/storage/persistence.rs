@@ -1,7 +1,7 @@
-use std::collections::HashMap;
-use std::fs;
-use std::io;
-use std::path::Path;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use chrono::NaiveDate;
 
@@ -15,21 +15,16 @@ pub struct DayInfo {
     pub has_recurring: bool,
 }
 
-use super::entries::{EntryType, Line, RawEntry, parse_lines, serialize_lines};
-
-pub fn load_day_lines(date: NaiveDate, path: &Path) -> io::Result<Vec<Line>> {
-    let content = load_day(date, path)?;
-    Ok(parse_lines(&content))
-}
-
-pub fn save_day_lines(date: NaiveDate, path: &Path, lines: &[Line]) -> io::Result<()> {
-    let content = serialize_lines(lines);
-    save_day(date, path, &content)
-}
+use super::entries::{EntryType, Line, RawEntry};
+use super::filter::record_completion;
+use super::journal_cache;
+use super::lock::lock_journal;
+use super::store::{JournalStore, modify_day_lines};
 
 /// Helper to load, mutate an entry, and save in one operation.
 /// Returns the result of the mutation function if the entry exists.
 pub fn mutate_entry<F, R>(
+    store: &dyn JournalStore,
     date: NaiveDate,
     path: &Path,
     line_index: usize,
@@ -38,35 +33,41 @@ pub fn mutate_entry<F, R>(
 where
     F: FnOnce(&mut RawEntry) -> R,
 {
-    let mut lines = load_day_lines(date, path)?;
-    let result = lines.get_mut(line_index).and_then(|line| match line {
-        Line::Entry(entry) => Some(f(entry)),
-        _ => None,
-    });
-    if result.is_some() {
-        save_day_lines(date, path, &lines)?;
-    }
-    Ok(result)
+    modify_day_lines(store, date, path, |lines| {
+        lines.get_mut(line_index).and_then(|line| match line {
+            Line::Entry(entry) => Some(f(entry)),
+            _ => None,
+        })
+    })
 }
 
 /// Updates an entry's content at a specific line index for a given date.
 /// Returns Ok(true) if update succeeded, Ok(false) if no entry at that index.
 pub fn update_entry_content(
+    store: &dyn JournalStore,
     date: NaiveDate,
     path: &Path,
     line_index: usize,
     content: String,
 ) -> io::Result<bool> {
-    mutate_entry(date, path, line_index, |entry| {
+    mutate_entry(store, date, path, line_index, |entry| {
         entry.content = content;
     })
     .map(|opt| opt.is_some())
 }
 
-/// Toggles the completion status of a task at a specific line index.
-pub fn toggle_entry_complete(date: NaiveDate, path: &Path, line_index: usize) -> io::Result<()> {
-    mutate_entry(date, path, line_index, |entry| {
+/// Toggles the completion status of a task at a specific line index,
+/// recording `completed_on` in its metadata when it becomes done.
+pub fn toggle_entry_complete(
+    store: &dyn JournalStore,
+    date: NaiveDate,
+    path: &Path,
+    line_index: usize,
+    completed_on: Option<NaiveDate>,
+) -> io::Result<()> {
+    mutate_entry(store, date, path, line_index, |entry| {
         entry.toggle_complete();
+        entry.content = record_completion(&entry.content, &entry.entry_type, completed_on);
     })?;
     Ok(())
 }
@@ -74,11 +75,12 @@ pub fn toggle_entry_complete(date: NaiveDate, path: &Path, line_index: usize) ->
 /// Cycles the entry type (Task -> Note -> Event -> Task) at a specific line index.
 /// Returns the new entry type if successful.
 pub fn cycle_entry_type(
+    store: &dyn JournalStore,
     date: NaiveDate,
     path: &Path,
     line_index: usize,
 ) -> io::Result<Option<EntryType>> {
-    mutate_entry(date, path, line_index, |entry| {
+    mutate_entry(store, date, path, line_index, |entry| {
         entry.entry_type = entry.entry_type.cycle();
         entry.entry_type.clone()
     })
@@ -87,8 +89,14 @@ pub fn cycle_entry_type(
 /// Gets the entry type at a specific line index for a given date.
 /// Returns the default task type if the entry doesn't exist.
 #[must_use]
-pub fn get_entry_type(date: NaiveDate, path: &Path, line_index: usize) -> EntryType {
-    load_day_lines(date, path)
+pub fn get_entry_type(
+    store: &dyn JournalStore,
+    date: NaiveDate,
+    path: &Path,
+    line_index: usize,
+) -> EntryType {
+    store
+        .load_day_lines(date, path)
         .ok()
         .and_then(|lines| {
             lines.get(line_index).and_then(|line| {
@@ -99,14 +107,19 @@ pub fn get_entry_type(date: NaiveDate, path: &Path, line_index: usize) -> EntryT
                 }
             })
         })
-        .unwrap_or(EntryType::Task { completed: false })
+        .unwrap_or(EntryType::task())
 }
 
 /// Gets the entry content at a specific line index for a given date.
 /// Returns None if the entry doesn't exist.
 #[must_use]
-pub fn get_entry_content(date: NaiveDate, path: &Path, line_index: usize) -> Option<String> {
-    load_day_lines(date, path).ok().and_then(|lines| {
+pub fn get_entry_content(
+    store: &dyn JournalStore,
+    date: NaiveDate,
+    path: &Path,
+    line_index: usize,
+) -> Option<String> {
+    store.load_day_lines(date, path).ok().and_then(|lines| {
         lines.get(line_index).and_then(|line| {
             if let Line::Entry(entry) = line {
                 Some(entry.content.clone())
@@ -118,12 +131,17 @@ pub fn get_entry_content(date: NaiveDate, path: &Path, line_index: usize) -> Opt
 }
 
 /// Deletes an entry at a specific line index for a given date.
-pub fn delete_entry(date: NaiveDate, path: &Path, line_index: usize) -> io::Result<()> {
-    let mut lines = load_day_lines(date, path)?;
-    if line_index < lines.len() {
-        lines.remove(line_index);
-    }
-    save_day_lines(date, path, &lines)
+pub fn delete_entry(
+    store: &dyn JournalStore,
+    date: NaiveDate,
+    path: &Path,
+    line_index: usize,
+) -> io::Result<()> {
+    modify_day_lines(store, date, path, |lines| {
+        if line_index < lines.len() {
+            lines.remove(line_index);
+        }
+    })
 }
 
 fn day_header(date: NaiveDate) -> String {
@@ -138,12 +156,122 @@ pub fn load_journal(path: &Path) -> io::Result<String> {
     }
 }
 
+/// Overwrites the whole journal. Use `modify_journal` when the new content is
+/// derived from the current file, so concurrent writers can't interleave.
 pub fn save_journal(path: &Path, content: &str) -> io::Result<()> {
+    let _lock = lock_journal(path)?;
+    journal_cache::invalidate(path);
+    write_atomic(path, content)
+}
+
+/// Runs a read-modify-write cycle on the journal while holding its lock.
+/// The file is only rewritten if `f` changed the content.
+pub fn modify_journal<F, R>(path: &Path, f: F) -> io::Result<R>
+where
+    F: FnOnce(&mut String) -> R,
+{
+    let _lock = lock_journal(path)?;
+    let original = load_journal(path)?;
+    let mut journal = original.clone();
+    let result = f(&mut journal);
+    if journal != original {
+        journal_cache::invalidate(path);
+        write_atomic(path, &journal)?;
+    }
+    Ok(result)
+}
+
+/// Writes via a temp file in the same directory, fsyncs it, and renames it over
+/// the target, so readers never see a partially written journal. Symlinked
+/// journals are resolved so the link itself isn't replaced.
+pub(super) fn write_atomic(path: &Path, content: &str) -> io::Result<()> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)?;
     }
+    replace_file(&resolve_journal_path(path), content, false)
+}
 
-    fs::write(path, content)
+/// Writes a file only its owner can read, replaced atomically the way
+/// journals are. For caches of data that may be private.
+pub fn write_private_file(path: &Path, content: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    replace_file(path, content, true)
+}
+
+/// Renames a fully written, fsynced temp file over `target`. A private file
+/// is created with mode 0600; otherwise an existing target's permissions
+/// carry over.
+fn replace_file(target: &Path, content: &str, private: bool) -> io::Result<()> {
+    let temp_path = temp_path_for(target);
+
+    let result = (|| {
+        let mut file = create_temp_file(&temp_path, private)?;
+        file.write_all(content.as_bytes())?;
+        if !private && let Ok(metadata) = fs::metadata(target) {
+            file.set_permissions(metadata.permissions())?;
+        }
+        file.sync_all()?;
+        fs::rename(&temp_path, target)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result?;
+
+    #[cfg(unix)]
+    if let Some(parent) = target.parent()
+        && let Ok(dir) = File::open(parent)
+    {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn create_temp_file(path: &Path, private: bool) -> io::Result<File> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    if private {
+        options.mode(0o600);
+    }
+    options.open(path)
+}
+
+#[cfg(not(unix))]
+fn create_temp_file(path: &Path, _private: bool) -> io::Result<File> {
+    File::create(path)
+}
+
+/// The file a journal path really refers to, with symlinks resolved. A file
+/// that doesn't exist yet resolves through its parent directory, so every
+/// path to one journal agrees on where it is written and locked.
+pub(super) fn resolve_journal_path(path: &Path) -> PathBuf {
+    if let Ok(target) = fs::canonicalize(path) {
+        return target;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => fs::canonicalize(parent)
+            .map(|dir| dir.join(name))
+            .unwrap_or_else(|_| path.to_path_buf()),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// A temp file name unique to this process and write, so concurrent writes
+/// of the same file don't share one.
+fn temp_path_for(target: &Path) -> PathBuf {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+
+    let name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let seq = NEXT.fetch_add(1, Ordering::Relaxed);
+    target.with_file_name(format!(".{name}.{}.{seq}.tmp", std::process::id()))
 }
 
 pub fn extract_day_content(journal: &str, date: NaiveDate) -> String {
@@ -305,65 +433,3 @@ pub fn load_day(date: NaiveDate, path: &Path) -> io::Result<String> {
     let journal = load_journal(path)?;
     Ok(extract_day_content(&journal, date))
 }
-
-pub fn save_day(date: NaiveDate, path: &Path, content: &str) -> io::Result<()> {
-    let journal = load_journal(path)?;
-    let updated = update_day_content(&journal, date, content);
-    save_journal(path, &updated)
-}
-
-/// Scans journal for day info within a date range (inclusive).
-/// Returns a map of dates to their content info for calendar display.
-pub fn scan_days_in_range(
-    start: NaiveDate,
-    end: NaiveDate,
-    path: &Path,
-) -> io::Result<HashMap<NaiveDate, DayInfo>> {
-    let journal = load_journal(path)?;
-    let mut result = HashMap::new();
-    let mut current_date: Option<NaiveDate> = None;
-    let mut current_info = DayInfo::default();
-
-    for line in journal.lines() {
-        if let Some(date) = parse_day_header(line) {
-            // Save previous day if in range
-            if let Some(prev_date) = current_date
-                && prev_date >= start
-                && prev_date <= end
-                && current_info.has_entries
-            {
-                result.insert(prev_date, current_info);
-            }
-            current_date = Some(date);
-            current_info = DayInfo::default();
-            continue;
-        }
-
-        if current_date.is_some() {
-            let trimmed = line.trim_start();
-            if trimmed.starts_with("- [ ] ") {
-                current_info.has_entries = true;
-                current_info.has_incomplete_tasks = true;
-            } else if trimmed.starts_with("* ") {
-                current_info.has_entries = true;
-                current_info.has_events = true;
-            } else if trimmed.starts_with("- [x] ")
-                || trimmed.starts_with("- [X] ")
-                || (trimmed.starts_with("- ") && !trimmed.starts_with("- ["))
-            {
-                current_info.has_entries = true;
-            }
-        }
-    }
-
-    // Save last day if in range
-    if let Some(date) = current_date
-        && date >= start
-        && date <= end
-        && current_info.has_entries
-    {
-        result.insert(date, current_info);
-    }
-
-    Ok(result)
-}
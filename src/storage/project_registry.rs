@@ -227,10 +227,11 @@ fn resolve_project_info(
     let root = caliber_path.parent()?;
 
     // Check config for custom journal location
-    let journal_path = Config::load_merged_from(root)
-        .map(|c| c.config.get_project_journal_path(root))
-        .unwrap_or_else(|_| caliber_path.join("journal.md"));
-    let available = journal_path.exists();
+    let config = Config::load_merged_from(root)
+        .map(|c| c.config)
+        .unwrap_or_default();
+    let journal_path = config.get_project_journal_path(root);
+    let available = config.journal_store().journal_exists(&journal_path);
 
     let (name, id) = derive_identity(root);
     let hide_from_registry = load_hide_from_registry(&caliber_path.join("config.toml"));
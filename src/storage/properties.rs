@@ -0,0 +1,231 @@
+use std::cmp::Ordering;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use super::search::SearchTerm;
+
+/// A property key: a letter, then letters, digits, `_` or `-`
+pub(super) const PROPERTY_KEY_PATTERN: &str = r"\p{L}[\p{L}\p{N}_-]*";
+
+/// A property value: starts and ends with a letter or digit, so `est:2h,`
+/// stops before the comma. URLs (`https://…`) and times (`10:30`) don't fit
+/// the grammar and stay plain text.
+pub(super) const PROPERTY_VALUE_PATTERN: &str = r"[\p{L}\p{N}](?:\S*[\p{L}\p{N}%])?";
+
+/// Matches `key:value` properties at the start of the text or after
+/// whitespace. Group 1 is the key and group 2 the value.
+pub static PROPERTY_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?:^|\s)({PROPERTY_KEY_PATTERN}):({PROPERTY_VALUE_PATTERN})"
+    ))
+    .unwrap()
+});
+
+static PROPERTY_KEY_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!("^{PROPERTY_KEY_PATTERN}$")).unwrap());
+
+static DURATION_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:\d+(?:\.\d+)?[wdhm])+$").unwrap());
+
+static DURATION_PART_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\d+(?:\.\d+)?)([wdhm])").unwrap());
+
+#[must_use]
+pub(super) fn is_property_key(key: &str) -> bool {
+    PROPERTY_KEY_REGEX.is_match(key)
+}
+
+/// Byte range of each property in `content`, without the whitespace before it.
+#[must_use]
+pub fn find_properties(content: &str) -> Vec<(usize, usize)> {
+    PROPERTY_REGEX
+        .captures_iter(content)
+        .filter_map(|cap| Some((cap.get(1)?.start(), cap.get(2)?.end())))
+        .collect()
+}
+
+/// Properties of an entry as (key, value) pairs, keys lowercased, in the
+/// order they're written.
+#[must_use]
+pub fn extract_properties(content: &str) -> Vec<(String, String)> {
+    PROPERTY_REGEX
+        .captures_iter(content)
+        .map(|cap| (cap[1].to_lowercase(), cap[2].to_string()))
+        .collect()
+}
+
+/// A property value as compared by filters and sorting. Durations (`90m`,
+/// `1h30m`, `2d`) and numbers compare by size; anything else as text,
+/// ignoring case.
+#[derive(Debug, Clone)]
+pub enum PropertyValue {
+    /// Minutes, with `1d` = 24h and `1w` = 7d
+    Duration(f64),
+    Number(f64),
+    Text(String),
+}
+
+impl PropertyValue {
+    #[must_use]
+    pub fn parse(value: &str) -> Self {
+        if DURATION_REGEX.is_match(value) {
+            let minutes = DURATION_PART_REGEX
+                .captures_iter(value)
+                .map(|cap| {
+                    let amount: f64 = cap[1].parse().unwrap_or_default();
+                    let scale = match &cap[2] {
+                        "w" => 7.0 * 24.0 * 60.0,
+                        "d" => 24.0 * 60.0,
+                        "h" => 60.0,
+                        _ => 1.0,
+                    };
+                    amount * scale
+                })
+                .sum();
+            return Self::Duration(minutes);
+        }
+        match value.parse::<f64>() {
+            Ok(number) if number.is_finite() => Self::Number(number),
+            _ => Self::Text(value.to_lowercase()),
+        }
+    }
+
+    fn rank(&self) -> usize {
+        match self {
+            Self::Duration(_) => 0,
+            Self::Number(_) => 1,
+            Self::Text(_) => 2,
+        }
+    }
+
+    /// Orders values of the same kind; values of different kinds don't compare.
+    fn compare(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Self::Duration(a), Self::Duration(b)) | (Self::Number(a), Self::Number(b)) => {
+                Some(a.total_cmp(b))
+            }
+            (Self::Text(a), Self::Text(b)) => Some(a.cmp(b)),
+            _ => None,
+        }
+    }
+}
+
+/// Total order for sorting: durations, then numbers, then text.
+impl Ord for PropertyValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.compare(other)
+            .unwrap_or_else(|| self.rank().cmp(&other.rank()))
+    }
+}
+
+impl PartialOrd for PropertyValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for PropertyValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PropertyValue {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Eq,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+/// A property filter term: `client:acme`, `est:>1h`, `pr:<=1200`, or
+/// `client:*` for entries with any value.
+#[derive(Debug, Clone)]
+pub struct PropertyFilter {
+    key: String,
+    /// None matches any value
+    condition: Option<(Comparison, PropertyValue)>,
+    /// A plain `key:value` also matches as text, so `todo:lat` still finds
+    /// `todo:later` the way any other word would
+    literal: Option<SearchTerm>,
+}
+
+impl PropertyFilter {
+    /// Parses a `key:value` filter term, or None if the token isn't one.
+    #[must_use]
+    pub fn parse(token: &str) -> Option<Self> {
+        let (key, value) = token.split_once(':')?;
+        if !is_property_key(key) {
+            return None;
+        }
+        if value == "*" {
+            return Some(Self {
+                key: key.to_lowercase(),
+                condition: None,
+                literal: None,
+            });
+        }
+        let (comparison, value) = [
+            (">=", Comparison::Ge),
+            ("<=", Comparison::Le),
+            (">", Comparison::Gt),
+            ("<", Comparison::Lt),
+        ]
+        .iter()
+        .find_map(|(op, comparison)| value.strip_prefix(op).map(|rest| (*comparison, rest)))
+        .unwrap_or((Comparison::Eq, value));
+        if !value.starts_with(|c: char| c.is_alphanumeric()) {
+            return None;
+        }
+        Some(Self {
+            key: key.to_lowercase(),
+            condition: Some((comparison, PropertyValue::parse(value))),
+            literal: (comparison == Comparison::Eq).then(|| SearchTerm::literal(token)),
+        })
+    }
+
+    /// Whether any of an entry's properties satisfies the term, or for a plain
+    /// `key:value`, whether the entry's text contains it.
+    #[must_use]
+    pub fn matches(&self, text: &str, properties: &[(String, String)]) -> bool {
+        if self
+            .literal
+            .as_ref()
+            .is_some_and(|term| term.is_match(text))
+        {
+            return true;
+        }
+        properties
+            .iter()
+            .filter(|(key, _)| *key == self.key)
+            .any(|(_, value)| {
+                let Some((comparison, wanted)) = &self.condition else {
+                    return true;
+                };
+                let Some(ordering) = PropertyValue::parse(value).compare(wanted) else {
+                    return false;
+                };
+                match comparison {
+                    Comparison::Eq => ordering.is_eq(),
+                    Comparison::Gt => ordering.is_gt(),
+                    Comparison::Ge => ordering.is_ge(),
+                    Comparison::Lt => ordering.is_lt(),
+                    Comparison::Le => ordering.is_le(),
+                }
+            })
+    }
+}
+
+/// Value of the first `key` property in `content`, for sorting. `key` is
+/// lowercase.
+#[must_use]
+pub fn property_value(content: &str, key: &str) -> Option<PropertyValue> {
+    PROPERTY_REGEX
+        .captures_iter(content)
+        .find(|cap| cap[1].to_lowercase() == key)
+        .map(|cap| PropertyValue::parse(&cap[2]))
+}
@@ -0,0 +1,141 @@
+use std::ops::Range;
+
+use regex::Regex;
+
+/// A text term of a filter query, matched against entry content.
+#[derive(Debug, Clone)]
+pub enum SearchTerm {
+    /// A word or `"quoted phrase"` (case-insensitive), or `re:/pattern/`
+    Pattern(Regex),
+    /// `~term`: the term's characters in order, with anything between them
+    Fuzzy(String),
+}
+
+impl SearchTerm {
+    /// Case-insensitive match of the exact text.
+    #[must_use]
+    pub fn literal(text: &str) -> Self {
+        let regex = Regex::new(&format!("(?i){}", regex::escape(text)))
+            .expect("escaped text is a valid pattern");
+        Self::Pattern(regex)
+    }
+
+    pub fn regex(pattern: &str) -> Result<Self, regex::Error> {
+        Regex::new(pattern).map(Self::Pattern)
+    }
+
+    #[must_use]
+    pub fn is_match(&self, text: &str) -> bool {
+        match self {
+            Self::Pattern(regex) => regex.is_match(text),
+            Self::Fuzzy(term) => fuzzy_match(term, text).is_some(),
+        }
+    }
+
+    /// Byte ranges of `text` the term matched, for highlighting.
+    #[must_use]
+    pub fn find_ranges(&self, text: &str) -> Vec<Range<usize>> {
+        match self {
+            Self::Pattern(regex) => regex
+                .find_iter(text)
+                .filter(|m| !m.is_empty())
+                .map(|m| m.range())
+                .collect(),
+            Self::Fuzzy(term) => fuzzy_match(term, text)
+                .map(|found| found.ranges)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// How well a fuzzy term matches, higher being better. Other terms
+    /// either match or don't, so they score nothing.
+    #[must_use]
+    pub fn score(&self, text: &str) -> u32 {
+        match self {
+            Self::Pattern(_) => 0,
+            Self::Fuzzy(term) => fuzzy_match(term, text).map_or(0, |found| found.score),
+        }
+    }
+}
+
+struct FuzzyMatch {
+    score: u32,
+    ranges: Vec<Range<usize>>,
+}
+
+/// Points for each matched character, with bonuses for runs of adjacent
+/// characters and for characters that start a word
+const CHAR_SCORE: u32 = 1;
+const RUN_BONUS: u32 = 4;
+const WORD_START_BONUS: u32 = 3;
+
+/// Finds `term`'s characters in order in `text`, ignoring case. Tries each
+/// place the first character occurs and keeps the best scoring alignment.
+fn fuzzy_match(term: &str, text: &str) -> Option<FuzzyMatch> {
+    let needle: Vec<char> = term.chars().flat_map(char::to_lowercase).collect();
+    if needle.is_empty() {
+        return None;
+    }
+    // Lowercased the same way as the needle; a character whose lowercase is
+    // several characters keeps its byte offset for each of them
+    let haystack: Vec<(usize, char)> = text
+        .char_indices()
+        .flat_map(|(i, c)| c.to_lowercase().map(move |lower| (i, lower)))
+        .collect();
+
+    let mut best: Option<FuzzyMatch> = None;
+    for start in 0..haystack.len() {
+        if haystack[start].1 != needle[0] {
+            continue;
+        }
+        let Some(found) = align_from(&needle, &haystack, start, text) else {
+            // Later starts can only find fewer characters
+            break;
+        };
+        if best.as_ref().is_none_or(|b| found.score > b.score) {
+            best = Some(found);
+        }
+    }
+    best
+}
+
+/// Matches `needle` from `start`, taking the earliest occurrence of each
+/// character after the previous one.
+fn align_from(
+    needle: &[char],
+    haystack: &[(usize, char)],
+    start: usize,
+    text: &str,
+) -> Option<FuzzyMatch> {
+    let mut score = 0;
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    let mut previous: Option<usize> = None;
+    let mut pos = start;
+
+    for &wanted in needle {
+        let found = (pos..haystack.len()).find(|&i| haystack[i].1 == wanted)?;
+        let (byte, c) = haystack[found];
+        let end = byte
+            + text[byte..]
+                .chars()
+                .next()
+                .map_or(c.len_utf8(), char::len_utf8);
+
+        score += CHAR_SCORE;
+        let word_start = found == 0 || !haystack[found - 1].1.is_alphanumeric();
+        if word_start {
+            score += WORD_START_BONUS;
+        }
+        match ranges.last_mut() {
+            Some(range) if previous == Some(found.wrapping_sub(1)) => {
+                score += RUN_BONUS;
+                range.end = end;
+            }
+            _ => ranges.push(byte..end),
+        }
+        previous = Some(found);
+        pos = found + 1;
+    }
+
+    Some(FuzzyMatch { score, ranges })
+}
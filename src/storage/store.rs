@@ -0,0 +1,385 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use std::time::SystemTime;
+
+use chrono::NaiveDate;
+
+use super::entries::{EntryType, Line, serialize_lines};
+use super::journal_cache::{self, index_days, parse_section};
+use super::persistence::DayInfo;
+
+/// One day's lines as stored in a journal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JournalDay {
+    pub date: NaiveDate,
+    pub lines: Vec<Line>,
+}
+
+/// Backend that persists journals. A journal is identified by its path, which each
+/// backend interprets in its own way (a markdown file, a key in memory, ...).
+///
+/// `modify_day` and `modify_days` must be atomic with respect to other writers.
+pub trait JournalStore: Send + Sync {
+    /// Loads one day's lines; a day that doesn't exist has no lines.
+    fn load_day_lines(&self, date: NaiveDate, path: &Path) -> io::Result<Vec<Line>>;
+
+    /// Loads a day, applies `f`, and stores the result if it changed.
+    /// Days left without content are removed.
+    fn modify_day(
+        &self,
+        date: NaiveDate,
+        path: &Path,
+        f: &mut dyn FnMut(&mut Vec<Line>),
+    ) -> io::Result<()>;
+
+    /// Whether the journal has been created.
+    fn journal_exists(&self, path: &Path) -> bool;
+
+    /// Creates an empty journal if it doesn't exist yet.
+    fn create_journal(&self, path: &Path) -> io::Result<()>;
+
+    /// When the storage holding `date` last changed, for detecting edits made
+    /// outside caliber. None if the backend can't tell.
+    fn modified(&self, _date: NaiveDate, _path: &Path) -> Option<SystemTime> {
+        None
+    }
+
+    /// Every day in the journal, in storage order.
+    fn days(&self, path: &Path) -> io::Result<Vec<JournalDay>>;
+
+    /// Calls `f` with every day in storage order. Backends that keep parsed days
+    /// around override this to avoid copying them, so `f` must not call back into
+    /// the store.
+    fn visit_days(&self, path: &Path, f: &mut dyn FnMut(NaiveDate, &[Line])) -> io::Result<()> {
+        for day in self.days(path)? {
+            f(day.date, &day.lines);
+        }
+        Ok(())
+    }
+
+    /// Applies `f` to every day in one atomic update.
+    fn modify_days(
+        &self,
+        path: &Path,
+        f: &mut dyn FnMut(NaiveDate, &mut Vec<Line>),
+    ) -> io::Result<()>;
+
+    fn save_day_lines(&self, date: NaiveDate, path: &Path, lines: &[Line]) -> io::Result<()> {
+        self.modify_day(date, path, &mut |day| *day = lines.to_vec())
+    }
+
+    /// Summarizes days within a date range (inclusive) for calendar display.
+    fn scan_days_in_range(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        path: &Path,
+    ) -> io::Result<HashMap<NaiveDate, DayInfo>> {
+        let mut result = HashMap::new();
+        self.visit_days(path, &mut |date, lines| {
+            if date < start || date > end {
+                return;
+            }
+            let info = DayInfo::from_lines(lines);
+            if info.has_entries {
+                result.insert(date, info);
+            }
+        })?;
+        Ok(result)
+    }
+}
+
+impl DayInfo {
+    #[must_use]
+    pub fn from_lines(lines: &[Line]) -> Self {
+        let mut info = Self::default();
+        for line in lines {
+            if let Line::Entry(entry) = line {
+                info.has_entries = true;
+                match entry.entry_type {
+                    EntryType::Task { state } if !state.is_closed() => {
+                        info.has_incomplete_tasks = true;
+                    }
+                    EntryType::Event => info.has_events = true,
+                    _ => {}
+                }
+            }
+        }
+        info
+    }
+}
+
+/// Loads a day, applies `f`, and stores the result if it changed, returning `f`'s result.
+pub fn modify_day_lines<F, R>(
+    store: &dyn JournalStore,
+    date: NaiveDate,
+    path: &Path,
+    f: F,
+) -> io::Result<R>
+where
+    F: FnOnce(&mut Vec<Line>) -> R,
+{
+    let mut f = Some(f);
+    let mut result = None;
+    store.modify_day(date, path, &mut |lines| {
+        if let Some(f) = f.take() {
+            result = Some(f(lines));
+        }
+    })?;
+    result.ok_or_else(|| io::Error::other("Journal store skipped the update"))
+}
+
+/// Copies every day of a journal from one store to another, returning the number
+/// of days copied. Repeated sections for one date are joined in file order so
+/// none is lost. Refuses to write into a journal that already has days.
+pub fn copy_journal(
+    from: &dyn JournalStore,
+    to: &dyn JournalStore,
+    path: &Path,
+) -> io::Result<usize> {
+    if !to.days(path)?.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "Destination journal already has entries",
+        ));
+    }
+    let mut merged: Vec<(NaiveDate, Vec<Line>)> = Vec::new();
+    let mut index: HashMap<NaiveDate, usize> = HashMap::new();
+    for day in from.days(path)? {
+        match index.get(&day.date) {
+            Some(&i) => merged[i].1.extend(day.lines),
+            None => {
+                index.insert(day.date, merged.len());
+                merged.push((day.date, day.lines));
+            }
+        }
+    }
+    for (date, lines) in &merged {
+        to.save_day_lines(*date, path, lines)?;
+    }
+    Ok(merged.len())
+}
+
+/// Splits a markdown journal into its day sections, in file order.
+/// Text before the first day header is ignored.
+#[must_use]
+pub fn parse_journal_days(journal: &str) -> Vec<JournalDay> {
+    index_days(journal)
+        .into_iter()
+        .map(|(date, content)| JournalDay {
+            date,
+            lines: parse_section(&journal[content]),
+        })
+        .collect()
+}
+
+/// The default store: each journal is a single markdown file with `# YYYY/MM/DD` headers.
+/// Parsed journals are cached per process and re-read only when the file's
+/// modification time or size changes; writes re-parse only the days they touch.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MarkdownStore;
+
+impl JournalStore for MarkdownStore {
+    fn load_day_lines(&self, date: NaiveDate, path: &Path) -> io::Result<Vec<Line>> {
+        journal_cache::with_journal(path, |journal| {
+            journal
+                .day(date)
+                .map(|day| day.lines.clone())
+                .unwrap_or_default()
+        })
+    }
+
+    fn modify_day(
+        &self,
+        date: NaiveDate,
+        path: &Path,
+        f: &mut dyn FnMut(&mut Vec<Line>),
+    ) -> io::Result<()> {
+        journal_cache::modify_days(path, Some(date), &mut |_, lines| f(lines))
+    }
+
+    fn journal_exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn create_journal(&self, path: &Path) -> io::Result<()> {
+        if path.exists() {
+            return Ok(());
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, "")
+    }
+
+    fn modified(&self, _date: NaiveDate, path: &Path) -> Option<SystemTime> {
+        std::fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+
+    fn days(&self, path: &Path) -> io::Result<Vec<JournalDay>> {
+        journal_cache::with_journal(path, |journal| {
+            journal
+                .days
+                .iter()
+                .map(|day| JournalDay {
+                    date: day.date,
+                    lines: day.lines.clone(),
+                })
+                .collect()
+        })
+    }
+
+    fn visit_days(&self, path: &Path, f: &mut dyn FnMut(NaiveDate, &[Line])) -> io::Result<()> {
+        journal_cache::with_journal(path, |journal| {
+            for day in &journal.days {
+                f(day.date, &day.lines);
+            }
+        })
+    }
+
+    fn modify_days(
+        &self,
+        path: &Path,
+        f: &mut dyn FnMut(NaiveDate, &mut Vec<Line>),
+    ) -> io::Result<()> {
+        journal_cache::modify_days(path, None, f)
+    }
+}
+
+/// Keeps journals in memory, keyed by path. Useful for tests and for tools that
+/// embed caliber without touching the filesystem.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    journals: Mutex<HashMap<PathBuf, BTreeMap<NaiveDate, Vec<Line>>>>,
+}
+
+impl MemoryStore {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces a journal with the days parsed from markdown text.
+    pub fn insert_markdown(&self, path: &Path, markdown: &str) {
+        let days = parse_journal_days(markdown)
+            .into_iter()
+            .filter(|day| !is_empty_day(&day.lines))
+            .map(|day| (day.date, day.lines))
+            .collect();
+        self.lock().insert(path.to_path_buf(), days);
+    }
+
+    /// Renders a journal as markdown, in the same layout `MarkdownStore` writes.
+    #[must_use]
+    pub fn to_markdown(&self, path: &Path) -> String {
+        let journals = self.lock();
+        let Some(days) = journals.get(path) else {
+            return String::new();
+        };
+        days.iter()
+            .map(|(date, lines)| {
+                format!(
+                    "# {}\n{}\n",
+                    date.format("%Y/%m/%d"),
+                    serialize_lines(lines)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<PathBuf, BTreeMap<NaiveDate, Vec<Line>>>> {
+        self.journals.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+pub(super) fn is_empty_day(lines: &[Line]) -> bool {
+    serialize_lines(lines).trim().is_empty()
+}
+
+/// Lines as they read back after being stored: trailing blank lines are dropped.
+#[must_use]
+pub fn normalize_day_lines(lines: &[Line]) -> Vec<Line> {
+    parse_section(&serialize_lines(lines))
+}
+
+impl JournalStore for MemoryStore {
+    fn load_day_lines(&self, date: NaiveDate, path: &Path) -> io::Result<Vec<Line>> {
+        Ok(self
+            .lock()
+            .get(path)
+            .and_then(|days| days.get(&date))
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    fn modify_day(
+        &self,
+        date: NaiveDate,
+        path: &Path,
+        f: &mut dyn FnMut(&mut Vec<Line>),
+    ) -> io::Result<()> {
+        let mut journals = self.lock();
+        let days = journals.entry(path.to_path_buf()).or_default();
+        let mut lines = days.get(&date).cloned().unwrap_or_default();
+        f(&mut lines);
+        if is_empty_day(&lines) {
+            days.remove(&date);
+        } else {
+            days.insert(date, normalize_day_lines(&lines));
+        }
+        Ok(())
+    }
+
+    fn journal_exists(&self, path: &Path) -> bool {
+        self.lock().contains_key(path)
+    }
+
+    fn create_journal(&self, path: &Path) -> io::Result<()> {
+        self.lock().entry(path.to_path_buf()).or_default();
+        Ok(())
+    }
+
+    fn days(&self, path: &Path) -> io::Result<Vec<JournalDay>> {
+        Ok(self
+            .lock()
+            .get(path)
+            .map(|days| {
+                days.iter()
+                    .map(|(date, lines)| JournalDay {
+                        date: *date,
+                        lines: lines.clone(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    fn visit_days(&self, path: &Path, f: &mut dyn FnMut(NaiveDate, &[Line])) -> io::Result<()> {
+        if let Some(days) = self.lock().get(path) {
+            for (date, lines) in days {
+                f(*date, lines);
+            }
+        }
+        Ok(())
+    }
+
+    fn modify_days(
+        &self,
+        path: &Path,
+        f: &mut dyn FnMut(NaiveDate, &mut Vec<Line>),
+    ) -> io::Result<()> {
+        let mut journals = self.lock();
+        let Some(days) = journals.get_mut(path) else {
+            return Ok(());
+        };
+        for (date, lines) in days.iter_mut() {
+            f(*date, lines);
+            *lines = normalize_day_lines(lines);
+        }
+        days.retain(|_, lines| !is_empty_day(lines));
+        Ok(())
+    }
+}
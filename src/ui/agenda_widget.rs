@@ -6,7 +6,7 @@ use ratatui::text::{Line as RatatuiLine, Span};
 use unicode_width::UnicodeWidthStr;
 
 use crate::calendar::CalendarStore;
-use crate::storage::{self, EntryType, SourceType};
+use crate::storage::{self, EntryType, JournalStore, SourceType};
 
 use super::shared::truncate_text;
 use super::theme;
@@ -156,7 +156,11 @@ fn fit_entries_to_height(
     result
 }
 
-pub fn collect_agenda_cache(calendar_store: &CalendarStore, path: &Path) -> AgendaCache {
+pub fn collect_agenda_cache(
+    store: &dyn JournalStore,
+    calendar_store: &CalendarStore,
+    path: &Path,
+) -> AgendaCache {
     let today = Local::now().date_naive();
     let mut days = Vec::new();
     let mut max_width = theme::AGENDA_DATE_WIDTH;
@@ -195,7 +199,7 @@ pub fn collect_agenda_cache(calendar_store: &CalendarStore, path: &Path) -> Agen
             });
         }
 
-        if let Ok(projected) = storage::collect_projected_entries_for_date(date, path) {
+        if let Ok(projected) = storage::collect_projected_entries_for_date(store, date, path) {
             for entry in projected
                 .iter()
                 .filter(|e| e.entry_type == EntryType::Event)
@@ -215,7 +219,7 @@ pub fn collect_agenda_cache(calendar_store: &CalendarStore, path: &Path) -> Agen
             }
         }
 
-        if let Ok(day_lines) = storage::load_day_lines(date, path) {
+        if let Ok(day_lines) = store.load_day_lines(date, path) {
             for line in &day_lines {
                 if let storage::Line::Entry(raw) = line {
                     if raw.entry_type != EntryType::Event {
@@ -2,7 +2,7 @@ use ratatui::style::{Style, Stylize};
 use unicode_width::UnicodeWidthStr;
 
 use crate::app::{App, EditContext, InputMode, ViewMode};
-use crate::storage::{EntryType, Line};
+use crate::storage::{INDENT, Line};
 
 use super::helpers::edit_text;
 use super::model::ListModel;
@@ -45,10 +45,7 @@ pub fn build_daily_list(app: &App, width: usize) -> ListModel {
     let mut visible_projected_idx = 0;
 
     for projected_entry in &state.projected_entries {
-        let is_completed = matches!(
-            projected_entry.entry_type,
-            EntryType::Task { completed: true }
-        );
+        let is_completed = projected_entry.entry_type.is_closed_task();
         if app.hide_completed && is_completed {
             continue;
         }
@@ -69,9 +66,7 @@ pub fn build_daily_list(app: &App, width: usize) -> ListModel {
     let mut visible_entry_idx = 0;
     for &line_idx in &app.entry_indices {
         if let Line::Entry(entry) = &app.lines[line_idx] {
-            let is_completed = matches!(entry.entry_type, EntryType::Task { completed: true });
-
-            if app.hide_completed && is_completed {
+            if !app.is_daily_line_visible(line_idx) {
                 continue;
             }
 
@@ -85,13 +80,13 @@ pub fn build_daily_list(app: &App, width: usize) -> ListModel {
 
             let text = edit_text(app, is_editing, &entry.content);
 
-            let prefix = entry.prefix();
+            let prefix = format!("{}{}", INDENT.repeat(entry.depth), entry.prefix());
             let prefix_width = prefix.width();
 
             if is_editing {
                 let text_width = width.saturating_sub(prefix_width);
                 rows.extend(build_edit_rows_with_prefix_width(
-                    prefix,
+                    &prefix,
                     prefix_width,
                     content_style,
                     &text,
@@ -102,6 +97,7 @@ pub fn build_daily_list(app: &App, width: usize) -> ListModel {
                 rows.push(rows::build_daily_entry_row(
                     app,
                     entry,
+                    line_idx,
                     is_selected,
                     selection_idx,
                     width,
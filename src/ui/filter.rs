@@ -4,13 +4,14 @@ use ratatui::{
 };
 use unicode_width::UnicodeWidthStr;
 
-use crate::app::{App, EditContext, InputMode, ViewMode};
+use crate::app::{App, EditContext, FilterRow, InputMode, ViewMode};
+use crate::storage::{Entry, INDENT};
 
 use super::helpers::edit_text;
 use super::model::ListModel;
 use super::rows;
 use super::rows::build_edit_rows_with_prefix_width;
-use super::shared::{date_suffix_style, entry_style, format_date_suffix};
+use super::shared::{date_suffix_style, entry_style, format_filter_suffix};
 
 pub fn build_filter_list(app: &App, width: usize) -> ListModel {
     let ViewMode::Filter(state) = &app.view else {
@@ -28,7 +29,30 @@ pub fn build_filter_list(app: &App, width: usize) -> ListModel {
         InputMode::Edit(EditContext::FilterEdit { .. })
     );
 
-    for (idx, filter_entry) in state.entries.iter().enumerate() {
+    let depths = tree_depths(&state.entries);
+    for row in state.rows() {
+        let idx = match row {
+            FilterRow::Entry(idx) => idx,
+            FilterRow::Header {
+                label,
+                first,
+                count,
+                collapsed,
+            } => {
+                let is_selected = collapsed
+                    && (first..first + count).contains(&state.selected)
+                    && !is_quick_adding;
+                rows.push(rows::build_group_header_row(
+                    app,
+                    &label,
+                    count,
+                    collapsed,
+                    is_selected,
+                ));
+                continue;
+            }
+        };
+        let filter_entry = &state.entries[idx];
         let is_selected = idx == state.selected && !is_quick_adding;
         let is_editing_this = is_selected && is_editing;
 
@@ -36,15 +60,20 @@ pub fn build_filter_list(app: &App, width: usize) -> ListModel {
 
         let text = edit_text(app, is_editing_this, &filter_entry.content);
 
-        let prefix = filter_entry.entry_type.prefix();
+        let depth = depths[idx];
+        let prefix = format!(
+            "{}{}",
+            INDENT.repeat(depth),
+            filter_entry.entry_type.prefix()
+        );
         let prefix_width = prefix.width();
 
         if is_selected {
             if is_editing_this {
-                let (date_suffix, date_suffix_width) = format_date_suffix(filter_entry.source_date);
+                let (date_suffix, date_suffix_width) = format_filter_suffix(filter_entry);
                 let text_width = width.saturating_sub(prefix_width + date_suffix_width);
                 rows.extend(build_edit_rows_with_prefix_width(
-                    prefix,
+                    &prefix,
                     prefix_width,
                     content_style,
                     &text,
@@ -56,11 +85,12 @@ pub fn build_filter_list(app: &App, width: usize) -> ListModel {
                     app,
                     filter_entry,
                     idx,
+                    depth,
                     width,
                 ));
             }
         } else {
-            rows.push(rows::build_filter_row(app, filter_entry, idx, width));
+            rows.push(rows::build_filter_row(app, filter_entry, idx, depth, width));
         }
     }
 
@@ -90,3 +120,31 @@ pub fn build_filter_list(app: &App, width: usize) -> ListModel {
 
     ListModel::from_rows(None, rows, app.scroll_offset())
 }
+
+/// Display depth of each filter result. A result is nested beneath the
+/// shallower results directly above it from the same day, so a match whose
+/// parents were all filtered out lines up at the top level.
+#[must_use]
+pub fn tree_depths(entries: &[Entry]) -> Vec<usize> {
+    let mut depths = Vec::with_capacity(entries.len());
+    let mut ancestors: Vec<usize> = Vec::new();
+    let mut previous: Option<&Entry> = None;
+
+    for entry in entries {
+        let continues_day = previous.is_some_and(|p| {
+            p.source_date == entry.source_date
+                && p.journal == entry.journal
+                && p.line_index < entry.line_index
+        });
+        if !continues_day {
+            ancestors.clear();
+        }
+        while ancestors.last().is_some_and(|&depth| depth >= entry.depth) {
+            ancestors.pop();
+        }
+        depths.push(ancestors.len());
+        ancestors.push(entry.depth);
+        previous = Some(entry);
+    }
+    depths
+}
@@ -66,7 +66,8 @@ impl FooterMode {
                 CommandPaletteMode::Tags => FooterMode::CommandPaletteTags,
             },
             InputMode::FilterPrompt => FooterMode::FilterPrompt,
-            InputMode::Confirm(_) | InputMode::DatePicker(_) => match view {
+            InputMode::Confirm(_) | InputMode::Conflict(_) | InputMode::DatePicker(_) => match view
+            {
                 ViewMode::Daily(_) => FooterMode::Daily,
                 ViewMode::Filter(_) => FooterMode::Filter,
             },
@@ -0,0 +1,94 @@
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line as RatatuiLine, Span};
+
+use crate::storage::{Habit, OccurrenceMark};
+
+use super::shared::truncate_text;
+use super::theme;
+
+/// Lines for one habit: its content, a grid of recent occurrences with the
+/// completion rate, and current/best streaks.
+const LINES_PER_HABIT: usize = 3;
+
+fn mark_span(mark: OccurrenceMark) -> Span<'static> {
+    let (glyph, style) = match mark {
+        OccurrenceMark::Done => (
+            theme::GLYPH_HABIT_DONE,
+            Style::default().fg(theme::HABIT_DONE),
+        ),
+        OccurrenceMark::Missed => (
+            theme::GLYPH_HABIT_MISSED,
+            Style::default().fg(theme::HABIT_MISSED),
+        ),
+        OccurrenceMark::Skipped => (
+            theme::GLYPH_HABIT_SKIPPED,
+            Style::default().add_modifier(Modifier::DIM),
+        ),
+        OccurrenceMark::Pending => (
+            theme::GLYPH_HABIT_PENDING,
+            Style::default().add_modifier(Modifier::DIM),
+        ),
+    };
+    Span::styled(glyph.to_string(), style)
+}
+
+/// Builds habit tracker lines that fit within `width` columns and `height` rows.
+#[must_use]
+pub fn build_habit_lines(
+    habits: &[Habit],
+    width: usize,
+    height: usize,
+) -> Vec<RatatuiLine<'static>> {
+    let dim = Style::default().add_modifier(Modifier::DIM);
+    if habits.is_empty() {
+        return vec![RatatuiLine::from(Span::styled(
+            truncate_text(" No recurring tasks", width),
+            dim,
+        ))];
+    }
+
+    let grid_length = theme::HABIT_GRID_LENGTH.min(width.saturating_sub(7));
+    let mut lines = Vec::new();
+
+    for (i, habit) in habits.iter().enumerate() {
+        let spacing = usize::from(i > 0);
+        if lines.len() + spacing + LINES_PER_HABIT > height {
+            break;
+        }
+        if spacing > 0 {
+            lines.push(RatatuiLine::from(""));
+        }
+
+        lines.push(RatatuiLine::from(format!(
+            " {}",
+            truncate_text(&habit.content, width.saturating_sub(1))
+        )));
+
+        let mut grid = vec![Span::raw(" ")];
+        grid.extend(
+            habit
+                .recent(grid_length)
+                .iter()
+                .map(|(_, mark)| mark_span(*mark)),
+        );
+        let percent = habit
+            .completion_percent()
+            .map_or_else(|| "  –".to_string(), |p| format!("{p:>3}%"));
+        grid.push(Span::styled(format!("  {percent}"), dim));
+        lines.push(RatatuiLine::from(grid));
+
+        lines.push(RatatuiLine::from(Span::styled(
+            truncate_text(
+                &format!(
+                    " streak {}, best {}",
+                    habit.current_streak(),
+                    habit.best_streak()
+                ),
+                width,
+            ),
+            dim,
+        )));
+    }
+
+    lines
+}
@@ -6,6 +6,7 @@ mod context;
 mod daily;
 mod filter;
 mod footer;
+mod habit_widget;
 mod header;
 mod help;
 mod helpers;
@@ -31,7 +31,9 @@ pub struct PaletteProject {
 }
 
 pub struct PaletteTag {
+    /// Last segment of the tag path
     pub name: String,
+    pub depth: usize,
     pub count: usize,
 }
 
@@ -60,7 +62,8 @@ impl CommandPaletteModel {
         let tags = tags
             .iter()
             .map(|t| PaletteTag {
-                name: t.name.clone(),
+                name: t.leaf().to_string(),
+                depth: t.depth(),
                 count: t.count,
             })
             .collect();
@@ -113,8 +116,8 @@ fn build_palette_item_line(
 ) -> RatatuiLine<'static> {
     let (name_style, desc_style) = item_styles(item.is_selected, item.is_available, bg, muted);
     let available = list_width.saturating_sub(padding * 2);
-    let name_width = item.name.len();
-    let desc_width = item.description.len();
+    let name_width = item.name.width();
+    let desc_width = item.description.width();
     let gap = available.saturating_sub(name_width + desc_width);
 
     RatatuiLine::from(vec![
@@ -403,7 +406,12 @@ pub fn render_command_palette(
                         selected_line = Some(lines.len());
                     }
 
-                    let tag_name = format!("#{}", tag.name);
+                    // Nested tags are indented under their parent by segment
+                    let tag_name = if tag.depth == 0 {
+                        format!("#{}", tag.name)
+                    } else {
+                        format!("{}{}", "  ".repeat(tag.depth), tag.name)
+                    };
                     let count_str = format!("({})", tag.count);
                     lines.push(build_palette_item_line(
                         PaletteItem {
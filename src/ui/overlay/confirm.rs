@@ -45,6 +45,13 @@ pub fn render_confirm_modal(f: &mut Frame<'_>, area: Rect, model: ConfirmModel)
                 theme::LABEL_CANNOT_UNDO.to_string(),
             ],
         ),
+        ConfirmContext::RepairJournal(count) => (
+            theme::TITLE_REPAIR_JOURNAL,
+            [
+                format!("Repair {count} issue(s) in journal?"),
+                theme::LABEL_CANNOT_UNDO.to_string(),
+            ],
+        ),
     };
 
     let popup_area = centered_rect(50, 30, area);
@@ -0,0 +1,68 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Style,
+    text::{Line as RatatuiLine, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+use crate::storage::{DayConflict, Line, MergeChunk, serialize_lines};
+
+use super::super::layout::centered_rect;
+use super::super::theme;
+
+pub struct ConflictModel {
+    pub conflict: DayConflict,
+}
+
+fn push_side(lines: &mut Vec<RatatuiLine<'static>>, label: &'static str, side: &[Line]) {
+    lines.push(RatatuiLine::styled(
+        label,
+        Style::default().fg(theme::CONFLICT_LABEL),
+    ));
+    if side.is_empty() {
+        lines.push(RatatuiLine::raw(theme::LABEL_CONFLICT_EMPTY));
+    }
+    for line in side {
+        lines.push(RatatuiLine::raw(format!(
+            "  {}",
+            serialize_lines(std::slice::from_ref(line))
+        )));
+    }
+}
+
+pub fn render_conflict_modal(f: &mut Frame<'_>, area: Rect, model: ConflictModel) {
+    let popup_area = centered_rect(70, 60, area);
+    f.render_widget(Clear, popup_area);
+
+    let title = format!(" Conflict on {} ", model.conflict.date.format("%Y/%m/%d"));
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::CONFIRM_BORDER));
+    let inner = block.inner(popup_area);
+    f.render_widget(block, popup_area);
+
+    let mut lines = vec![RatatuiLine::raw(theme::MSG_CONFLICT)];
+    for chunk in &model.conflict.chunks {
+        if let MergeChunk::Conflict { mine, theirs, .. } = chunk {
+            lines.push(RatatuiLine::raw(""));
+            push_side(&mut lines, theme::LABEL_CONFLICT_MINE, mine);
+            push_side(&mut lines, theme::LABEL_CONFLICT_THEIRS, theirs);
+        }
+    }
+    lines.push(RatatuiLine::raw(""));
+    lines.push(RatatuiLine::from(vec![
+        Span::styled("[M]", Style::default().fg(theme::CONFIRM_YES)),
+        Span::raw(" Mine    "),
+        Span::styled("[T]", Style::default().fg(theme::CONFIRM_YES)),
+        Span::raw(" Theirs    "),
+        Span::styled("[B]", Style::default().fg(theme::CONFIRM_YES)),
+        Span::raw(" Both    "),
+        Span::styled("[Esc]", Style::default().fg(theme::CONFIRM_NO)),
+        Span::raw(" Later"),
+    ]));
+
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false });
+    f.render_widget(paragraph, inner);
+}
@@ -10,6 +10,7 @@ use super::super::layout::centered_rect_max;
 use super::super::theme;
 
 pub struct DatePickerModel {
+    pub title: &'static str,
     pub buffer: String,
     pub cursor_pos: usize,
 }
@@ -18,7 +19,7 @@ pub fn render_date_picker(f: &mut Frame<'_>, area: Rect, model: DatePickerModel)
     let popup_area = centered_rect_max(16, 3, area);
     f.render_widget(Clear, popup_area);
 
-    let block = Block::default().title(" Go to Date ").borders(Borders::ALL);
+    let block = Block::default().title(model.title).borders(Borders::ALL);
 
     let inner = block.inner(popup_area);
     f.render_widget(block, popup_area);
@@ -1,10 +1,12 @@
 mod command_palette;
 mod confirm;
+mod conflict;
 mod date_picker;
 mod shared;
 
 pub use command_palette::{CommandPaletteModel, render_command_palette};
 pub use confirm::{ConfirmModel, render_confirm_modal};
+pub use conflict::{ConflictModel, render_conflict_modal};
 pub use date_picker::{DatePickerModel, render_date_picker};
 
 use ratatui::{Frame, layout::Rect};
@@ -13,6 +15,7 @@ use super::surface::Surface;
 
 pub struct OverlayModel {
     pub confirm: Option<ConfirmModel>,
+    pub conflict: Option<ConflictModel>,
     pub command_palette: Option<CommandPaletteModel>,
     pub date_picker: Option<DatePickerModel>,
 }
@@ -26,6 +29,9 @@ pub fn render_overlays(f: &mut Frame<'_>, overlays: OverlayModel, layout: Overla
     if let Some(confirm) = overlays.confirm {
         render_confirm_modal(f, layout.screen_area, confirm);
     }
+    if let Some(conflict) = overlays.conflict {
+        render_conflict_modal(f, layout.screen_area, conflict);
+    }
     if let Some(palette) = overlays.command_palette {
         render_command_palette(f, layout.screen_area, palette, layout.surface);
     }
@@ -1,9 +1,10 @@
 use crate::app::{App, DATE_SUFFIX_WIDTH, EditContext, InputMode, ViewMode};
 use crate::cursor::{CursorBuffer, cursor_position_in_wrap};
-use crate::storage::Line;
+use crate::storage::{INDENT, Line};
 use unicode_width::UnicodeWidthStr;
 
 use super::context::RenderContext;
+use super::filter::tree_depths;
 use super::scroll::{CursorContext, ensure_line_visible, ensure_selected_visible};
 use super::views::{
     list_content_height_for_daily, list_content_height_for_filter, list_content_width_for_daily,
@@ -88,8 +89,10 @@ pub fn prepare_render(app: &mut App, layout: &RenderContext) -> RenderPrep {
                 let ViewMode::Filter(state) = &app.view else {
                     unreachable!()
                 };
+                let depths = tree_depths(&state.entries);
                 state.entries.get(*filter_index).map(|filter_entry| {
-                    let prefix_width = filter_entry.entry_type.prefix().len();
+                    let prefix_width = INDENT.len() * depths[*filter_index]
+                        + filter_entry.entry_type.prefix().len();
                     let available_width =
                         list_content_width_for_filter(layout).saturating_sub(DATE_SUFFIX_WIDTH);
                     build_cursor_context(buffer, prefix_width, available_width, *filter_index)
@@ -100,13 +103,13 @@ pub fn prepare_render(app: &mut App, layout: &RenderContext) -> RenderPrep {
                 .get(*entry_index)
                 .and_then(|&i| {
                     if let Line::Entry(entry) = &app.lines[i] {
-                        Some(&entry.entry_type)
+                        Some(entry)
                     } else {
                         None
                     }
                 })
-                .map(|entry_type| {
-                    let prefix_width = entry_type.prefix().width();
+                .map(|entry| {
+                    let prefix_width = INDENT.len() * entry.depth + entry.prefix().width();
                     let available_width = list_content_width_for_daily(layout);
                     let hidden_row = if app.has_hidden_entries_row() { 1 } else { 0 };
                     let entry_start_line = app.visible_calendar_event_count()
@@ -12,11 +12,13 @@ use super::autocomplete::render_autocomplete_dropdown;
 use super::calendar::{CalendarModel, render_calendar};
 use super::container::{ContainerConfig, render_container_in_area, render_list};
 use super::context::RenderContext;
+use super::habit_widget::build_habit_lines;
 use super::header::render_header_bar;
 use super::layout::layout_nodes;
 use super::overlay::{OverlayLayout, render_overlays};
 use super::prep::prepare_render;
 use super::scroll::set_edit_cursor;
+use super::shared::truncate_text;
 use super::theme;
 use super::view_model::{PanelContent, build_view_model};
 
@@ -24,10 +26,13 @@ pub fn render_app(f: &mut Frame<'_>, app: &mut App) {
     if app.active_sidebar().is_some() {
         app.ensure_agenda_cache();
     }
+    if app.active_sidebar() == Some(SidebarType::Habits) {
+        app.ensure_habit_cache();
+    }
 
     let base_context = RenderContext::new(f.area());
     let sidebar_width = match app.active_sidebar() {
-        Some(SidebarType::Calendar) => CalendarModel::panel_width(),
+        Some(SidebarType::Calendar | SidebarType::Habits) => CalendarModel::panel_width(),
         Some(SidebarType::Agenda) => {
             let max_width = base_context
                 .main_area
@@ -83,6 +88,7 @@ pub fn render_app(f: &mut Frame<'_>, app: &mut App) {
         match app.active_sidebar() {
             Some(SidebarType::Calendar) => render_calendar_sidebar(f, app, sidebar_area),
             Some(SidebarType::Agenda) => render_agenda_sidebar(f, app, sidebar_area),
+            Some(SidebarType::Habits) => render_habit_sidebar(f, app, sidebar_area),
             None => {}
         }
     }
@@ -259,33 +265,49 @@ fn render_view_heading(
 
     let is_filter_prompt = matches!(app.input_mode, InputMode::FilterPrompt);
 
-    let (label, color) = match &app.view {
+    let (label, color, invalid_spans) = match &app.view {
         ViewMode::Daily(_) => {
             let date_label =
                 super::shared::format_date_smart(app.current_date, &app.config.header_date_format);
             let color = theme::context_primary(app.active_journal());
-            (date_label, color)
+            (date_label, color, Vec::new())
         }
         ViewMode::Filter(state) => {
-            let query_text = if is_filter_prompt {
-                state.query_buffer.content().to_string()
+            let prefix = "Filter: ";
+            let (query_text, invalid_spans) = if is_filter_prompt {
+                (state.query_buffer.content().to_string(), Vec::new())
             } else {
-                state.query.clone()
+                // Point at the parts of a submitted query that failed to parse
+                let spans = state
+                    .invalid_spans
+                    .iter()
+                    .map(|span| span.start + prefix.len()..span.end + prefix.len())
+                    .collect();
+                (state.query.clone(), spans)
             };
-            let filter_label = format!("Filter: {}", query_text);
+            let filter_label = format!("{prefix}{query_text}");
             let color = theme::context_primary(app.active_journal());
-            (filter_label, color)
+            (filter_label, color, invalid_spans)
         }
     };
 
     let label_width = label.width();
-    let line_spans = vec![
-        Span::raw(" ".repeat(theme::HEADING_PADDING)),
-        Span::styled(
-            label,
-            Style::default().fg(color).add_modifier(Modifier::BOLD),
-        ),
-    ];
+    let label_style = Style::default().fg(color).add_modifier(Modifier::BOLD);
+    let error_style = label_style.fg(theme::STATUS_ERROR);
+    let mut line_spans = vec![Span::raw(" ".repeat(theme::HEADING_PADDING))];
+    let mut pos = 0;
+    for span in invalid_spans {
+        if span.start < pos || span.end > label.len() {
+            continue;
+        }
+        line_spans.push(Span::styled(
+            label[pos..span.start].to_string(),
+            label_style,
+        ));
+        line_spans.push(Span::styled(label[span.clone()].to_string(), error_style));
+        pos = span.end;
+    }
+    line_spans.push(Span::styled(label[pos..].to_string(), label_style));
     let heading_line = RatatuiLine::from(line_spans);
     f.render_widget(Paragraph::new(heading_line), heading_row);
 
@@ -388,8 +410,13 @@ fn render_calendar_sidebar(f: &mut Frame<'_>, app: &App, sidebar_area: Rect) {
 }
 
 fn render_agenda_sidebar(f: &mut Frame<'_>, app: &App, sidebar_area: Rect) {
+    let title = if app.calendar_fetches_in_flight > 0 {
+        format!(" Agenda {} ", sync_spinner_frame())
+    } else {
+        " Agenda ".to_string()
+    };
     let config = ContainerConfig {
-        title: Some(RatatuiLine::from(" Agenda ")),
+        title: Some(RatatuiLine::from(title)),
         border_color: theme::BORDER_DEFAULT,
         focused_border_color: None,
         padded: false,
@@ -399,16 +426,99 @@ fn render_agenda_sidebar(f: &mut Frame<'_>, app: &App, sidebar_area: Rect) {
     };
 
     let layout = render_container_in_area(f, sidebar_area, &config, false);
+    let width = layout.content_area.width as usize;
+    let sync_lines = build_calendar_sync_lines(app, width);
+    let header_height = (sync_lines.len() as u16).min(layout.content_area.height);
+    let [header_area, agenda_area] = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(header_height), Constraint::Min(0)])
+        .areas(layout.content_area);
+    f.render_widget(Paragraph::new(sync_lines), header_area);
+
     if let Some(ref cache) = app.agenda_cache {
         let agenda = build_agenda_widget(
             cache,
-            layout.content_area.width as usize,
-            layout.content_area.height as usize,
+            width,
+            agenda_area.height as usize,
             AgendaVariant::Full,
         );
         let lines = agenda.render_lines();
         let content = Paragraph::new(lines);
-        f.render_widget(content, layout.content_area);
+        f.render_widget(content, agenda_area);
+    }
+}
+
+/// One line per visible calendar: how long ago it synced, or why it didn't.
+fn build_calendar_sync_lines(app: &App, width: usize) -> Vec<RatatuiLine<'static>> {
+    let statuses = &app.calendar_store.sync_statuses;
+    if statuses.is_empty() {
+        return Vec::new();
+    }
+    let now = chrono::Local::now();
+    let mut lines: Vec<RatatuiLine<'static>> = statuses
+        .iter()
+        .map(|status| {
+            let age = status.last_synced.map_or_else(
+                || "never synced".to_string(),
+                |synced| format!("synced {}", format_sync_age(now - synced)),
+            );
+            let (text, color) = match &status.error {
+                Some(error) => (
+                    format!("{} · {} · {}", status.calendar_id, age, error),
+                    theme::STATUS_ERROR,
+                ),
+                None => (
+                    format!("{} · {}", status.calendar_id, age),
+                    theme::STATUS_TEXT,
+                ),
+            };
+            RatatuiLine::from(Span::styled(
+                truncate_text(&text, width),
+                Style::default().fg(color),
+            ))
+        })
+        .collect();
+    lines.push(RatatuiLine::default());
+    lines
+}
+
+/// Spinner shown in the agenda title while calendars are fetching; advances
+/// with wall-clock time since the main loop redraws continuously.
+fn sync_spinner_frame() -> char {
+    const FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+    let tick = chrono::Local::now().timestamp_subsec_millis() / 100;
+    FRAMES[tick as usize % FRAMES.len()]
+}
+
+fn format_sync_age(age: chrono::TimeDelta) -> String {
+    let minutes = age.num_minutes().max(0);
+    match minutes {
+        0 => "just now".to_string(),
+        1..60 => format!("{}m ago", minutes),
+        60..1440 => format!("{}h ago", minutes / 60),
+        _ => format!("{}d ago", minutes / 1440),
+    }
+}
+
+fn render_habit_sidebar(f: &mut Frame<'_>, app: &App, sidebar_area: Rect) {
+    let config = ContainerConfig {
+        title: Some(RatatuiLine::from(" Habits ")),
+        border_color: theme::BORDER_DEFAULT,
+        focused_border_color: None,
+        padded: false,
+        borders: Borders::ALL,
+        rounded: true,
+        bottom_buffer: 0,
+    };
+
+    let layout = render_container_in_area(f, sidebar_area, &config, false);
+    if let Some(ref habits) = app.habit_cache {
+        let lines = build_habit_lines(
+            habits,
+            layout.content_area.width as usize,
+            layout.content_area.height as usize,
+        );
+        f.render_widget(Paragraph::new(lines), layout.content_area);
     }
 }
 
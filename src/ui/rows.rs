@@ -1,18 +1,21 @@
-use chrono::Timelike;
+use chrono::{Local, NaiveDate, Timelike};
 use ratatui::{
     style::{Color, Style, Stylize},
     text::Span,
 };
 use unicode_width::UnicodeWidthStr;
 
-use crate::app::{App, InputMode};
+use crate::app::{App, InputMode, ViewMode};
 use crate::calendar::CalendarEvent;
-use crate::storage::{Entry, EntryType, RawEntry, SourceType, strip_done_meta};
+use crate::storage::{
+    Entry, EntryType, INDENT, RawEntry, SearchTerm, SourceType, extract_due_date, strip_entry_meta,
+    subtask_progress,
+};
 
 use super::model::RowModel;
 use super::shared::{
-    date_suffix_style, entry_style, format_date_suffix, style_content, truncate_with_tags,
-    wrap_text,
+    date_suffix_style, entry_style, format_date_suffix, format_due_countdown, format_filter_suffix,
+    highlight_ranges, style_content, truncate_with_tags, wrap_text,
 };
 use super::theme;
 
@@ -74,16 +77,21 @@ pub fn build_projected_row(
         let (source_suffix, _) = format_date_suffix(projected_entry.source_date);
         EntrySuffix::Date(source_suffix)
     };
+    let content = strip_entry_meta(&projected_entry.content);
     build_entry_row(
         app,
         EntryRowSpec {
             entry_type: &projected_entry.entry_type,
-            text: &projected_entry.content,
+            text: &content,
             width,
             is_selected,
             visible_idx,
             indicator: EntryIndicator::Projected(&projected_entry.source_type),
             suffix,
+            depth: 0,
+            collapsed: false,
+            due: extract_due_date(&projected_entry.content, projected_entry.source_date),
+            highlights: &[],
         },
     )
 }
@@ -91,11 +99,16 @@ pub fn build_projected_row(
 pub fn build_daily_entry_row(
     app: &App,
     entry: &RawEntry,
+    line_idx: usize,
     is_selected: bool,
     visible_idx: usize,
     width: usize,
 ) -> RowModel {
-    let content = strip_done_meta(&entry.content);
+    let content = strip_entry_meta(&entry.content);
+    let suffix = match subtask_progress(&app.lines, line_idx) {
+        (_, 0) => EntrySuffix::None,
+        (done, total) => EntrySuffix::Progress(format!(" {done}/{total}")),
+    };
     build_entry_row(
         app,
         EntryRowSpec {
@@ -105,23 +118,38 @@ pub fn build_daily_entry_row(
             is_selected,
             visible_idx,
             indicator: EntryIndicator::Daily,
-            suffix: EntrySuffix::None,
+            suffix,
+            depth: entry.depth,
+            collapsed: entry.collapsed,
+            due: extract_due_date(&entry.content, app.current_date),
+            highlights: &[],
         },
     )
 }
 
-pub fn build_filter_selected_row(app: &App, entry: &Entry, index: usize, width: usize) -> RowModel {
-    let (date_suffix, _) = format_date_suffix(entry.source_date);
+pub fn build_filter_selected_row(
+    app: &App,
+    entry: &Entry,
+    index: usize,
+    depth: usize,
+    width: usize,
+) -> RowModel {
+    let (date_suffix, _) = format_filter_suffix(entry);
+    let content = strip_entry_meta(&entry.content);
     build_entry_row(
         app,
         EntryRowSpec {
             entry_type: &entry.entry_type,
-            text: &entry.content,
+            text: &content,
             width,
             is_selected: true,
             visible_idx: index,
             indicator: EntryIndicator::FilterSelected,
             suffix: EntrySuffix::Date(date_suffix),
+            depth,
+            collapsed: false,
+            due: extract_due_date(&entry.content, entry.source_date),
+            highlights: filter_highlights(app),
         },
     )
 }
@@ -137,6 +165,8 @@ enum EntryIndicator<'a> {
 enum EntrySuffix {
     None,
     Date(String),
+    /// Completed/total sub-tasks of a parent entry
+    Progress(String),
 }
 
 struct EntryRowSpec<'a> {
@@ -147,25 +177,45 @@ struct EntryRowSpec<'a> {
     visible_idx: usize,
     indicator: EntryIndicator<'a>,
     suffix: EntrySuffix,
+    depth: usize,
+    collapsed: bool,
+    due: Option<NaiveDate>,
+    /// Filter terms whose matches are highlighted
+    highlights: &'a [SearchTerm],
 }
 
 fn build_entry_row(app: &App, spec: EntryRowSpec<'_>) -> RowModel {
     let content_style = entry_style(spec.entry_type);
     let prefix = spec.entry_type.prefix();
-    let prefix_width = prefix.width();
+    let indent = INDENT.repeat(spec.depth);
+    let prefix_width = indent.width() + prefix.width();
 
     let (suffix_text, suffix_width) = match spec.suffix {
         EntrySuffix::None => (None, 0),
-        EntrySuffix::Date(text) => {
+        EntrySuffix::Date(text) | EntrySuffix::Progress(text) => {
             let width = text.width();
             (Some(text), width)
         }
     };
 
-    let available = spec.width.saturating_sub(prefix_width + suffix_width);
+    // Finished tasks no longer need a countdown
+    let due = spec
+        .due
+        .filter(|_| !spec.entry_type.is_closed_task())
+        .map(|due| format_due_countdown(due, Local::now().date_naive()));
+    let due_width = due.as_ref().map_or(0, |(label, _)| label.width());
+
+    let available = spec
+        .width
+        .saturating_sub(prefix_width + suffix_width + due_width);
     let display_text = truncate_with_tags(spec.text, available);
 
     let (first_char, rest_of_prefix) = split_prefix(prefix);
+    let first_char = if spec.collapsed {
+        theme::GLYPH_COLLAPSED.to_string()
+    } else {
+        first_char
+    };
     let resolver = IndicatorResolver::new(app);
     let indicator = match spec.indicator {
         EntryIndicator::Daily => resolver.entry_indicator(
@@ -183,29 +233,70 @@ fn build_entry_row(app: &App, spec: EntryRowSpec<'_>) -> RowModel {
             resolver.projected_indicator(spec.is_selected, source_type, content_style)
         }
     };
+    let indicator = if indent.is_empty() {
+        indicator
+    } else {
+        Span::styled(format!("{indent}{}", indicator.content), indicator.style)
+    };
 
     let suffix_span = suffix_text.map(|text| Span::styled(text, date_suffix_style(content_style)));
 
+    let mut content = style_content(&display_text, content_style);
+    if !spec.highlights.is_empty() {
+        let ranges: Vec<_> = spec
+            .highlights
+            .iter()
+            .flat_map(|term| term.find_ranges(&display_text))
+            .collect();
+        content = highlight_ranges(content, &ranges);
+    }
+    if let Some((label, overdue)) = due {
+        let style = if overdue {
+            content_style.fg(theme::OVERDUE)
+        } else {
+            date_suffix_style(content_style)
+        };
+        content.push(Span::styled(label, style));
+    }
+
     RowModel::new(
         Some(indicator),
         Some(Span::styled(rest_of_prefix, content_style)),
-        style_content(&display_text, content_style),
+        content,
         suffix_span,
     )
 }
 
-pub fn build_filter_row(app: &App, entry: &Entry, index: usize, width: usize) -> RowModel {
-    let (date_suffix, _) = format_date_suffix(entry.source_date);
+fn filter_highlights(app: &App) -> &[SearchTerm] {
+    match &app.view {
+        ViewMode::Filter(state) => &state.highlights,
+        ViewMode::Daily(_) => &[],
+    }
+}
+
+pub fn build_filter_row(
+    app: &App,
+    entry: &Entry,
+    index: usize,
+    depth: usize,
+    width: usize,
+) -> RowModel {
+    let (date_suffix, _) = format_filter_suffix(entry);
+    let content = strip_entry_meta(&entry.content);
     build_entry_row(
         app,
         EntryRowSpec {
             entry_type: &entry.entry_type,
-            text: &entry.content,
+            text: &content,
             width,
             is_selected: false,
             visible_idx: index,
             indicator: EntryIndicator::Filter,
             suffix: EntrySuffix::Date(date_suffix),
+            depth,
+            collapsed: false,
+            due: extract_due_date(&entry.content, entry.source_date),
+            highlights: filter_highlights(app),
         },
     )
 }
@@ -249,6 +340,34 @@ pub fn build_edit_rows_with_prefix_width(
         .collect()
 }
 
+/// Header of a group of filter results, with the group's size. A collapsed
+/// header carries the cursor while one of its results is selected.
+pub fn build_group_header_row(
+    app: &App,
+    label: &str,
+    count: usize,
+    collapsed: bool,
+    is_selected: bool,
+) -> RowModel {
+    let resolver = IndicatorResolver::new(app);
+    let indicator = if is_selected {
+        Span::styled(
+            theme::GLYPH_CURSOR,
+            Style::default().fg(resolver.cursor_color()),
+        )
+    } else if collapsed {
+        Span::styled(theme::GLYPH_COLLAPSED, Style::default().dim())
+    } else {
+        Span::styled(theme::GLYPH_EXPANDED, Style::default().dim())
+    };
+    RowModel::new(
+        Some(indicator),
+        Some(Span::raw(" ")),
+        vec![Span::styled(label.to_string(), Style::default().bold())],
+        Some(Span::styled(format!(" ({count})"), Style::default().dim())),
+    )
+}
+
 pub fn build_message_row(message: &str, style: Style) -> RowModel {
     RowModel::from_spans(vec![Span::styled(message.to_string(), style)])
 }
@@ -322,6 +441,7 @@ impl<'a> IndicatorResolver<'a> {
             SourceType::Recurring => theme::GLYPH_PROJECTED_RECURRING,
             SourceType::Local => unreachable!("projected entries are never Local"),
             SourceType::Calendar { .. } => theme::GLYPH_PROJECTED_CALENDAR,
+            SourceType::Due => theme::GLYPH_PROJECTED_DUE,
         };
 
         if is_cursor {
@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use chrono::{Datelike, Local, NaiveDate};
 use ratatui::{
     style::{Color, Style},
@@ -8,13 +10,18 @@ use super::theme;
 use unicode_width::UnicodeWidthStr;
 
 use crate::storage::{
-    EntryType, LAST_TRAILING_TAG_REGEX, RECURRING_REGEX, TAG_REGEX, TRAILING_TAGS_REGEX,
+    DUE_REGEX, Entry, EntryType, PROPERTY_REGEX, RECURRING_REGEX, TAG_REGEX,
+    TRAILING_SECTION_REGEX, TaskState,
 };
 
 #[must_use]
 pub fn entry_style(entry_type: &EntryType) -> Style {
     match entry_type {
-        EntryType::Task { completed: true } => {
+        EntryType::Task {
+            state: TaskState::Cancelled,
+        } => Style::default()
+            .add_modifier(ratatui::style::Modifier::DIM | ratatui::style::Modifier::CROSSED_OUT),
+        EntryType::Task { state } if state.is_closed() => {
             Style::default().add_modifier(ratatui::style::Modifier::DIM)
         }
         EntryType::Event => Style::default().add_modifier(ratatui::style::Modifier::ITALIC),
@@ -29,6 +36,18 @@ pub fn format_date_suffix(date: NaiveDate) -> (String, usize) {
     (suffix, width)
 }
 
+/// Format the suffix for a filter result, naming its journal when the
+/// result came from a cross-journal query
+#[must_use]
+pub fn format_filter_suffix(entry: &Entry) -> (String, usize) {
+    let Some(journal) = &entry.journal else {
+        return format_date_suffix(entry.source_date);
+    };
+    let suffix = format!(" ({} {})", journal.name, entry.source_date.format("%m/%d"));
+    let width = suffix.width();
+    (suffix, width)
+}
+
 /// Format a date for display, showing year only if different from current year
 #[must_use]
 pub fn format_date_smart(date: NaiveDate, format: &str) -> String {
@@ -41,35 +60,67 @@ pub fn format_date_smart(date: NaiveDate, format: &str) -> String {
     }
 }
 
+/// Countdown to a due date, e.g. " due in 3d". The flag is true once overdue.
+#[must_use]
+pub fn format_due_countdown(due: NaiveDate, today: NaiveDate) -> (String, bool) {
+    let days = (due - today).num_days();
+    let label = match days {
+        0 => " due today".to_string(),
+        1 => " due tomorrow".to_string(),
+        d if d > 1 => format!(" due in {d}d"),
+        d => format!(" overdue {}d", -d),
+    };
+    (label, days < 0)
+}
+
 /// Style for date suffixes - always dimmed relative to entry content
 #[must_use]
 pub fn date_suffix_style(base: Style) -> Style {
     base.add_modifier(ratatui::style::Modifier::DIM)
 }
 
-/// Remove the last trailing tag, returns None if no trailing tags or entry is only tags
+/// Remove the last trailing tag, returns None if no trailing tags or entry is only tags.
+/// Properties in the trailing section stay.
 #[must_use]
 pub fn remove_last_trailing_tag(text: &str) -> Option<String> {
-    LAST_TRAILING_TAG_REGEX.find(text).and_then(|m| {
-        let before = &text[..m.start()];
-        if before.chars().any(|c| !c.is_whitespace()) {
-            Some(before.to_string())
-        } else {
-            None
-        }
-    })
+    let (before, section) = split_trailing_section(text)?;
+    let tag = TAG_REGEX.find_iter(section).last()?;
+    let rest = format!(
+        "{}{}",
+        section[..tag.start()].trim_end(),
+        &section[tag.end()..]
+    );
+    Some(format!("{before}{}", rest.trim_end()))
 }
 
-/// Remove all trailing tags, returns None if no trailing tags or entry is only tags
+/// Remove all trailing tags, returns None if no trailing tags or entry is only tags.
+/// Properties in the trailing section stay.
 #[must_use]
 pub fn remove_all_trailing_tags(text: &str) -> Option<String> {
-    TRAILING_TAGS_REGEX.find(text).and_then(|m| {
+    let (before, section) = split_trailing_section(text)?;
+    let (tags, properties): (Vec<&str>, Vec<&str>) = section
+        .split_whitespace()
+        .partition(|item| item.starts_with('#'));
+    if tags.is_empty() {
+        return None;
+    }
+    let mut result = before.to_string();
+    for property in properties {
+        result.push(' ');
+        result.push_str(property);
+    }
+    Some(result)
+}
+
+/// Splits off the trailing section of tags and properties, unless the entry
+/// has nothing else
+fn split_trailing_section(text: &str) -> Option<(&str, &str)> {
+    TRAILING_SECTION_REGEX.find(text).and_then(|m| {
         let before = &text[..m.start()];
-        if before.chars().any(|c| !c.is_whitespace()) {
-            Some(before.to_string())
-        } else {
-            None
-        }
+        before
+            .chars()
+            .any(|c| !c.is_whitespace())
+            .then_some((before, m.as_str()))
     })
 }
 
@@ -86,6 +137,17 @@ pub fn style_content(text: &str, base_style: Style) -> Vec<Span<'static>> {
 
     collect_matches(&TAG_REGEX, theme::TAG, &mut matches);
     collect_matches(&RECURRING_REGEX, theme::PROJECTED_DATE, &mut matches);
+    collect_matches(&DUE_REGEX, theme::PROJECTED_DATE, &mut matches);
+    // Properties give way to any tag or date written inside their value
+    for cap in PROPERTY_REGEX.captures_iter(text) {
+        if let (Some(key), Some(value)) = (cap.get(1), cap.get(2))
+            && !matches
+                .iter()
+                .any(|&(s, e, _)| s < value.end() && key.start() < e)
+        {
+            matches.push((key.start(), value.end(), theme::PROPERTY));
+        }
+    }
 
     matches.sort_by_key(|(start, _, _)| *start);
 
@@ -114,6 +176,56 @@ pub fn style_content(text: &str, base_style: Style) -> Vec<Span<'static>> {
     spans
 }
 
+/// Marks the byte ranges of the text the spans hold together as search
+/// matches, splitting spans where a match starts or ends.
+#[must_use]
+pub fn highlight_ranges(spans: Vec<Span<'static>>, ranges: &[Range<usize>]) -> Vec<Span<'static>> {
+    let mut merged: Vec<Range<usize>> = ranges.to_vec();
+    merged.sort_by_key(|range| range.start);
+    merged.dedup_by(|next, kept| {
+        if next.start <= kept.end {
+            kept.end = kept.end.max(next.end);
+            true
+        } else {
+            false
+        }
+    });
+
+    let mut result = Vec::with_capacity(spans.len());
+    let mut offset = 0;
+    for span in spans {
+        let text = span.content.as_ref();
+        let span_end = offset + text.len();
+        let mut cursor = offset;
+        for range in &merged {
+            let start = range.start.max(cursor);
+            let end = range.end.min(span_end);
+            if start >= end {
+                continue;
+            }
+            if start > cursor {
+                result.push(Span::styled(
+                    text[cursor - offset..start - offset].to_string(),
+                    span.style,
+                ));
+            }
+            result.push(Span::styled(
+                text[start - offset..end - offset].to_string(),
+                span.style.add_modifier(theme::SEARCH_MATCH),
+            ));
+            cursor = end;
+        }
+        if cursor < span_end {
+            result.push(Span::styled(
+                text[cursor - offset..].to_string(),
+                span.style,
+            ));
+        }
+        offset = span_end;
+    }
+    result
+}
+
 pub fn truncate_text(text: &str, max_width: usize) -> String {
     if text.width() <= max_width {
         return text.to_string();
@@ -138,10 +250,10 @@ pub fn truncate_text(text: &str, max_width: usize) -> String {
     format!("{trimmed}{ellipsis}")
 }
 
-/// Split text into (content, trailing_tags) if tags exist at end
+/// Split text into (content, trailing_tags) if tags or properties exist at end
 #[must_use]
 pub fn split_trailing_tags(text: &str) -> (&str, Option<&str>) {
-    if let Some(m) = TRAILING_TAGS_REGEX.find(text) {
+    if let Some(m) = TRAILING_SECTION_REGEX.find(text) {
         (&text[..m.start()], Some(m.as_str().trim()))
     } else {
         (text, None)
@@ -1,4 +1,4 @@
-use ratatui::style::Color;
+use ratatui::style::{Color, Modifier};
 
 use super::surface::Surface;
 use crate::storage::JournalSlot;
@@ -15,12 +15,16 @@ pub const FILTER_BORDER: Color = Color::Magenta;
 
 // Content highlighting
 pub const TAG: Color = Color::Yellow;
+pub const PROPERTY: Color = Color::Cyan;
 pub const PROJECTED_DATE: Color = Color::Red;
+pub const OVERDUE: Color = Color::LightRed;
+pub const SEARCH_MATCH: Modifier = Modifier::BOLD.union(Modifier::UNDERLINED);
 
 // Confirm dialog
 pub const CONFIRM_BORDER: Color = Color::Blue;
 pub const CONFIRM_YES: Color = Color::Green;
 pub const CONFIRM_NO: Color = Color::Red;
+pub const CONFLICT_LABEL: Color = Color::Yellow;
 
 // Command palette
 pub const PALETTE_ACCENT: Color = Color::Cyan;
@@ -102,8 +106,11 @@ pub const GLYPH_CURSOR: &str = "→";
 pub const GLYPH_SELECTED: &str = "◉";
 pub const GLYPH_UNSELECTED: &str = "○";
 pub const GLYPH_REORDER: &str = "↕";
+pub const GLYPH_COLLAPSED: &str = "▸";
+pub const GLYPH_EXPANDED: &str = "▾";
 pub const GLYPH_PROJECTED_RECURRING: &str = "↺";
 pub const GLYPH_PROJECTED_CALENDAR: &str = "○";
+pub const GLYPH_PROJECTED_DUE: &str = "!";
 
 // Default color cycle for calendars (based on config order)
 pub const CALENDAR_COLORS: &[Color] = &[
@@ -145,6 +152,15 @@ pub const AGENDA_ENTRY_PADDING: usize = 5;
 pub const AGENDA_MIN_GUTTER: u16 = 20;
 pub const AGENDA_BORDER_WIDTH: usize = 2;
 
+// Habit tracker
+pub const HABIT_GRID_LENGTH: usize = 14;
+pub const HABIT_DONE: Color = Color::Green;
+pub const HABIT_MISSED: Color = Color::Red;
+pub const GLYPH_HABIT_DONE: char = '●';
+pub const GLYPH_HABIT_MISSED: char = '○';
+pub const GLYPH_HABIT_SKIPPED: char = '–';
+pub const GLYPH_HABIT_PENDING: char = '·';
+
 // Hint colors
 pub const HINT_FILTER_TYPE: Color = Color::Magenta;
 pub const HINT_INACTIVE: Color = Color::Reset;
@@ -175,11 +191,18 @@ pub const LABEL_CANNOT_UNDO: &str = "This cannot be undone.";
 pub const TITLE_CREATE_PROJECT: &str = " Create Project Journal ";
 pub const TITLE_DELETE_TAG: &str = " Delete Tag ";
 pub const TITLE_REMOVE_FROM_COMPLETED: &str = " Remove from Completed ";
+pub const TITLE_REPAIR_JOURNAL: &str = " Repair Journal ";
 
 // UI Labels - Confirm dialog messages
 pub const MSG_NO_PROJECT_JOURNAL: &str = "No project journal found.";
 pub const MSG_CREATE_PROJECT_JOURNAL: &str = "Create .caliber/journal.md?";
 
+// UI Labels - Conflict dialog
+pub const MSG_CONFLICT: &str = "This day was changed outside caliber while you were editing.";
+pub const LABEL_CONFLICT_MINE: &str = "Mine:";
+pub const LABEL_CONFLICT_THEIRS: &str = "Theirs (on disk):";
+pub const LABEL_CONFLICT_EMPTY: &str = "  (removed)";
+
 // UI Labels - Daily view
 #[must_use]
 pub fn hidden_entries_label(count: usize) -> String {
@@ -1,14 +1,16 @@
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line as RatatuiLine, Span};
 
-use crate::app::{App, InputMode};
+use crate::app::{App, DatePickerPurpose, InputMode};
 
 use super::container::ContainerConfig;
 use super::context::RenderContext;
 use super::header::HeaderModel;
 use super::layout::{LayoutNode, PanelId};
 use super::model::ListModel;
-use super::overlay::{CommandPaletteModel, ConfirmModel, DatePickerModel, OverlayModel};
+use super::overlay::{
+    CommandPaletteModel, ConfirmModel, ConflictModel, DatePickerModel, OverlayModel,
+};
 use super::prep::RenderPrep;
 use super::scroll::CursorContext;
 use super::theme;
@@ -71,6 +73,12 @@ pub fn build_view_model(app: &App, context: &RenderContext, prep: RenderPrep) ->
             InputMode::Confirm(confirm_context) => Some(ConfirmModel::new(confirm_context.clone())),
             _ => None,
         },
+        conflict: match &app.input_mode {
+            InputMode::Conflict(state) => Some(ConflictModel {
+                conflict: state.conflict.clone(),
+            }),
+            _ => None,
+        },
         command_palette: match &app.input_mode {
             InputMode::CommandPalette(state) => Some(CommandPaletteModel::new(
                 state,
@@ -81,6 +89,10 @@ pub fn build_view_model(app: &App, context: &RenderContext, prep: RenderPrep) ->
         },
         date_picker: match &app.input_mode {
             InputMode::DatePicker(state) => Some(DatePickerModel {
+                title: match state.purpose {
+                    DatePickerPurpose::GoTo => " Go to Date ",
+                    DatePickerPurpose::MoveOccurrence(_) => " Move to Date ",
+                },
                 buffer: state.buffer.content().to_string(),
                 cursor_pos: state.buffer.cursor_char_pos(),
             }),
@@ -0,0 +1,392 @@
+mod helpers;
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use caliber::calendar::{
+    CachedFeed, CalendarFetchResult, CalendarSyncStatus, FeedMeta, fetch_all_calendars,
+    load_cached_calendars, load_feed, refresh_calendars, run_secret_command, save_feed,
+};
+use caliber::config::Config;
+use chrono::{Local, NaiveDate};
+use helpers::TestContext;
+use tiny_http::{Header, Response, Server};
+
+const ETAG: &str = "\"v1\"";
+
+fn ics_with_event_today(summary: &str) -> String {
+    let today = Local::now().date_naive().format("%Y%m%d");
+    format!(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:standup\r\n\
+         DTSTART;VALUE=DATE:{today}\r\nSUMMARY:{summary}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n"
+    )
+}
+
+/// `If-None-Match` header of each request the server saw
+type SeenHeaders = Arc<Mutex<Vec<Option<String>>>>;
+
+/// Serves one feed, answering 304 when the client sends the current ETag.
+fn spawn_feed_server(body: String) -> (Arc<Server>, String, SeenHeaders) {
+    let server = Arc::new(Server::http("127.0.0.1:0").unwrap());
+    let url = format!("http://{}/work.ics", server.server_addr().to_ip().unwrap());
+    let seen = Arc::new(Mutex::new(Vec::new()));
+
+    let (server_ref, seen_ref) = (Arc::clone(&server), Arc::clone(&seen));
+    thread::spawn(move || {
+        for request in server_ref.incoming_requests() {
+            let if_none_match = request
+                .headers()
+                .iter()
+                .find(|h| h.field.equiv("If-None-Match"))
+                .map(|h| h.value.to_string());
+            seen_ref.lock().unwrap().push(if_none_match.clone());
+
+            let etag = Header::from_bytes("ETag", ETAG).unwrap();
+            if if_none_match.as_deref() == Some(ETAG) {
+                drop(request.respond(Response::empty(304).with_header(etag)));
+            } else {
+                drop(request.respond(Response::from_string(body.clone()).with_header(etag)));
+            }
+        }
+    });
+
+    (server, url, seen)
+}
+
+/// Serves one feed only to requests carrying the expected `Authorization`
+/// and `X-Calendar-Key` headers; anything else gets a 401.
+fn spawn_auth_server(authorization: &'static str, body: String) -> (Arc<Server>, String) {
+    let server = Arc::new(Server::http("127.0.0.1:0").unwrap());
+    let url = format!(
+        "http://{}/private.ics",
+        server.server_addr().to_ip().unwrap()
+    );
+
+    let server_ref = Arc::clone(&server);
+    thread::spawn(move || {
+        for request in server_ref.incoming_requests() {
+            let header = |name: &'static str| {
+                request
+                    .headers()
+                    .iter()
+                    .find(|h| h.field.equiv(name))
+                    .map(|h| h.value.to_string())
+            };
+            let authorized = header("Authorization").as_deref() == Some(authorization)
+                && header("X-Calendar-Key").as_deref() == Some("k1");
+            let response = if authorized {
+                Response::from_string(body.clone())
+            } else {
+                Response::from_string("unauthorized").with_status_code(401)
+            };
+            drop(request.respond(response));
+        }
+    });
+
+    (server, url)
+}
+
+/// Config with a single `work` calendar made of the given TOML fields.
+fn calendar_config(fields: &str) -> Config {
+    toml::from_str(&format!("[calendars.work]\n{fields}\n")).unwrap()
+}
+
+fn config_with_calendar(url: &str) -> Config {
+    calendar_config(&format!(r#"url = "{url}""#))
+}
+
+fn fetch(config: &Config, cache_dir: &Path) -> CalendarFetchResult {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(fetch_all_calendars(
+        config,
+        &["work".to_string()],
+        cache_dir,
+    ))
+}
+
+fn titles(result: &CalendarFetchResult) -> Vec<&str> {
+    result.events.iter().map(|e| e.title.as_str()).collect()
+}
+
+#[test]
+fn calendar_feeds_are_cached_and_revalidated() {
+    let cache_dir = tempfile::tempdir().unwrap();
+    let (server, url, seen) = spawn_feed_server(ics_with_event_today("Standup"));
+    let config = config_with_calendar(&url);
+    let ids = ["work".to_string()];
+
+    // Nothing cached yet
+    let cached = load_cached_calendars(&config, &ids, cache_dir.path());
+    assert!(cached.events.is_empty());
+    assert!(cached.statuses[0].last_synced.is_none());
+
+    // First fetch downloads the feed and caches it
+    let first = fetch(&config, cache_dir.path());
+    assert_eq!(titles(&first), ["Standup"]);
+    assert!(first.statuses[0].error.is_none());
+    assert!(first.statuses[0].last_synced.is_some());
+    assert!(cache_dir.path().join("work.json").exists());
+
+    // Second fetch sends the stored ETag and gets a 304, served from cache
+    let second = fetch(&config, cache_dir.path());
+    assert_eq!(titles(&second), ["Standup"]);
+    assert!(second.statuses[0].error.is_none());
+    assert_eq!(*seen.lock().unwrap(), [None, Some(ETAG.to_string())]);
+
+    // The cache renders on its own at launch
+    let cached = load_cached_calendars(&config, &ids, cache_dir.path());
+    assert_eq!(titles(&cached), ["Standup"]);
+    assert_eq!(
+        cached.statuses[0].last_synced,
+        second.statuses[0].last_synced
+    );
+
+    // Pointing the calendar at another URL ignores the old cache
+    server.unblock();
+    let moved = config_with_calendar("http://127.0.0.1:9/work.ics");
+    let cached = load_cached_calendars(&moved, &ids, cache_dir.path());
+    assert!(cached.events.is_empty());
+}
+
+#[test]
+fn failed_fetch_falls_back_to_cached_feed() {
+    let cache_dir = tempfile::tempdir().unwrap();
+    let (server, url, _) = spawn_feed_server(ics_with_event_today("Review"));
+    let config = config_with_calendar(&url);
+
+    let online = fetch(&config, cache_dir.path());
+    assert_eq!(titles(&online), ["Review"]);
+
+    // Take the server down; the same URL now refuses connections
+    server.unblock();
+    drop(server);
+    thread::sleep(std::time::Duration::from_millis(50));
+
+    let offline = fetch(&config, cache_dir.path());
+    assert_eq!(titles(&offline), ["Review"]);
+    let status = &offline.statuses[0];
+    assert!(
+        status
+            .error
+            .as_deref()
+            .is_some_and(|e| e.contains("Failed to fetch"))
+    );
+    assert_eq!(status.last_synced, online.statuses[0].last_synced);
+}
+
+#[test]
+fn cached_feeds_are_private_and_never_share_a_file() {
+    let cache_dir = tempfile::tempdir().unwrap();
+    let url = "https://example.com/feed.ics";
+    let feed = |body: &str| CachedFeed {
+        meta: FeedMeta {
+            url: url.to_string(),
+            etag: Some(ETAG.to_string()),
+            last_modified: None,
+            synced_at: Local::now(),
+        },
+        body: body.to_string(),
+    };
+
+    save_feed(cache_dir.path(), "a.b", &feed("dotted")).unwrap();
+    save_feed(cache_dir.path(), "a_b", &feed("underscored")).unwrap();
+    let body = |id: &str| load_feed(cache_dir.path(), id, url).unwrap().body;
+    assert_eq!(body("a.b"), "dotted");
+    assert_eq!(body("a_b"), "underscored");
+    assert_eq!(std::fs::read_dir(cache_dir.path()).unwrap().count(), 2);
+
+    #[cfg(unix)]
+    for entry in std::fs::read_dir(cache_dir.path()).unwrap() {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = entry.unwrap().metadata().unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
+
+#[test]
+fn refresh_fetches_only_due_calendars() {
+    let cache_dir = tempfile::tempdir().unwrap();
+    let (_server, url, seen) = spawn_feed_server(ics_with_event_today("Planning"));
+    let config = config_with_calendar(&url);
+    let ids = ["work".to_string()];
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    let first = fetch(&config, cache_dir.path());
+    assert_eq!(seen.lock().unwrap().len(), 1);
+
+    // Not due: served from cache, with the previous status carried over
+    let previous = [CalendarSyncStatus {
+        error: Some("timed out".to_string()),
+        ..first.statuses[0].clone()
+    }];
+    let skipped = runtime.block_on(refresh_calendars(
+        &config,
+        &ids,
+        &[],
+        &previous,
+        cache_dir.path(),
+    ));
+    assert_eq!(titles(&skipped), ["Planning"]);
+    assert_eq!(skipped.statuses[0].error.as_deref(), Some("timed out"));
+    assert_eq!(seen.lock().unwrap().len(), 1);
+
+    // Due: revalidated against the server, clearing the old error
+    let due = runtime.block_on(refresh_calendars(
+        &config,
+        &ids,
+        &ids,
+        &previous,
+        cache_dir.path(),
+    ));
+    assert_eq!(titles(&due), ["Planning"]);
+    assert!(due.statuses[0].error.is_none());
+    assert_eq!(seen.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn refresh_interval_falls_back_to_global_setting() {
+    let config: Config = toml::from_str(
+        r#"
+        calendar_refresh_interval = 30
+
+        [calendars.work]
+        url = "https://example.com/work.ics"
+        refresh_interval = 5
+
+        [calendars.home]
+        url = "https://example.com/home.ics"
+
+        [calendars.archive]
+        url = "https://example.com/archive.ics"
+        refresh_interval = 0
+        "#,
+    )
+    .unwrap();
+
+    let minutes = |m: u64| Some(std::time::Duration::from_secs(m * 60));
+    assert_eq!(config.refresh_interval_for("work"), minutes(5));
+    assert_eq!(config.refresh_interval_for("home"), minutes(30));
+    assert_eq!(config.refresh_interval_for("archive"), None);
+
+    let defaults: Config = toml::from_str("").unwrap();
+    assert_eq!(defaults.calendar_refresh_interval, 15);
+}
+
+#[test]
+fn basic_auth_password_comes_from_command() {
+    let cache_dir = tempfile::tempdir().unwrap();
+    let (_server, url) = spawn_auth_server(
+        "Basic YWxpY2U6aHVudGVyMg==",
+        ics_with_event_today("Private"),
+    );
+
+    let config = calendar_config(&format!(
+        r#"url = "{url}"
+        username = "alice"
+        password_command = "echo hunter2"
+        headers = {{ X-Calendar-Key = "k1" }}"#
+    ));
+    let result = fetch(&config, cache_dir.path());
+    assert_eq!(titles(&result), ["Private"]);
+    assert!(result.statuses[0].error.is_none());
+
+    let wrong_password = calendar_config(&format!(
+        r#"url = "{url}"
+        username = "alice"
+        password_command = "echo wrong"
+        headers = {{ X-Calendar-Key = "k1" }}"#
+    ));
+    let other_cache = tempfile::tempdir().unwrap();
+    let result = fetch(&wrong_password, other_cache.path());
+    assert!(result.events.is_empty());
+    assert!(
+        result.statuses[0]
+            .error
+            .as_deref()
+            .is_some_and(|e| e.contains("401"))
+    );
+}
+
+#[test]
+fn bearer_token_comes_from_command() {
+    let cache_dir = tempfile::tempdir().unwrap();
+    let (_server, url) = spawn_auth_server("Bearer s3cret", ics_with_event_today("Oncall"));
+
+    let config = calendar_config(&format!(
+        r#"url = "{url}"
+        token_command = "printf 's3cret\\nsecond line'"
+        headers = {{ X-Calendar-Key = "k1" }}"#
+    ));
+    let result = fetch(&config, cache_dir.path());
+    assert_eq!(titles(&result), ["Oncall"]);
+}
+
+#[test]
+fn failing_secret_command_is_reported() {
+    let cache_dir = tempfile::tempdir().unwrap();
+    let (_server, url) = spawn_auth_server("Bearer s3cret", ics_with_event_today("Oncall"));
+
+    let config = calendar_config(&format!(
+        r#"url = "{url}"
+        token_command = "echo 'no such entry' >&2; exit 1""#
+    ));
+    let result = fetch(&config, cache_dir.path());
+    assert!(result.events.is_empty());
+    assert_eq!(
+        result.statuses[0].error.as_deref(),
+        Some("token_command failed: no such entry")
+    );
+}
+
+#[test]
+fn hanging_secret_command_times_out() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let started = std::time::Instant::now();
+    let result = runtime.block_on(run_secret_command(
+        "sleep 10",
+        "token_command",
+        std::time::Duration::from_millis(200),
+    ));
+    assert_eq!(result, Err("token_command timed out".to_string()));
+    assert!(started.elapsed() < std::time::Duration::from_secs(5));
+}
+
+#[test]
+fn stale_fetch_results_are_dropped() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let mut ctx = TestContext::with_journal_content(date, "# 2026/01/15\n- [ ] Task\n");
+    let (tx, rx) = tokio::sync::mpsc::channel(4);
+    ctx.app.calendar_tx = Some(tx.clone());
+    ctx.app.calendar_rx = Some(rx);
+
+    let result = |calendar_id: &str| CalendarFetchResult {
+        events: Vec::new(),
+        visible_count: 1,
+        statuses: vec![CalendarSyncStatus {
+            calendar_id: calendar_id.to_string(),
+            last_synced: None,
+            error: None,
+        }],
+    };
+    let synced_ids = |ctx: &TestContext| -> Vec<String> {
+        ctx.app
+            .calendar_store
+            .sync_statuses
+            .iter()
+            .map(|status| status.calendar_id.clone())
+            .collect()
+    };
+
+    // Two fetches in flight; the newer one finishes first
+    ctx.app.calendar_generation = 2;
+    ctx.app.calendar_fetches_in_flight = 2;
+    tx.try_send((2, result("newer"))).unwrap();
+    ctx.app.poll_calendar_results();
+    assert_eq!(synced_ids(&ctx), ["newer"]);
+
+    tx.try_send((1, result("older"))).unwrap();
+    ctx.app.poll_calendar_results();
+    assert_eq!(synced_ids(&ctx), ["newer"]);
+    assert_eq!(ctx.app.calendar_fetches_in_flight, 0);
+}
@@ -0,0 +1,685 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+use tempfile::TempDir;
+
+/// Isolated environment for running the caliber binary headlessly.
+/// Config lives under a temp XDG dir and the working directory is outside any git repo.
+struct CliEnv {
+    temp_dir: TempDir,
+}
+
+impl CliEnv {
+    fn new() -> Self {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        Self { temp_dir }
+    }
+
+    fn config_dir(&self) -> PathBuf {
+        self.temp_dir.path().join("caliber")
+    }
+
+    fn hub_path(&self) -> PathBuf {
+        self.config_dir().join("hub_journal.md")
+    }
+
+    fn write_hub(&self, content: &str) {
+        std::fs::create_dir_all(self.config_dir()).unwrap();
+        std::fs::write(self.hub_path(), content).unwrap();
+    }
+
+    fn write_config(&self, content: &str) {
+        std::fs::create_dir_all(self.config_dir()).unwrap();
+        std::fs::write(self.config_dir().join("config.toml"), content).unwrap();
+    }
+
+    fn read_hub(&self) -> String {
+        std::fs::read_to_string(self.hub_path()).unwrap_or_default()
+    }
+
+    fn command(&self, args: &[&str]) -> Command {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_caliber"));
+        cmd.args(args)
+            .current_dir(self.temp_dir.path())
+            .env("XDG_CONFIG_HOME", self.temp_dir.path())
+            .env("CALIBER_SKIP_REGISTRY", "1");
+        cmd
+    }
+
+    fn run(&self, args: &[&str]) -> Output {
+        self.command(args).output().expect("Failed to run caliber")
+    }
+
+    fn run_with_stdin(&self, args: &[&str], stdin: &str) -> Output {
+        let mut child = self
+            .command(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Failed to run caliber");
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(stdin.as_bytes())
+            .unwrap();
+        child.wait_with_output().unwrap()
+    }
+}
+
+#[test]
+fn add_appends_normalized_task_to_date() {
+    let env = CliEnv::new();
+    env.write_hub("# 2026/01/15\n- [ ] Existing\n");
+
+    let output = env.run(&["add", "--date", "2026/01/15", "Fix", "login", "#2"]);
+    assert!(output.status.success(), "{output:?}");
+
+    let journal = env.read_hub();
+    assert!(journal.contains("- [ ] Existing\n- [ ] Fix login #bug"));
+}
+
+#[test]
+fn add_reads_stdin_and_respects_type_override() {
+    let env = CliEnv::new();
+    env.write_config("[favorite_tags]\n1 = \"work\"\n");
+
+    let output = env.run_with_stdin(
+        &["add", "--note", "--date", "2026/01/20", "-"],
+        "first thought #1\n\n* second thought\n",
+    );
+    assert!(output.status.success(), "{output:?}");
+
+    let journal = env.read_hub();
+    assert!(journal.contains("# 2026/01/20\n- first thought #work\n- second thought\n"));
+}
+
+#[test]
+fn add_rejects_invalid_date_without_writing() {
+    let env = CliEnv::new();
+
+    let output = env.run(&["add", "--date", "not-a-date", "Something"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid date"));
+    assert!(env.read_hub().is_empty());
+}
+
+#[test]
+fn add_keeps_flag_like_words_after_text_starts() {
+    let env = CliEnv::new();
+
+    let output = env.run(&["add", "--date", "2026/01/15", "Explain", "-n", "option"]);
+    assert!(output.status.success(), "{output:?}");
+    let output = env.run(&["add", "--date", "2026/01/15", "Remove", "the", "-d", "flag"]);
+    assert!(output.status.success(), "{output:?}");
+
+    let journal = env.read_hub();
+    assert!(journal.contains("- [ ] Explain -n option\n- [ ] Remove the -d flag"));
+}
+
+#[test]
+fn query_prints_json_lines_with_location_and_tags() {
+    let env = CliEnv::new();
+    env.write_hub(
+        "# 2026/01/10\n- [ ] Ship release #work\n- Lunch notes\n\n# 2026/01/12\n- [x] Done thing #work\n- [ ] Call mom #home\n",
+    );
+
+    let output = env.run(&["query", "--format", "json", "#work"]);
+    assert!(output.status.success(), "{output:?}");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(
+        lines[0],
+        r#"{"date":"2026-01-10","line_index":0,"type":"task","completed":false,"state":"open","content":"Ship release #work","tags":["work"]}"#
+    );
+    assert!(lines[1].contains(r#""date":"2026-01-12","line_index":0"#));
+    assert!(lines[1].contains(r#""completed":true"#));
+}
+
+#[test]
+fn query_expands_saved_filters_and_negations() {
+    let env = CliEnv::new();
+    env.write_config("[filters]\nopen = \"!tasks\"\n");
+    env.write_hub("# 2026/01/10\n- [ ] Ship release #work\n- [ ] Call mom #home\n- A note\n");
+
+    let output = env.run(&["query", "$open", "-#home"]);
+    assert!(output.status.success(), "{output:?}");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().count(), 1);
+    assert!(stdout.contains("2026/01/10  0  task   Ship release #work  work"));
+
+    let output = env.run(&["query", "$missing"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown filter: $missing"));
+}
+
+#[test]
+fn query_evaluates_boolean_expressions_with_saved_filters() {
+    let env = CliEnv::new();
+    env.write_config("[filters]\neither = \"#bug | #regression\"\n");
+    env.write_hub(
+        "# 2026/01/10\n- [ ] Crash #bug\n- [ ] Slow again #regression\n- [x] Fixed #bug\n- Triage notes #bug\n- [ ] Plan #work\n* Dinner #home\n* Offsite #work\n",
+    );
+    let contents = |args: &[&str]| -> Vec<String> {
+        let output = env.run(&[&["query", "--format", "json"], args].concat());
+        assert!(output.status.success(), "{output:?}");
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.split(r#""content":""#).nth(1).unwrap().to_string())
+            .map(|rest| rest.split('"').next().unwrap().to_string())
+            .collect()
+    };
+
+    assert_eq!(
+        contents(&["#bug", "|", "#regression"]),
+        [
+            "Crash #bug",
+            "Slow again #regression",
+            "Fixed #bug",
+            "Triage notes #bug"
+        ]
+    );
+    assert_eq!(
+        contents(&["(#work !tasks) or (#home !events)"]),
+        ["Plan #work", "Dinner #home"]
+    );
+    assert_eq!(
+        contents(&["!tasks -(#bug | #work)"]),
+        ["Slow again #regression"]
+    );
+    assert_eq!(
+        contents(&["$either !tasks"]),
+        ["Crash #bug", "Slow again #regression"]
+    );
+    assert_eq!(
+        contents(&["-($either) !events"]),
+        ["Dinner #home", "Offsite #work"]
+    );
+
+    let output = env.run(&["query", "(#bug"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown filter: ("));
+
+    let saved = Default::default();
+    let filter = caliber::storage::parse_filter_query("#a | ", &saved);
+    assert_eq!(filter.invalid_tokens[0].span, 3..4);
+    let filter = caliber::storage::parse_filter_query("#a (#b !bogus)", &saved);
+    assert_eq!(filter.invalid_tokens[0].text, "!bogus");
+    assert_eq!(filter.invalid_tokens[0].span, 7..13);
+}
+
+#[test]
+fn query_scopes_span_hub_and_registered_projects() {
+    let env = CliEnv::new();
+    env.write_hub("# 2026/01/10\n- [ ] Pay rent\n");
+    let caliber_dir = env.temp_dir.path().join("widgets/.caliber");
+    std::fs::create_dir_all(&caliber_dir).unwrap();
+    std::fs::write(
+        caliber_dir.join("journal.md"),
+        "# 2026/01/09\n- [ ] Ship widget #work\n- Spec notes\n",
+    )
+    .unwrap();
+    std::fs::write(
+        env.config_dir().join("projects.toml"),
+        format!("[[project]]\npath = {:?}\n", caliber_dir),
+    )
+    .unwrap();
+
+    let output = env.run(&["query", "--format", "json", "@all", "!tasks"]);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2, "{stdout}");
+    assert!(lines[0].contains("Ship widget"));
+    assert!(lines[0].contains(r#""journal":"widgets""#));
+    assert!(lines[1].contains("Pay rent"));
+    assert!(lines[1].contains(r#""journal":"hub""#));
+
+    let output = env.run(&["query", "@project:widgets"]);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("2026/01/09  widgets  0  task"), "{stdout}");
+    assert!(!stdout.contains("Pay rent"));
+
+    let output = env.run(&["query", "@project:gadgets"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown project: gadgets"));
+}
+
+#[test]
+fn serve_stdio_answers_mcp_tool_calls() {
+    let env = CliEnv::new();
+    env.write_hub("# 2026/01/10\n- [ ] Ship release #work\n- A note #idea\n");
+
+    let requests = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+        r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#,
+        r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"toggle_complete","arguments":{"date":"2026-01-10","line_index":0}}}"#,
+        r#"{"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"toggle_complete","arguments":{"date":"2026-01-10","line_index":1}}}"#,
+        r#"{"jsonrpc":"2.0","id":5,"method":"tools/call","params":{"name":"add_entry","arguments":{"text":"Follow up #work","date":"2026-01-11"}}}"#,
+        r#"{"jsonrpc":"2.0","id":6,"method":"tools/call","params":{"name":"list_tags","arguments":{}}}"#,
+        r#"{"jsonrpc":"2.0","id":7,"method":"bogus"}"#,
+        "not json",
+        r#"{"jsonrpc":"2.0","id":8,"method":"tools/call","params":{"name":"toggle_complete","arguments":{"date":"2026-01-10","line_index":9}}}"#,
+    ];
+    let output = env.run_with_stdin(&["serve", "--stdio"], &(requests.join("\n") + "\n"));
+    assert!(output.status.success(), "{output:?}");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let responses: Vec<&str> = stdout.lines().collect();
+    // The initialized notification gets no response
+    assert_eq!(responses.len(), 9);
+
+    assert!(responses[0].contains(r#""protocolVersion""#));
+    for tool in [
+        "list_days",
+        "query",
+        "add_entry",
+        "toggle_complete",
+        "list_tags",
+    ] {
+        assert!(responses[1].contains(&format!(r#""name":"{tool}""#)));
+    }
+    assert!(responses[2].contains(r#""isError":false"#));
+    assert!(responses[2].contains(r#""completed":true"#));
+    assert!(responses[3].contains(r#""isError":true"#));
+    assert!(responses[3].contains("is not a task"));
+    assert!(responses[4].contains(r#""line_index":0"#));
+    assert!(responses[5].contains(r#""tags":["idea","work"]"#));
+    assert!(responses[6].contains("-32601"));
+    assert!(responses[7].contains("-32700"));
+    assert!(responses[8].contains(r#""isError":true"#));
+    assert!(responses[8].contains("No entry at 9 on 2026-01-10"));
+
+    let journal = env.read_hub();
+    assert!(journal.contains("- [x] Ship release #work"));
+    assert!(journal.contains("# 2026/01/11\n- [ ] Follow up #work"));
+}
+
+#[test]
+fn doctor_reports_issues_and_fix_rewrites_canonical_journal() {
+    let env = CliEnv::new();
+    env.write_hub(
+        "# 2026/01/16\r\n- [X] Shipped\r\n\n# 2026/01/15\n- Note <!-- done: 2026-01-15 -->\n\n# 2026/01/16\n- [ ] Follow up\n",
+    );
+
+    let output = env.run(&["doctor"]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("line 2: uppercase checkbox"), "{stdout}");
+    assert!(
+        stdout.contains("line 4: day 2026/01/15 is out of order"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("line 7: duplicate day header 2026/01/16"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("CRLF"), "{stdout}");
+    assert!(stdout.contains("isn't recurring"), "{stdout}");
+
+    let output = env.run(&["doctor", "--fix"]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        env.read_hub(),
+        "# 2026/01/15\n- Note\n\n# 2026/01/16\n- [x] Shipped\n- [ ] Follow up\n"
+    );
+
+    let output = env.run(&["doctor"]);
+    assert!(output.status.success(), "{output:?}");
+}
+
+#[test]
+fn doctor_leaves_unknown_recurring_patterns_for_manual_fix() {
+    let env = CliEnv::new();
+    env.write_hub("# 2026/01/15\n- [ ] Standup @every-fortnight\n");
+
+    let output = env.run(&["doctor", "--fix"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("need manual attention"));
+    assert_eq!(
+        env.read_hub(),
+        "# 2026/01/15\n- [ ] Standup @every-fortnight\n"
+    );
+}
+
+#[test]
+fn add_fails_cleanly_while_journal_is_locked() {
+    let env = CliEnv::new();
+    env.write_hub("# 2026/01/15\n- [ ] Existing\n");
+
+    let lock = caliber::storage::lock_journal(&env.hub_path()).unwrap();
+    let output = env.run(&["add", "--date", "2026/01/15", "Blocked"]);
+    drop(lock);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("locked"));
+    assert_eq!(env.read_hub(), "# 2026/01/15\n- [ ] Existing\n");
+}
+
+#[test]
+fn migrate_round_trips_between_layouts() {
+    let env = CliEnv::new();
+    let journal = "# 2026/01/14\n- [ ] First #work\n\n# 2026/01/15\n* Standup\n- Note\n";
+    env.write_hub(journal);
+
+    let output = env.run(&["migrate", "daily_files"]);
+    assert!(output.status.success(), "{output:?}");
+    let day_dir = env.config_dir().join("hub_journal");
+    assert_eq!(
+        std::fs::read_to_string(day_dir.join("2026/01/15.md")).unwrap(),
+        "* Standup\n- Note\n"
+    );
+    assert!(!env.hub_path().exists());
+    assert!(env.config_dir().join("hub_journal.md.bak").exists());
+
+    env.write_config("journal_layout = \"daily_files\"\n");
+    let output = env.run(&["add", "--date", "2026/01/16", "New"]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        std::fs::read_to_string(day_dir.join("2026/01/16.md")).unwrap(),
+        "- [ ] New\n"
+    );
+
+    let output = env.run(&["query", "--format", "json", "#work"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("First #work"));
+
+    let output = env.run(&["migrate", "single_file"]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        env.read_hub(),
+        format!("{journal}\n# 2026/01/16\n- [ ] New\n")
+    );
+    assert!(env.config_dir().join("hub_journal.bak").is_dir());
+}
+
+#[test]
+fn migrate_joins_repeated_day_sections() {
+    let env = CliEnv::new();
+    env.write_hub(
+        "# 2026/01/15\n- [ ] Morning\n\n# 2026/01/14\n- Note\n\n# 2026/01/15\n- [x] Evening\n",
+    );
+
+    let output = env.run(&["migrate", "daily_files"]);
+    assert!(output.status.success(), "{output:?}");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Migrated 2 day(s)"));
+    let day_dir = env.config_dir().join("hub_journal");
+    assert_eq!(
+        std::fs::read_to_string(day_dir.join("2026/01/15.md")).unwrap(),
+        "- [ ] Morning\n- [x] Evening\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(day_dir.join("2026/01/14.md")).unwrap(),
+        "- Note\n"
+    );
+}
+
+#[test]
+fn migrate_refuses_to_overwrite_existing_days() {
+    let env = CliEnv::new();
+    env.write_hub("# 2026/01/15\n- [ ] Task\n");
+    let day = env.config_dir().join("hub_journal/2026/01/10.md");
+    std::fs::create_dir_all(day.parent().unwrap()).unwrap();
+    std::fs::write(&day, "- Existing\n").unwrap();
+
+    let output = env.run(&["migrate", "daily_files"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already has entries"));
+    assert_eq!(env.read_hub(), "# 2026/01/15\n- [ ] Task\n");
+}
+
+#[test]
+fn query_sorts_and_groups_results() {
+    let env = CliEnv::new();
+    env.write_hub(
+        "# 2026/01/10\n- [ ] Old task #work\n- Note #home\n\n# 2026/01/12\n- [x] Done task #work\n- [ ] New task\n",
+    );
+    let contents = |args: &[&str]| -> Vec<String> {
+        let output = env.run(&[&["query", "--format", "json"], args].concat());
+        assert!(output.status.success(), "{output:?}");
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.split(r#""content":""#).nth(1).unwrap().to_string())
+            .map(|rest| rest.split('"').next().unwrap().to_string())
+            .collect()
+    };
+
+    assert_eq!(
+        contents(&["sort:date-desc"]),
+        [
+            "Done task #work",
+            "New task",
+            "Old task #work",
+            "Note #home"
+        ]
+    );
+    assert_eq!(
+        contents(&["sort:completion"]),
+        [
+            "Old task #work",
+            "New task",
+            "Done task #work",
+            "Note #home"
+        ]
+    );
+    assert_eq!(
+        contents(&["sort:date-desc", "group:tag"]),
+        [
+            "Done task #work",
+            "Old task #work",
+            "New task",
+            "Note #home"
+        ]
+    );
+
+    let output = env.run(&["query", "sort:2nd"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown filter: sort:2nd"));
+}
+
+#[test]
+fn query_sorts_sub_entries_by_their_own_content() {
+    let env = CliEnv::new();
+    env.write_hub(
+        "# 2026/01/10\n- Launch plan #work\n  - [ ] Book venue #errand\n  - [ ] Draft invite #work\n- [ ] Buy milk #errand\n",
+    );
+    let contents = |args: &[&str]| -> Vec<String> {
+        let output = env.run(&[&["query", "--format", "json"], args].concat());
+        assert!(output.status.success(), "{output:?}");
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.split(r#""content":""#).nth(1).unwrap().to_string())
+            .map(|rest| rest.split('"').next().unwrap().to_string())
+            .collect()
+    };
+
+    // Date sorts keep each sub-entry below its parent
+    assert_eq!(
+        contents(&["sort:date-desc"]),
+        [
+            "Launch plan #work",
+            "Book venue #errand",
+            "Draft invite #work",
+            "Buy milk #errand"
+        ]
+    );
+    // Other sorts place a sub-entry by its own content, away from its parent
+    assert_eq!(
+        contents(&["sort:tag"]),
+        [
+            "Book venue #errand",
+            "Buy milk #errand",
+            "Launch plan #work",
+            "Draft invite #work"
+        ]
+    );
+    assert_eq!(
+        contents(&["sort:type"]),
+        [
+            "Book venue #errand",
+            "Draft invite #work",
+            "Buy milk #errand",
+            "Launch plan #work"
+        ]
+    );
+}
+
+#[test]
+fn query_matches_patterns_phrases_and_fuzzy_terms() {
+    let env = CliEnv::new();
+    env.write_hub(
+        "# 2026/01/09\n- [ ] Call Rachel about shop\n\n# 2026/01/10\n- [ ] Fix PROJ-12 crash\n- Review the code tomorrow\n- [ ] Code review for PROJ-7\n- [ ] Reviewed budget\n",
+    );
+    let contents = |args: &[&str]| -> Vec<String> {
+        let output = env.run(&[&["query", "--format", "json"], args].concat());
+        assert!(output.status.success(), "{output:?}");
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.split(r#""content":""#).nth(1).unwrap().to_string())
+            .map(|rest| rest.split('"').next().unwrap().to_string())
+            .collect()
+    };
+
+    assert_eq!(
+        contents(&[r"re:/PROJ-\d+ (crash|for)/"]),
+        ["Fix PROJ-12 crash"]
+    );
+    assert_eq!(contents(&[r"re:/PROJ-\d$/"]), ["Code review for PROJ-7"]);
+    assert_eq!(contents(&[r#""code review""#]), ["Code review for PROJ-7"]);
+    assert_eq!(
+        contents(&[r#"-"code review""#, "review"]),
+        ["Review the code tomorrow", "Reviewed budget"]
+    );
+
+    // Fuzzy terms rank the closest match first
+    assert_eq!(contents(&["~revbud"]), ["Reviewed budget"]);
+    assert_eq!(
+        contents(&["~crash"]),
+        ["Fix PROJ-12 crash", "Call Rachel about shop"]
+    );
+    assert_eq!(
+        contents(&["~crash", "sort:date"]),
+        ["Call Rachel about shop", "Fix PROJ-12 crash"]
+    );
+
+    // Characters that lowercase to several characters still match themselves
+    let term = caliber::storage::SearchTerm::Fuzzy("İstanbul".to_string());
+    assert!(term.is_match("Trip to İstanbul"));
+    assert_eq!(term.find_ranges("Trip to İstanbul"), vec![8..17]);
+
+    let output = env.run(&["query", "re:/(/"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown filter: re:/(/"));
+}
+
+#[test]
+fn query_filters_on_completion_and_creation_dates() {
+    let env = CliEnv::new();
+    let today = chrono::Local::now().date_naive();
+    let day = |days: u64| {
+        today
+            .checked_sub_days(chrono::Days::new(days))
+            .unwrap()
+            .format("%Y-%m-%d")
+            .to_string()
+    };
+    env.write_hub(&format!(
+        "# {}\n- [ ] Stale chore <!-- created: {} -->\n- [x] Old win <!-- completed: {} -->\n\n\
+         # {}\n- [x] Fresh win <!-- created: {} --> <!-- completed: {} -->\n- [ ] New idea\n",
+        day(30).replace('-', "/"),
+        day(40),
+        day(20),
+        day(3).replace('-', "/"),
+        day(3),
+        day(1),
+    ));
+    let contents = |args: &[&str]| -> Vec<String> {
+        let output = env.run(&[&["query", "--format", "json"], args].concat());
+        assert!(output.status.success(), "{output:?}");
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.split(r#""content":""#).nth(1).unwrap().to_string())
+            .map(|rest| rest.split('"').next().unwrap().to_string())
+            .collect()
+    };
+
+    assert_eq!(contents(&["done:d7.."]), ["Fresh win"]);
+    assert_eq!(contents(&["done:..d7"]), ["Old win"]);
+    assert_eq!(contents(&["created:..d35"]), ["Stale chore"]);
+    // Entries without a creation date count from the day they're on
+    assert_eq!(contents(&["age:>14"]), ["Stale chore", "Old win"]);
+    assert_eq!(contents(&["age:<=3"]), ["Fresh win", "New idea"]);
+    assert_eq!(contents(&["age:3"]), ["Fresh win", "New idea"]);
+    assert_eq!(
+        contents(&["sort:age"]),
+        ["Stale chore", "Old win", "Fresh win", "New idea"]
+    );
+
+    let output = env.run(&["query", "age:soon"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn query_filters_and_sorts_by_properties() {
+    let env = CliEnv::new();
+    env.write_hub(
+        "# 2026/01/10\n- [ ] Fix login client:acme est:2h\n- [ ] Write docs est:45m\n\n\
+         # 2026/01/11\n- [ ] Plan launch client:Globex est:1d\n- [ ] Read https://example.com today\n- [ ] Review pr:1234 est:1h30m\n",
+    );
+    let contents = |args: &[&str]| -> Vec<String> {
+        let output = env.run(&[&["query", "--format", "json"], args].concat());
+        assert!(output.status.success(), "{output:?}");
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.split(r#""content":""#).nth(1).unwrap().to_string())
+            .map(|rest| rest.split('"').next().unwrap().to_string())
+            .collect()
+    };
+
+    assert_eq!(contents(&["client:ACME"]), ["Fix login client:acme est:2h"]);
+    assert_eq!(
+        contents(&["est:>1h"]),
+        [
+            "Fix login client:acme est:2h",
+            "Plan launch client:Globex est:1d",
+            "Review pr:1234 est:1h30m"
+        ]
+    );
+    assert_eq!(contents(&["est:<=90m", "-client:*"]).len(), 2);
+    assert_eq!(contents(&["pr:>1000"]), ["Review pr:1234 est:1h30m"]);
+    // URLs aren't properties
+    assert!(contents(&["https:*"]).is_empty());
+    // A plain key:value still matches as text, and by value where the text differs
+    assert_eq!(contents(&["pr:12"]), ["Review pr:1234 est:1h30m"]);
+    assert_eq!(contents(&["est:120m"]), ["Fix login client:acme est:2h"]);
+
+    assert_eq!(
+        contents(&["sort:est"]),
+        [
+            "Write docs est:45m",
+            "Review pr:1234 est:1h30m",
+            "Fix login client:acme est:2h",
+            "Plan launch client:Globex est:1d",
+            "Read https://example.com today"
+        ]
+    );
+    assert_eq!(
+        contents(&["sort:est-desc"])[..2],
+        [
+            "Plan launch client:Globex est:1d",
+            "Fix login client:acme est:2h"
+        ]
+    );
+
+    let output = env.run(&["query", "--format", "json", "pr:*"]);
+    assert!(
+        String::from_utf8_lossy(&output.stdout)
+            .contains(r#""properties":{"est":"1h30m","pr":"1234"}"#)
+    );
+}
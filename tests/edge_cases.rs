@@ -128,3 +128,75 @@ fn entry_types_round_trip_through_persist() {
 
     ctx.verify_invariants();
 }
+
+#[test]
+fn contended_lock_surfaces_error_and_leaves_journal_untouched() {
+    let date = chrono::NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/15\n- [ ] Task\n";
+    let mut ctx = TestContext::with_journal_content(date, content);
+
+    let lock = caliber::storage::lock_journal(&ctx.journal_path()).unwrap();
+
+    // Creating an entry saves through the locked read-modify-write path
+    ctx.press(KeyCode::Char('o'));
+    ctx.type_str("Blocked");
+    ctx.press(KeyCode::Enter);
+    assert!(ctx.status_contains("locked by another caliber process"));
+    assert_eq!(ctx.read_journal(), content);
+
+    drop(lock);
+    ctx.press(KeyCode::Char('o'));
+    ctx.type_str("Saved");
+    ctx.press(KeyCode::Enter);
+    assert!(ctx.read_journal().contains("- [ ] Saved"));
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinked_journal_paths_share_one_lock() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let real_path = temp_dir.path().join("real.md");
+    let link_path = temp_dir.path().join("journal.md");
+    std::fs::write(&real_path, "# 2026/01/15\n- [ ] Task\n").unwrap();
+    std::os::unix::fs::symlink(&real_path, &link_path).unwrap();
+
+    assert_eq!(
+        caliber::storage::lock_path(&link_path),
+        caliber::storage::lock_path(&real_path)
+    );
+    let _held = caliber::storage::lock_journal(&link_path).unwrap();
+    let err = caliber::storage::lock_journal(&real_path).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::WouldBlock);
+}
+
+#[cfg(unix)]
+#[test]
+fn atomic_save_keeps_symlinked_journal_and_leaves_no_temp_files() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let real_path = temp_dir.path().join("real.md");
+    let link_path = temp_dir.path().join("journal.md");
+    std::fs::write(&real_path, "# 2026/01/15\n- [ ] Task\n").unwrap();
+    std::os::unix::fs::symlink(&real_path, &link_path).unwrap();
+
+    let date = chrono::NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    caliber::storage::toggle_entry_complete(
+        &caliber::storage::MarkdownStore,
+        date,
+        &link_path,
+        0,
+        None,
+    )
+    .unwrap();
+
+    assert!(link_path.symlink_metadata().unwrap().is_symlink());
+    assert_eq!(
+        std::fs::read_to_string(&real_path).unwrap(),
+        "# 2026/01/15\n- [x] Task\n"
+    );
+    let leftovers: Vec<_> = std::fs::read_dir(temp_dir.path())
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name().to_string_lossy().ends_with(".tmp"))
+        .collect();
+    assert!(leftovers.is_empty());
+}
@@ -1,18 +1,32 @@
 #![allow(dead_code)]
 
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use chrono::NaiveDate;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use tempfile::TempDir;
 
 use caliber::app::{App, InputMode, ViewMode};
-use caliber::config::Config;
+use caliber::config::{Config, JournalLayout};
 use caliber::handlers;
-use caliber::storage::{JournalContext, JournalSlot};
+use caliber::storage::{JournalContext, JournalSlot, MemoryStore};
 use caliber::ui;
 use caliber::ui::surface::Surface;
 
+/// Skips the clipboard and project registry, then makes the temp dir every
+/// fixture starts from and the journal path inside it.
+fn temp_journal() -> (TempDir, PathBuf) {
+    // SAFETY: Tests run single-threaded per test file, env var is set before any other work
+    unsafe {
+        std::env::set_var("CALIBER_SKIP_CLIPBOARD", "1");
+        std::env::set_var("CALIBER_SKIP_REGISTRY", "1");
+    }
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let journal_path = temp_dir.path().join("test_journal.md");
+    (temp_dir, journal_path)
+}
+
 pub struct TestContext {
     pub app: App,
     pub temp_dir: TempDir,
@@ -20,47 +34,78 @@ pub struct TestContext {
 
 impl TestContext {
     pub fn new() -> Self {
-        // SAFETY: Tests run single-threaded per test file, env var is set before any other work
-        unsafe {
-            std::env::set_var("CALIBER_SKIP_CLIPBOARD", "1");
-            std::env::set_var("CALIBER_SKIP_REGISTRY", "1");
-        }
         Self::with_date(NaiveDate::from_ymd_opt(2026, 1, 15).unwrap())
     }
 
     pub fn with_date(date: NaiveDate) -> Self {
-        // SAFETY: Tests run single-threaded per test file, env var is set before any other work
-        unsafe {
-            std::env::set_var("CALIBER_SKIP_CLIPBOARD", "1");
-            std::env::set_var("CALIBER_SKIP_REGISTRY", "1");
-        }
-        let temp_dir = TempDir::new().expect("Failed to create temp dir");
-        let journal_path = temp_dir.path().join("test_journal.md");
-        std::fs::write(&journal_path, "").expect("Failed to create journal");
+        Self::with_journal_content(date, "")
+    }
 
-        let context = JournalContext::new(journal_path, None, JournalSlot::Hub);
+    pub fn with_journal_content(date: NaiveDate, content: &str) -> Self {
+        Self::with_config_and_content(date, content, Config::default())
+    }
 
-        let config = Config::default();
+    pub fn with_config_and_content(date: NaiveDate, content: &str, config: Config) -> Self {
+        let (temp_dir, journal_path) = temp_journal();
+        std::fs::write(&journal_path, content).expect("Failed to write journal");
+
+        let context = JournalContext::new(journal_path, None, JournalSlot::Hub);
         let app = App::new_with_context(config, date, context, None, Surface::default())
             .expect("Failed to create app");
 
         Self { app, temp_dir }
     }
 
-    pub fn with_journal_content(date: NaiveDate, content: &str) -> Self {
-        Self::with_config_and_content(date, content, Config::default())
+    /// Runs the app against an in-memory store seeded with `content`. The journal
+    /// path is still inside the temp dir but is never created.
+    pub fn with_memory_store(date: NaiveDate, content: &str) -> (Self, Arc<MemoryStore>) {
+        let (temp_dir, journal_path) = temp_journal();
+        let store = Arc::new(MemoryStore::new());
+        store.insert_markdown(&journal_path, content);
+
+        let context = JournalContext::new(journal_path, None, JournalSlot::Hub);
+        let app = App::new_with_store(
+            Config::default(),
+            date,
+            context,
+            store.clone(),
+            None,
+            Surface::default(),
+        )
+        .expect("Failed to create app");
+
+        (Self { app, temp_dir }, store)
     }
 
-    pub fn with_config_and_content(date: NaiveDate, content: &str, config: Config) -> Self {
-        // SAFETY: Tests run single-threaded per test file, env var is set before any other work
-        unsafe {
-            std::env::set_var("CALIBER_SKIP_CLIPBOARD", "1");
-            std::env::set_var("CALIBER_SKIP_REGISTRY", "1");
-        }
-        let temp_dir = TempDir::new().expect("Failed to create temp dir");
-        let journal_path = temp_dir.path().join("test_journal.md");
+    /// Opens `content` as the active project journal, with a separate hub
+    /// journal holding `hub_content`.
+    pub fn with_project_journal(date: NaiveDate, content: &str, hub_content: &str) -> Self {
+        let (temp_dir, journal_path) = temp_journal();
+        let hub_path = temp_dir.path().join("hub_journal.md");
         std::fs::write(&journal_path, content).expect("Failed to write journal");
+        std::fs::write(&hub_path, hub_content).expect("Failed to write hub journal");
+
+        let context = JournalContext::new(hub_path, Some(journal_path), JournalSlot::Project);
+        let app = App::new_with_context(Config::default(), date, context, None, Surface::default())
+            .expect("Failed to create app");
 
+        Self { app, temp_dir }
+    }
+
+    /// Uses the `daily_files` layout, writing each `(relative path, content)` under
+    /// the journal folder first.
+    pub fn with_daily_files(date: NaiveDate, files: &[(&str, &str)]) -> Self {
+        let (temp_dir, journal_path) = temp_journal();
+        for (relative, content) in files {
+            let file = temp_dir.path().join("test_journal").join(relative);
+            std::fs::create_dir_all(file.parent().unwrap()).expect("Failed to create day dir");
+            std::fs::write(file, content).expect("Failed to write day file");
+        }
+
+        let config = Config {
+            journal_layout: JournalLayout::DailyFiles,
+            ..Config::default()
+        };
         let context = JournalContext::new(journal_path, None, JournalSlot::Hub);
         let app = App::new_with_context(config, date, context, None, Surface::default())
             .expect("Failed to create app");
@@ -98,6 +143,9 @@ impl TestContext {
             InputMode::Confirm(_) => {
                 let _ = handlers::handle_confirm_key(&mut self.app, key.code);
             }
+            InputMode::Conflict(_) => {
+                let _ = handlers::handle_conflict_key(&mut self.app, key.code);
+            }
             InputMode::Selection(_) => {
                 let _ = handlers::handle_selection_key(&mut self.app, key);
             }
@@ -165,6 +213,10 @@ impl TestContext {
         std::fs::read_to_string(self.journal_path()).unwrap_or_default()
     }
 
+    pub fn read_hub(&self) -> String {
+        std::fs::read_to_string(self.temp_dir.path().join("hub_journal.md")).unwrap_or_default()
+    }
+
     pub fn cursor_position(&self) -> Option<usize> {
         self.app.edit_buffer.as_ref().map(|b| b.cursor_char_pos())
     }
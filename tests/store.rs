@@ -0,0 +1,218 @@
+mod helpers;
+
+use caliber::storage::{
+    self, DailyFilesStore, JournalStore, Line, MarkdownStore, MemoryStore, parse_filter_query,
+};
+use chrono::NaiveDate;
+use crossterm::event::KeyCode;
+use helpers::TestContext;
+
+#[test]
+fn memory_store_round_trips_markdown() {
+    let store = MemoryStore::new();
+    let path = std::path::Path::new("journal.md");
+    let content = "# 2026/01/14\n- [ ] Older\n\n# 2026/01/15\n- [x] Done\n* Event #work\n";
+    store.insert_markdown(path, content);
+
+    assert_eq!(store.to_markdown(path), content);
+
+    let date = NaiveDate::from_ymd_opt(2026, 1, 14).unwrap();
+    store.save_day_lines(date, path, &[]).unwrap();
+    assert_eq!(
+        store.to_markdown(path),
+        "# 2026/01/15\n- [x] Done\n* Event #work\n"
+    );
+}
+
+#[test]
+fn memory_and_markdown_stores_agree() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/15\n- [ ] Task #work\n- Note\n";
+    let dir = tempfile::TempDir::new().unwrap();
+    let file = dir.path().join("journal.md");
+    std::fs::write(&file, content).unwrap();
+    let memory = MemoryStore::new();
+    memory.insert_markdown(&file, content);
+
+    for store in [&MarkdownStore as &dyn JournalStore, &memory] {
+        storage::toggle_entry_complete(store, date, &file, 0, None).unwrap();
+        let lines = store.load_day_lines(date, &file).unwrap();
+        assert!(matches!(&lines[0], Line::Entry(e) if e.content == "Task #work"));
+        assert_eq!(
+            storage::get_entry_type(store, date, &file, 0),
+            storage::EntryType::Task {
+                state: storage::TaskState::Done
+            }
+        );
+
+        let filter = parse_filter_query("#work", &Default::default());
+        let found = storage::collect_filtered_entries(store, &filter, &file).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].line_index, 0);
+    }
+
+    assert_eq!(
+        memory.to_markdown(&file),
+        std::fs::read_to_string(&file).unwrap()
+    );
+}
+
+#[test]
+fn app_runs_against_memory_store_without_touching_disk() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let (mut ctx, store) = TestContext::with_memory_store(date, "# 2026/01/15\n- [ ] Existing\n");
+    let path = ctx.journal_path();
+
+    ctx.press(KeyCode::Char(' '));
+    assert!(ctx.screen_contains("[x]"));
+
+    ctx.press(KeyCode::Enter);
+    ctx.type_str("Added in memory");
+    ctx.press(KeyCode::Enter);
+    assert!(ctx.screen_contains("Added in memory"));
+
+    let markdown = store.to_markdown(&path);
+    assert!(markdown.contains("- [x] Existing"));
+    assert!(markdown.contains("- [ ] Added in memory"));
+    assert!(!path.exists());
+    ctx.verify_invariants();
+}
+
+#[test]
+fn daily_files_store_keeps_one_file_per_day() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("journal.md");
+    let store = DailyFilesStore;
+    let jan15 = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let dec31 = NaiveDate::from_ymd_opt(2025, 12, 31).unwrap();
+
+    let lines = storage::parse_lines("- [ ] Task #work\n- [ ] Daily @every-day");
+    store.save_day_lines(jan15, &path, &lines).unwrap();
+    store
+        .save_day_lines(dec31, &path, &storage::parse_lines("* Party"))
+        .unwrap();
+    std::fs::write(dir.path().join("journal/2026/notes.md"), "- ignored").unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("journal/2026/01/15.md")).unwrap(),
+        "- [ ] Task #work\n- [ ] Daily @every-day\n"
+    );
+    let days: Vec<_> = store.days(&path).unwrap().iter().map(|d| d.date).collect();
+    assert_eq!(days, vec![dec31, jan15]);
+
+    let jan16 = NaiveDate::from_ymd_opt(2026, 1, 16).unwrap();
+    let projected = storage::collect_projected_entries_for_date(&store, jan16, &path).unwrap();
+    assert_eq!(projected.len(), 1);
+    assert!(
+        storage::scan_recurring_in_range(&store, jan15, jan16, &path)
+            .unwrap()
+            .contains(&jan16)
+    );
+    assert_eq!(
+        store.scan_days_in_range(dec31, dec31, &path).unwrap().len(),
+        1
+    );
+
+    store.save_day_lines(dec31, &path, &[]).unwrap();
+    assert!(!dir.path().join("journal/2025").exists());
+}
+
+#[test]
+fn app_deletes_tags_across_daily_files() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let mut ctx = TestContext::with_daily_files(
+        date,
+        &[
+            ("2026/01/15.md", "- [ ] Ship #release\n"),
+            ("2026/01/10.md", "- [x] Plan #release\n- Keep\n"),
+        ],
+    );
+    assert!(ctx.screen_contains("Ship #release"));
+
+    ctx.app.confirm_delete_tag("release").unwrap();
+
+    let dir = ctx.temp_dir.path().join("test_journal");
+    assert_eq!(
+        std::fs::read_to_string(dir.join("2026/01/10.md")).unwrap(),
+        "- [x] Plan\n- Keep\n"
+    );
+    assert!(ctx.screen_contains("Ship"));
+    assert!(!ctx.screen_contains("#release"));
+    assert!(!ctx.journal_path().exists());
+    ctx.verify_invariants();
+}
+
+#[test]
+fn tag_rewrites_keep_repeated_day_sections_apart() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/10\n- [ ] First #release\n- Note\n\n\
+                   # 2026/01/15\n- [ ] Today\n\n\
+                   # 2026/01/10\n- [x] Second #release\n- [ ] Only #release\n";
+    let mut ctx = TestContext::with_journal_content(date, content);
+
+    ctx.app.confirm_delete_tag("release").unwrap();
+
+    assert_eq!(
+        ctx.read_journal(),
+        "# 2026/01/10\n- [ ] First\n- Note\n\n\
+         # 2026/01/15\n- [ ] Today\n\n\
+         # 2026/01/10\n- [x] Second\n- [ ] Only\n"
+    );
+    ctx.verify_invariants();
+}
+
+#[test]
+fn markdown_store_cache_tracks_writes_and_external_edits() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("journal.md");
+    let content = "Intro text\n# 2026/01/14\n- [ ] Older\n\n# 2026/01/15\n- [ ] Today\nraw line\n";
+    std::fs::write(&path, content).unwrap();
+    let store = MarkdownStore;
+    let jan14 = NaiveDate::from_ymd_opt(2026, 1, 14).unwrap();
+    let jan15 = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+
+    storage::toggle_entry_complete(&store, jan15, &path, 0, None).unwrap();
+    let on_disk = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        on_disk,
+        "Intro text\n# 2026/01/14\n- [ ] Older\n\n# 2026/01/15\n- [x] Today\nraw line\n"
+    );
+    assert_eq!(
+        store.days(&path).unwrap(),
+        storage::parse_journal_days(&on_disk)
+    );
+
+    std::fs::write(&path, "# 2026/01/14\n- [ ] Edited elsewhere\n").unwrap();
+    let lines = store.load_day_lines(jan14, &path).unwrap();
+    assert!(matches!(&lines[0], Line::Entry(e) if e.content == "Edited elsewhere"));
+    assert!(store.load_day_lines(jan15, &path).unwrap().is_empty());
+
+    std::fs::remove_file(&path).unwrap();
+    assert!(store.days(&path).unwrap().is_empty());
+}
+
+#[test]
+fn markdown_store_edits_can_read_the_store() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("journal.md");
+    std::fs::write(
+        &path,
+        "# 2026/01/14\n- [ ] Older\n\n# 2026/01/15\n- [ ] Today\n",
+    )
+    .unwrap();
+    let store = MarkdownStore;
+    let jan14 = NaiveDate::from_ymd_opt(2026, 1, 14).unwrap();
+    let jan15 = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+
+    // Copy yesterday's entries into today from inside the edit
+    storage::modify_day_lines(&store, jan15, &path, |lines| {
+        lines.extend(store.load_day_lines(jan14, &path).unwrap());
+    })
+    .unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "# 2026/01/14\n- [ ] Older\n\n# 2026/01/15\n- [ ] Today\n- [ ] Older\n"
+    );
+    assert_eq!(store.load_day_lines(jan15, &path).unwrap().len(), 2);
+}
@@ -1,5 +1,6 @@
 mod helpers;
 
+use caliber::app::InputMode;
 use chrono::NaiveDate;
 use crossterm::event::KeyCode;
 use helpers::TestContext;
@@ -104,3 +105,138 @@ fn scroll_keeps_selection_visible() {
 
     ctx.verify_invariants();
 }
+
+#[test]
+fn merge3_combines_non_overlapping_changes_and_flags_overlaps() {
+    use caliber::storage::{ConflictResolution, MergeChunk, merge3, resolve_chunks};
+
+    let base = ["a", "b", "c"];
+    let mine = ["a2", "b", "c"];
+    let theirs = ["a", "b", "c", "d"];
+    let chunks = merge3(&base, &mine, &theirs);
+    assert_eq!(
+        chunks,
+        vec![MergeChunk::Resolved(vec!["a2", "b", "c", "d"])]
+    );
+
+    let theirs = ["a3", "b", "c"];
+    let chunks = merge3(&base, &mine, &theirs);
+    assert!(matches!(chunks[0], MergeChunk::Conflict { .. }));
+    assert_eq!(
+        resolve_chunks(&chunks, ConflictResolution::Both),
+        vec!["a2", "a3", "b", "c"]
+    );
+}
+
+#[test]
+fn save_merges_external_change_to_another_line() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/15\n- [ ] A\n- [ ] B\n- [ ] C\n";
+    let mut ctx = TestContext::with_journal_content(date, content);
+
+    // Another tool edits C while caliber still shows the old day
+    std::fs::write(
+        ctx.journal_path(),
+        "# 2026/01/15\n- [ ] A\n- [ ] B\n- [ ] C from phone\n",
+    )
+    .unwrap();
+
+    ctx.press(KeyCode::Char('g'));
+    ctx.press(KeyCode::Char(' '));
+
+    assert_eq!(
+        ctx.read_journal(),
+        "# 2026/01/15\n- [x] A\n- [ ] B\n- [ ] C from phone\n"
+    );
+    assert!(ctx.status_contains("Merged"));
+    assert!(ctx.screen_contains("C from phone"));
+    ctx.verify_invariants();
+}
+
+#[test]
+fn overlapping_external_change_opens_conflict_overlay() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/15\n- [ ] A\n- [ ] B\n";
+    let mut ctx = TestContext::with_journal_content(date, content);
+
+    std::fs::write(
+        ctx.journal_path(),
+        "# 2026/01/15\n- [ ] A edited\n- [ ] B\n",
+    )
+    .unwrap();
+
+    ctx.press(KeyCode::Char('g'));
+    ctx.press(KeyCode::Char(' '));
+
+    // Nothing is written until the conflict is resolved
+    assert_eq!(
+        ctx.read_journal(),
+        "# 2026/01/15\n- [ ] A edited\n- [ ] B\n"
+    );
+    assert!(matches!(ctx.app.input_mode, InputMode::Conflict(_)));
+
+    ctx.press(KeyCode::Char('b'));
+    assert_eq!(
+        ctx.read_journal(),
+        "# 2026/01/15\n- [x] A\n- [ ] A edited\n- [ ] B\n"
+    );
+    assert!(ctx.status_contains("Kept both versions"));
+    assert_eq!(ctx.entry_count(), 3);
+    ctx.verify_invariants();
+}
+
+#[test]
+fn external_change_reloads_when_nothing_is_unsaved() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/15\n- [ ] A\n";
+    let mut ctx = TestContext::with_journal_content(date, content);
+
+    // Ensure the new mtime is observable
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    std::fs::write(ctx.journal_path(), "# 2026/01/15\n- [ ] A\n- [ ] B\n").unwrap();
+
+    assert!(ctx.app.check_external_changes());
+    assert!(ctx.screen_contains("B"));
+    assert_eq!(ctx.read_journal(), "# 2026/01/15\n- [ ] A\n- [ ] B\n");
+}
+
+#[test]
+fn merge_keeps_undo_history_for_other_days() {
+    use caliber::app::actions::AppendTag;
+    use caliber::app::{EntryLocation, ViewMode};
+
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/10\n- [ ] Water plants @every-day\n\n# 2026/01/15\n- [ ] A\n- [ ] B\n";
+    let mut ctx = TestContext::with_journal_content(date, content);
+
+    // Tag the recurring entry, which lives on another day
+    let ViewMode::Daily(state) = &ctx.app.view else {
+        panic!("expected daily view");
+    };
+    let projected = state.projected_entries[0].clone();
+    let original = projected.content.clone();
+    ctx.app
+        .execute_action(Box::new(AppendTag::single(
+            EntryLocation::Projected(projected),
+            original,
+            "home".to_string(),
+        )))
+        .unwrap();
+    assert!(ctx.read_journal().contains("Water plants @every-day #home"));
+
+    // Another tool edits this day, and the next save merges with it
+    let external = ctx
+        .read_journal()
+        .replace("- [ ] A\n", "- [ ] A from phone\n");
+    std::fs::write(ctx.journal_path(), external).unwrap();
+    ctx.press(KeyCode::Char('G'));
+    ctx.press(KeyCode::Char(' '));
+    assert!(ctx.status_contains("Merged"));
+    assert!(ctx.read_journal().contains("- [ ] A from phone\n- [x] B\n"));
+
+    // The tag on the other day can still be undone
+    ctx.app.undo();
+    let journal = ctx.read_journal();
+    assert!(journal.contains("- [ ] Water plants @every-day\n"));
+    assert!(journal.contains("- [ ] A from phone\n- [x] B\n"));
+}
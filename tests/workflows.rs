@@ -163,6 +163,105 @@ fn journal_switch_isolates_changes() {
     ctx.verify_invariants();
 }
 
+#[test]
+fn nested_and_unicode_tags_filter_and_show_as_tree() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/15\n- [ ] Plan sprint #work\n- [ ] Fix navbar #work/frontend\n\
+                   - [ ] Pottery class #workshop\n- [ ] Try the new place #café\n- [ ] Book flights #日本\n";
+    let mut ctx = TestContext::with_journal_content(date, content);
+
+    // Autocomplete offers nested tags once the parent is typed
+    ctx.press(KeyCode::Char('/'));
+    ctx.type_str("#work/");
+    assert!(matches!(
+        &ctx.app.hint_state,
+        caliber::app::HintContext::Tags { matches, .. } if matches == &["work/frontend"]
+    ));
+    ctx.press(KeyCode::Backspace);
+    ctx.press(KeyCode::Enter);
+    assert!(ctx.screen_contains("Plan sprint"));
+    assert!(ctx.screen_contains("Fix navbar"));
+    assert!(!ctx.screen_contains("Pottery class"));
+
+    // A trailing slash keeps only the nested tags
+    ctx.app.quick_filter("#work/").unwrap();
+    assert!(!ctx.screen_contains("Plan sprint"));
+    assert!(ctx.screen_contains("Fix navbar"));
+
+    ctx.app.quick_filter("#WORK -#work/").unwrap();
+    assert!(ctx.screen_contains("Plan sprint"));
+    assert!(!ctx.screen_contains("Fix navbar"));
+
+    ctx.app.quick_filter("#CAFÉ").unwrap();
+    assert!(ctx.screen_contains("Try the new place"));
+    ctx.app.quick_filter("#日本").unwrap();
+    assert!(ctx.screen_contains("Book flights"));
+
+    // Editing keeps nested and unicode tags in the trailing section
+    let (normalized, _) =
+        caliber::storage::normalize_entry_structure("Ship #work/frontend today #日本 #café");
+    assert_eq!(normalized, "Ship work/frontend today #日本 #café");
+
+    // The palette lists nested tags under their parent, counting the whole subtree
+    ctx.press(KeyCode::Esc);
+    ctx.press(KeyCode::Char('q'));
+    ctx.press(KeyCode::Right);
+    ctx.press(KeyCode::Right);
+    let tags: Vec<(&str, usize, usize)> = ctx
+        .app
+        .cached_journal_tags
+        .iter()
+        .map(|tag| (tag.leaf(), tag.depth(), tag.count))
+        .collect();
+    assert_eq!(
+        tags,
+        [
+            ("café", 0, 1),
+            ("work", 0, 2),
+            ("frontend", 1, 1),
+            ("workshop", 0, 1),
+            ("日本", 0, 1),
+        ]
+    );
+
+    // Deleting a parent takes its nested tags along, but not lookalikes
+    ctx.press(KeyCode::Down);
+    ctx.press(KeyCode::Char('d'));
+    ctx.press(KeyCode::Char('y'));
+    let journal = ctx.read_journal();
+    assert!(journal.contains("- [ ] Plan sprint\n"));
+    assert!(journal.contains("- [ ] Fix navbar\n"));
+    assert!(journal.contains("- [ ] Pottery class #workshop"));
+    ctx.verify_invariants();
+}
+
+#[test]
+fn properties_stay_in_trailing_section() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let mut ctx = TestContext::with_journal_content(date, "");
+
+    ctx.press(KeyCode::Enter);
+    ctx.type_str("Review pr:1234 for client:acme est:2h #work later");
+    ctx.press(KeyCode::Enter);
+    ctx.press(KeyCode::Esc);
+    ctx.press(KeyCode::Enter);
+    ctx.type_str("Fix build #work est:30m");
+    ctx.press(KeyCode::Enter);
+    ctx.press(KeyCode::Esc);
+    let journal = ctx.read_journal();
+    assert!(journal.contains("- [ ] Review pr:1234 for client:acme est:2h work later\n"));
+    assert!(journal.contains("- [ ] Fix build #work est:30m"));
+
+    // Removing tags leaves the properties beside them
+    ctx.press(KeyCode::Char('x'));
+    assert!(ctx.read_journal().contains("- [ ] Fix build est:30m"));
+
+    ctx.app.quick_filter("est:<1h").unwrap();
+    assert!(ctx.screen_contains("Fix build"));
+    assert!(!ctx.screen_contains("Review"));
+    ctx.verify_invariants();
+}
+
 #[test]
 fn delete_tag_from_completed_preserves_incomplete_tasks() {
     let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
@@ -208,3 +307,662 @@ fn delete_tag_from_completed_preserves_incomplete_tasks() {
 
     ctx.verify_invariants();
 }
+
+#[test]
+fn delete_tag_from_completed_covers_every_finished_state() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/15\n- [/] started #work\n- [-] dropped #work\n- [>] moved #work\n";
+    let mut ctx = TestContext::with_journal_content(date, content);
+
+    ctx.press(KeyCode::Char('q'));
+    ctx.press(KeyCode::Right);
+    ctx.press(KeyCode::Right);
+    ctx.press_with_modifiers(KeyCode::Char('D'), KeyModifiers::SHIFT);
+    ctx.press(KeyCode::Char('y'));
+
+    // Cancelled and migrated tasks are finished; in-progress ones aren't
+    assert_eq!(
+        ctx.read_journal(),
+        "# 2026/01/15\n- [/] started #work\n- [-] dropped\n- [>] moved\n"
+    );
+    ctx.verify_invariants();
+}
+
+#[test]
+fn doctor_command_repairs_journal_after_confirmation() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/15\n- [X] done\n\n# 2026/01/15\n- [ ] second chunk\n";
+    let mut ctx = TestContext::with_journal_content(date, content);
+
+    let doctor_index = caliber::registry::COMMANDS
+        .iter()
+        .position(|c| c.name == "doctor")
+        .unwrap();
+    ctx.press(KeyCode::Char('q'));
+    for _ in 0..doctor_index {
+        ctx.press(KeyCode::Down);
+    }
+    ctx.press(KeyCode::Enter);
+    assert_eq!(
+        ctx.read_journal(),
+        content,
+        "Repair should wait for confirmation"
+    );
+
+    ctx.press(KeyCode::Char('y'));
+    assert_eq!(
+        ctx.read_journal(),
+        "# 2026/01/15\n- [x] done\n- [ ] second chunk\n"
+    );
+    assert!(ctx.status_contains("Repaired 2 issue(s)"));
+    assert!(ctx.screen_contains("second chunk"));
+    ctx.verify_invariants();
+}
+
+#[test]
+fn nested_entries_keep_indentation_and_show_progress() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/15\n- [ ] Launch\n  - [x] Draft post\n  - [ ] Record demo\n\t- [ ] Tabbed step\n- Notes\n";
+    let mut ctx = TestContext::with_journal_content(date, content);
+
+    let parent = ctx.find_line("Launch").unwrap();
+    assert!(parent.contains("1/3"), "progress missing: {parent}");
+    let child = ctx.find_line("Record demo").unwrap();
+    assert!(
+        child.contains("  - [ ] Record demo"),
+        "child not indented: {child}"
+    );
+
+    // Toggling the last task rewrites the day without flattening it
+    ctx.press(KeyCode::Char('g'));
+    ctx.press(KeyCode::Char('j'));
+    ctx.press(KeyCode::Char('j'));
+    ctx.press(KeyCode::Char(' '));
+    let journal = ctx.read_journal();
+    assert!(journal.contains("\n  - [x] Draft post\n  - [x] Record demo\n  - [ ] Tabbed step\n"));
+    assert!(ctx.find_line("Launch").unwrap().contains("2/3"));
+    ctx.verify_invariants();
+
+    // Filter results nest beneath matching parents from the same day
+    ctx.press(KeyCode::Char('/'));
+    ctx.type_str("!tasks");
+    ctx.press(KeyCode::Enter);
+    let parent = ctx.find_line("Launch").unwrap();
+    let child = ctx.find_line("Tabbed step").unwrap();
+    let bullet_col = |line: &str| line[..line.find("[ ]").unwrap()].chars().count();
+    assert_eq!(bullet_col(&child), bullet_col(&parent) + 2);
+}
+
+#[test]
+fn indent_outdent_and_undo() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/15\n- [ ] Parent\n- [ ] Child\n  - [ ] Grandchild\n- [ ] Sibling\n";
+    let mut ctx = TestContext::with_journal_content(date, content);
+
+    ctx.press(KeyCode::Char('g'));
+    ctx.press(KeyCode::Char('j')); // On Child
+    ctx.press_with_modifiers(KeyCode::Char('l'), KeyModifiers::ALT);
+    assert!(
+        ctx.read_journal()
+            .contains("- [ ] Parent\n  - [ ] Child\n    - [ ] Grandchild\n- [ ] Sibling")
+    );
+
+    // Already nested under the entry above
+    ctx.press_with_modifiers(KeyCode::Char('l'), KeyModifiers::ALT);
+    assert!(
+        ctx.read_journal()
+            .contains("  - [ ] Child\n    - [ ] Grandchild")
+    );
+
+    // Outdenting brings the subtree back; top-level entries stay put
+    ctx.press_with_modifiers(KeyCode::Char('h'), KeyModifiers::ALT);
+    assert!(
+        ctx.read_journal()
+            .contains("\n- [ ] Child\n  - [ ] Grandchild")
+    );
+    ctx.press_with_modifiers(KeyCode::Left, KeyModifiers::ALT);
+    assert!(
+        ctx.read_journal()
+            .contains("\n- [ ] Child\n  - [ ] Grandchild")
+    );
+
+    ctx.press(KeyCode::Char('u'));
+    assert!(
+        ctx.read_journal()
+            .contains("  - [ ] Child\n    - [ ] Grandchild")
+    );
+    ctx.press(KeyCode::Char('u'));
+    assert!(ctx.read_journal().contains(content));
+    ctx.verify_invariants();
+}
+
+#[test]
+fn collapse_hides_sub_entries_and_reorder_moves_them() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/15\n- [ ] First\n- [ ] Second\n  - [ ] Second A\n  - [ ] Second B\n- [ ] Third\n";
+    let mut ctx = TestContext::with_journal_content(date, content);
+
+    ctx.press(KeyCode::Char('g'));
+    ctx.press(KeyCode::Char('j')); // On Second
+    ctx.press(KeyCode::Char('c'));
+    assert!(!ctx.screen_contains("Second A"));
+    assert!(ctx.screen_contains("0/2"));
+
+    // Moving down skips the hidden sub-entries and lands on Third
+    ctx.press(KeyCode::Char('j'));
+    assert!(ctx.find_line("Third").unwrap().contains('→'));
+    ctx.press(KeyCode::Char('k'));
+
+    // Reordering moves the parent together with its sub-entries
+    ctx.press(KeyCode::Char('r'));
+    ctx.press(KeyCode::Char('k'));
+    ctx.press(KeyCode::Enter);
+    assert!(
+        ctx.read_journal()
+            .contains("- [ ] Second\n  - [ ] Second A\n  - [ ] Second B\n- [ ] First\n- [ ] Third")
+    );
+
+    // Collapse state is never written to disk
+    ctx.press(KeyCode::Char('c'));
+    assert!(ctx.screen_contains("Second B"));
+    assert!(!ctx.read_journal().contains('▸'));
+    ctx.verify_invariants();
+}
+
+#[test]
+fn task_states_round_trip_cycle_and_filter() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content =
+        "# 2026/01/15\n- [/] Started\n- [x] Done\n- [-] Dropped\n- [>] Moved on\n- [ ] Open\n";
+    let mut ctx = TestContext::with_journal_content(date, content);
+
+    assert!(ctx.screen_contains("[/] Started"));
+    assert!(ctx.screen_contains("[-] Dropped"));
+    assert!(ctx.screen_contains("[>] Moved on"));
+
+    // Cycle the last task through its states, then undo
+    ctx.press(KeyCode::Char('-'));
+    assert!(ctx.read_journal().contains("- [/] Open"));
+    ctx.press(KeyCode::Char('-'));
+    ctx.press(KeyCode::Char('-'));
+    assert!(ctx.read_journal().contains("- [-] Open"));
+    ctx.press(KeyCode::Char('u'));
+    assert!(ctx.read_journal().contains("- [x] Open"));
+    ctx.verify_invariants();
+
+    // Toggling a cancelled task reopens it; toggling in-progress completes it
+    ctx.press(KeyCode::Char('k'));
+    ctx.press(KeyCode::Char('k'));
+    ctx.press(KeyCode::Char(' '));
+    assert!(ctx.read_journal().contains("- [ ] Dropped"));
+    ctx.press(KeyCode::Char('k'));
+    ctx.press(KeyCode::Char('k'));
+    ctx.press(KeyCode::Char(' '));
+    assert!(ctx.read_journal().contains("- [x] Started"));
+    ctx.verify_invariants();
+
+    // Hiding finished tasks also hides migrated ones
+    ctx.press(KeyCode::Char('z'));
+    assert!(!ctx.screen_contains("Moved on"));
+    assert!(ctx.screen_contains("Dropped"));
+    ctx.press(KeyCode::Char('z'));
+
+    ctx.press(KeyCode::Char('/'));
+    ctx.type_str("!migrated !cancelled");
+    ctx.press(KeyCode::Enter);
+    assert!(ctx.screen_contains("Moved on"));
+    assert!(!ctx.screen_contains("Started"));
+    assert!(!ctx.screen_contains("Dropped"));
+    ctx.verify_invariants();
+}
+
+#[test]
+fn tidy_groups_task_states() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/15\n- [ ] Open\n- [-] Dropped\n- [/] Started\n- [x] Done\n";
+    let mut ctx = TestContext::with_journal_content(date, content);
+
+    ctx.press(KeyCode::Char('s'));
+    assert!(
+        ctx.read_journal()
+            .contains("- [-] Dropped\n- [x] Done\n- [ ] Open\n- [/] Started\n")
+    );
+}
+
+#[test]
+fn due_dates_project_count_down_and_filter() {
+    let today = chrono::Local::now().date_naive();
+    let written = today - chrono::Days::new(3);
+    let fmt = |d: NaiveDate| d.format("%Y/%m/%d").to_string();
+    let content = format!(
+        "# {}\n- [ ] Ship it @due({})\n- [ ] Late report @due({})\n- [ ] File taxes @due({})\n- Plain note\n",
+        fmt(written),
+        fmt(today + chrono::Days::new(2)),
+        fmt(today - chrono::Days::new(2)),
+        fmt(today),
+    );
+
+    // Relative expressions count from the day the entry was written
+    let wednesday = NaiveDate::from_ymd_opt(2026, 1, 14).unwrap();
+    assert_eq!(
+        caliber::storage::extract_due_date("Send draft @due(fri)", wednesday),
+        NaiveDate::from_ymd_opt(2026, 1, 16)
+    );
+    assert_eq!(
+        caliber::storage::extract_due_date("Pay @due-1/20", wednesday),
+        NaiveDate::from_ymd_opt(2026, 1, 20)
+    );
+
+    let mut ctx = TestContext::with_journal_content(written, &content);
+    assert!(ctx.find_line("Ship it").unwrap().contains("due in 2d"));
+    assert!(ctx.find_line("Late report").unwrap().contains("overdue 2d"));
+
+    // On the due day the task is projected with a countdown
+    ctx.press(KeyCode::Char('t'));
+    let projected = ctx.find_line("File taxes").unwrap();
+    assert!(projected.contains("due today"));
+    assert!(!ctx.screen_contains("Ship it"));
+
+    // Completing the projection completes the source entry
+    ctx.press(KeyCode::Char(' '));
+    assert!(ctx.read_journal().contains("- [x] File taxes"));
+    assert!(!ctx.find_line("File taxes").unwrap().contains("due today"));
+    ctx.verify_invariants();
+
+    ctx.press(KeyCode::Char('/'));
+    ctx.type_str("!overdue");
+    ctx.press(KeyCode::Enter);
+    assert!(ctx.find_line("Late report").unwrap().contains("overdue 2d"));
+    assert!(!ctx.screen_contains("Ship it"));
+    assert!(!ctx.screen_contains("File taxes"));
+
+    ctx.press(KeyCode::Char('/'));
+    for _ in 0..="!overdue".len() {
+        ctx.press(KeyCode::Backspace);
+    }
+    ctx.type_str("due:..d7+");
+    ctx.press(KeyCode::Enter);
+    assert!(ctx.screen_contains("Ship it"));
+    assert!(ctx.screen_contains("File taxes"));
+    assert!(!ctx.screen_contains("Late report"));
+    assert!(!ctx.screen_contains("Plain note"));
+    ctx.verify_invariants();
+}
+
+#[test]
+fn recurrence_grammar_intervals_nth_weekdays_yearly_and_bounds() {
+    use caliber::storage::extract_recurring_pattern;
+
+    let d = |m, day| NaiveDate::from_ymd_opt(2026, m, day).unwrap();
+    // Written on Wednesday 2026/01/14
+    let written = d(1, 14);
+    let recurs = |content: &str, date| {
+        extract_recurring_pattern(content, written)
+            .unwrap_or_else(|| panic!("unparsed: {content}"))
+            .matches(date)
+    };
+
+    assert!(recurs("Water plants @every-3-days", d(1, 17)));
+    assert!(!recurs("Water plants @every-3-days", d(1, 18)));
+    assert!(!recurs("Water plants @every-3-days", d(1, 11)));
+
+    // Interval weeks keep the weekday of the day written
+    assert!(recurs("1:1 @every-2-weeks", d(1, 28)));
+    assert!(!recurs("1:1 @every-2-weeks", d(1, 21)));
+    assert!(recurs("Payroll @every-other-fri", d(1, 16)));
+    assert!(!recurs("Payroll @every-other-fri", d(1, 23)));
+    assert!(recurs("Payroll @every-other-fri", d(1, 30)));
+
+    assert!(recurs("Quarterly review @every-3-months", d(4, 14)));
+    assert!(!recurs("Quarterly review @every-3-months", d(2, 14)));
+
+    assert!(recurs("Book club @every-2nd-tue", d(1, 13)));
+    assert!(!recurs("Book club @every-2nd-tue", d(1, 20)));
+    assert!(recurs("Retro @every-last-fri", d(1, 30)));
+    assert!(!recurs("Retro @every-last-fri", d(1, 23)));
+
+    assert!(recurs("Birthday @every-mar-15", d(3, 15)));
+    assert!(!recurs("Birthday @every-mar-15", d(4, 15)));
+
+    let bounded = "Swim @every-mon@from-3/1@until-6/30";
+    assert!(!recurs(bounded, d(2, 23)));
+    assert!(recurs(bounded, d(3, 2)));
+    assert!(recurs(bounded, d(6, 29)));
+    assert!(!recurs(bounded, d(7, 6)));
+
+    assert!(extract_recurring_pattern("Nope @every-feb-30", written).is_none());
+    assert!(extract_recurring_pattern("Nope @every-0-days", written).is_none());
+
+    // Projected onto matching days in the daily view
+    let content = "# 2026/01/14\n- [ ] Payroll @every-other-fri\n";
+    let mut ctx = TestContext::with_journal_content(d(1, 30), content);
+    assert!(ctx.screen_contains("Payroll"));
+    ctx.press(KeyCode::Char('h'));
+    assert!(!ctx.screen_contains("Payroll"));
+}
+
+#[test]
+fn recurring_occurrences_can_be_skipped_and_moved() {
+    use caliber::storage::{
+        extract_recurring_pattern, recurs_on, restore_entry_meta, scan_recurring_in_range,
+        strip_entry_meta,
+    };
+
+    let d = |m, day| NaiveDate::from_ymd_opt(2026, m, day).unwrap();
+    // Written on Wednesday 2026/01/14, so it first projects onto Monday 01/19
+    let content = "# 2026/01/14\n- [ ] Standup @every-mon\n";
+    let mut ctx = TestContext::with_journal_content(d(1, 19), content);
+    assert!(ctx.screen_contains("Standup"));
+
+    ctx.press(KeyCode::Char('S'));
+    assert!(!ctx.screen_contains("Standup"));
+    assert!(
+        ctx.read_journal()
+            .contains("- [ ] Standup @every-mon <!-- skip: 2026-01-19 -->")
+    );
+    ctx.press(KeyCode::Char('u'));
+    assert!(ctx.screen_contains("Standup"));
+    assert!(!ctx.read_journal().contains("skip:"));
+
+    // Move just this occurrence to Wednesday
+    ctx.press(KeyCode::Char('M'));
+    ctx.type_str("1/21");
+    ctx.press(KeyCode::Enter);
+    assert!(!ctx.screen_contains("Standup"));
+    assert!(
+        ctx.read_journal()
+            .contains("<!-- moved: 2026-01-19>2026-01-21 -->")
+    );
+    ctx.press(KeyCode::Char('l'));
+    assert!(!ctx.screen_contains("Standup"));
+    ctx.press(KeyCode::Char('l'));
+    assert!(ctx.screen_contains("Standup"));
+    assert!(!ctx.screen_contains("moved:"));
+
+    // Completing the moved occurrence marks the day it now falls on
+    ctx.press(KeyCode::Char(' '));
+    assert!(ctx.read_journal().contains("<!-- done: 2026-01-21 -->"));
+    ctx.press(KeyCode::Char(' '));
+
+    // Skipping a moved occurrence skips its original date and drops the move
+    ctx.press(KeyCode::Char('S'));
+    let journal = ctx.read_journal();
+    assert!(journal.contains("- [ ] Standup @every-mon <!-- skip: 2026-01-19 -->\n"));
+    assert!(!journal.contains("moved:"));
+
+    // Later occurrences are untouched, including in the calendar scan
+    let source =
+        "Standup @every-mon <!-- skip: 2026-01-19 --> <!-- moved: 2026-01-26>2026-01-27 -->";
+    let recurrence = extract_recurring_pattern(source, d(1, 14)).unwrap();
+    let recurs = |date| recurs_on(source, &recurrence, d(1, 14), date);
+    assert!(!recurs(d(1, 19)));
+    assert!(!recurs(d(1, 26)));
+    assert!(recurs(d(1, 27)));
+    assert!(recurs(d(2, 2)));
+    let scanned = scan_recurring_in_range(
+        ctx.app.store.as_ref(),
+        d(1, 19),
+        d(1, 27),
+        &ctx.journal_path(),
+    )
+    .unwrap();
+    assert!(!scanned.contains(&d(1, 19)));
+    assert!(scanned.contains(&d(1, 26)));
+
+    // Editing keeps all occurrence metadata
+    assert_eq!(strip_entry_meta(source), "Standup @every-mon");
+    assert_eq!(
+        restore_entry_meta("Team standup @every-mon", source),
+        "Team standup @every-mon <!-- skip: 2026-01-19 --> <!-- moved: 2026-01-26>2026-01-27 -->"
+    );
+}
+
+#[test]
+fn habit_tracker_reports_streaks_and_completion() {
+    use caliber::app::SidebarType;
+    use caliber::storage::OccurrenceMark;
+
+    let today = chrono::Local::now().date_naive();
+    let ago = |days| today - chrono::Days::new(days);
+    let meta = |days: &[u64]| {
+        days.iter()
+            .map(|d| ago(*d).format("%Y-%m-%d").to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    // Missed 7 days ago, skipped 3 days ago, today still open
+    let content = format!(
+        "# {}\n- [ ] Stretch @every-day <!-- done: {} --> <!-- skip: {} -->\n",
+        ago(10).format("%Y/%m/%d"),
+        meta(&[9, 8, 6, 5, 4, 2, 1]),
+        meta(&[3]),
+    );
+    let mut ctx = TestContext::with_journal_content(today, &content);
+
+    let habits_index = caliber::registry::COMMANDS
+        .iter()
+        .position(|c| c.name == "habits")
+        .unwrap();
+    ctx.press(KeyCode::Char('q'));
+    for _ in 0..habits_index {
+        ctx.press(KeyCode::Down);
+    }
+    ctx.press(KeyCode::Enter);
+    assert!(ctx.app.active_sidebar() == Some(SidebarType::Habits));
+
+    ctx.app.ensure_habit_cache();
+    let habit = ctx.app.habit_cache.as_ref().unwrap()[0].clone();
+    assert_eq!(habit.content, "Stretch");
+    assert_eq!(habit.occurrences.len(), 10);
+    assert_eq!(habit.occurrences[2], (ago(7), OccurrenceMark::Missed));
+    assert_eq!(habit.occurrences[6], (ago(3), OccurrenceMark::Skipped));
+    assert_eq!(habit.occurrences[9], (today, OccurrenceMark::Pending));
+    assert_eq!(habit.current_streak(), 5);
+    assert_eq!(habit.best_streak(), 5);
+    assert_eq!(habit.completion_percent(), Some(87));
+    assert_eq!(habit.recent(3).len(), 3);
+
+    // Completing today's occurrence extends the streak
+    ctx.press(KeyCode::Char(' '));
+    assert!(ctx.app.habit_cache.is_none());
+    ctx.app.ensure_habit_cache();
+    let habit = &ctx.app.habit_cache.as_ref().unwrap()[0];
+    assert_eq!(habit.current_streak(), 6);
+    assert_eq!(habit.best_streak(), 6);
+}
+
+#[test]
+fn cross_journal_filter_writes_back_to_each_journal() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let project = "# 2026/01/15\n- [ ] Project task\n";
+    let mut ctx = TestContext::with_project_journal(
+        date,
+        project,
+        "# 2026/01/10\n- [ ] Pay rent\n- [ ] Call bank\n",
+    );
+
+    ctx.press(KeyCode::Char('/'));
+    ctx.type_str("@all !tasks");
+    ctx.press(KeyCode::Enter);
+    assert!(ctx.find_line("Pay rent").unwrap().contains("(hub 01/10)"));
+    assert!(
+        ctx.find_line("Project task")
+            .unwrap()
+            .contains("(project 01/15)")
+    );
+
+    ctx.press(KeyCode::Char('g'));
+    ctx.press(KeyCode::Char('j'));
+    ctx.press(KeyCode::Char(' '));
+    assert!(ctx.read_hub().contains("- [x] Call bank"));
+
+    ctx.press(KeyCode::Char('k'));
+    ctx.press(KeyCode::Char('i'));
+    ctx.type_str(" today");
+    ctx.press(KeyCode::Enter);
+    assert!(ctx.read_hub().contains("- [ ] Pay rent today"));
+
+    ctx.press(KeyCode::Char('d'));
+    assert!(!ctx.read_hub().contains("Pay rent"));
+    ctx.press(KeyCode::Char('u'));
+    assert!(
+        ctx.read_hub()
+            .contains("- [ ] Pay rent today\n- [x] Call bank")
+    );
+
+    // Moving needs the entry's own journal
+    ctx.press(KeyCode::Char('T'));
+    assert!(ctx.status_contains("Switch to hub"));
+    assert_eq!(ctx.read_journal(), project);
+
+    ctx.press(KeyCode::Char('/'));
+    for _ in 0.."@all !tasks".len() + 1 {
+        ctx.press(KeyCode::Backspace);
+    }
+    ctx.type_str("@project:missing");
+    ctx.press(KeyCode::Enter);
+    assert!(ctx.status_contains("Unknown project: missing"));
+    ctx.verify_invariants();
+}
+
+#[test]
+fn filter_results_sort_group_and_collapse() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/10\n- [ ] Old chore #home\n- [ ] Old report #work\n\n\
+                   # 2026/01/14\n- [ ] New report #work\n";
+    let mut config = caliber::config::Config::default();
+    config
+        .filters
+        .insert("work".to_string(), "#work sort:date-desc".to_string());
+    let mut ctx = TestContext::with_config_and_content(date, content, config);
+    let row = |screen: &[String], text: &str| {
+        screen
+            .iter()
+            .position(|l| l.contains(text))
+            .unwrap_or_else(|| panic!("{text} not on screen: {screen:?}"))
+    };
+
+    ctx.press(KeyCode::Char('/'));
+    ctx.type_str("!tasks group:day");
+    ctx.press(KeyCode::Enter);
+    let screen = ctx.render_current();
+    assert!(screen[0].contains("Sat 2026/01/10") && screen[0].contains("(2)"));
+    assert!(row(&screen, "Old report") < row(&screen, "Wed 2026/01/14"));
+    assert!(row(&screen, "Wed 2026/01/14") < row(&screen, "New report"));
+
+    // s rewrites the query with the next sort order
+    ctx.press(KeyCode::Char('s'));
+    assert!(ctx.status_contains("Sorted by date-desc"));
+    let screen = ctx.render_current();
+    assert!(row(&screen, "New report") < row(&screen, "Sat 2026/01/10"));
+    assert!(row(&screen, "Sat 2026/01/10") < row(&screen, "Old chore"));
+
+    // c folds the day under the cursor; its header takes the cursor
+    ctx.press(KeyCode::Char('g'));
+    ctx.press(KeyCode::Char('j'));
+    ctx.press(KeyCode::Char('c'));
+    assert!(!ctx.screen_contains("Old chore"));
+    ctx.press(KeyCode::Char(' '));
+    assert!(!ctx.read_journal().contains("[x]"));
+    ctx.press(KeyCode::Char('k'));
+    ctx.press(KeyCode::Char('j'));
+    ctx.press(KeyCode::Char('c'));
+    assert!(ctx.screen_contains("Old chore"));
+
+    ctx.press(KeyCode::Char('S'));
+    assert!(ctx.status_contains("Grouped by tag"));
+    let screen = ctx.render_current();
+    assert!(row(&screen, "#work (2)") < row(&screen, "#home (1)"));
+
+    // A saved filter brings its own order
+    ctx.app.quick_filter("$work").unwrap();
+    let screen = ctx.render_current();
+    assert!(row(&screen, "New report") < row(&screen, "Old report"));
+    ctx.verify_invariants();
+}
+
+#[test]
+fn record_dates_stamps_creation_and_completion() {
+    let date = chrono::Local::now().date_naive();
+    let config = caliber::config::Config {
+        record_dates: true,
+        ..Default::default()
+    };
+    let mut ctx = TestContext::with_config_and_content(date, "", config);
+
+    ctx.press(KeyCode::Enter);
+    ctx.type_str("Write report");
+    ctx.press(KeyCode::Enter);
+    ctx.press(KeyCode::Esc);
+    let today = date.format("%Y-%m-%d").to_string();
+    assert!(
+        ctx.read_journal()
+            .contains(&format!("Write report <!-- created: {today} -->"))
+    );
+
+    ctx.press(KeyCode::Char(' '));
+    assert!(
+        ctx.read_journal()
+            .contains(&format!("<!-- completed: {today} -->"))
+    );
+    assert!(!ctx.screen_contains("completed:"));
+
+    ctx.press(KeyCode::Char('/'));
+    ctx.type_str("done:d1..");
+    ctx.press(KeyCode::Enter);
+    assert!(ctx.screen_contains("Write report"));
+
+    // Reopening the task drops the completion date but keeps the creation date
+    ctx.press(KeyCode::Char(' '));
+    let journal = ctx.read_journal();
+    assert!(!journal.contains("completed:"));
+    assert!(journal.contains("created:"));
+    ctx.verify_invariants();
+}
+
+#[test]
+fn filter_highlights_search_matches() {
+    use ratatui::style::Modifier;
+
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/15\n- [ ] Code review for PROJ-7\n- [ ] Review budget\n";
+    let mut ctx = TestContext::with_journal_content(date, content);
+
+    ctx.press(KeyCode::Char('/'));
+    ctx.type_str(r#""code review" re:/PROJ-\d+/"#);
+    ctx.press(KeyCode::Enter);
+    assert!(ctx.screen_contains("Code review for PROJ-7"));
+    assert!(!ctx.screen_contains("Review budget"));
+
+    let lines = caliber::ui::build_filter_list(&ctx.app, 80).into_lines();
+    let highlighted: Vec<String> = lines
+        .iter()
+        .flat_map(|line| line.spans.iter())
+        .filter(|span| span.style.add_modifier.contains(Modifier::UNDERLINED))
+        .map(|span| span.content.to_string())
+        .collect();
+    assert_eq!(highlighted, ["Code review", "PROJ-7"]);
+    ctx.verify_invariants();
+}
+
+#[test]
+fn filter_heading_marks_invalid_terms() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let content = "# 2026/01/15\n- [ ] Ship release #work\n";
+    let mut ctx = TestContext::with_journal_content(date, content);
+
+    ctx.app.quick_filter("#work !bogus").unwrap();
+    let caliber::app::ViewMode::Filter(state) = &ctx.app.view else {
+        panic!("expected filter view");
+    };
+    assert_eq!(state.invalid_spans.len(), 1);
+    assert_eq!(state.invalid_spans[0], 6..12);
+
+    // Fixing the query clears the marks on refresh
+    ctx.app.quick_filter("#work").unwrap();
+    let caliber::app::ViewMode::Filter(state) = &ctx.app.view else {
+        panic!("expected filter view");
+    };
+    assert!(state.invalid_spans.is_empty());
+    ctx.verify_invariants();
+}